@@ -0,0 +1,279 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde_json;
+use ureq;
+
+use github::{encode, IssueQuery, PullRequestFilter, StateFilter};
+use issue::{ExtraFields, Issue};
+use progress;
+use source::{get_json, Source};
+
+const API: &str = "https://api.bitbucket.org/2.0";
+
+// Maximum number of issues returned per page by the API
+const PER_PAGE: usize = 50;
+
+const OPEN_STATES: &[&str] = &["new", "open", "on hold", "submitted"];
+
+// Bitbucket priorities, from the most urgent, exported as the 'P0' to 'P4' labels
+const PRIORITIES: &[&str] = &["blocker", "critical", "major", "minor", "trivial"];
+
+#[derive(Debug, Deserialize)]
+struct Named {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    // Not set for some accounts
+    nickname: Option<String>,
+    display_name: String,
+}
+
+impl User {
+    fn login(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.display_name)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Content {
+    raw: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    href: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Links {
+    html: Link,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketIssue {
+    id: u32,
+    title: String,
+    content: Option<Content>,
+    // new, open, on hold, submitted, resolved, duplicate, invalid, wontfix or closed
+    state: String,
+    // bug, enhancement, proposal or task
+    kind: String,
+    // blocker, critical, major, minor or trivial
+    priority: String,
+    reporter: Option<User>,
+    assignee: Option<User>,
+    milestone: Option<Named>,
+    component: Option<Named>,
+    #[serde(default)]
+    votes: u32,
+    created_on: DateTime<Utc>,
+    updated_on: DateTime<Utc>,
+    links: Links,
+}
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    values: Vec<BitbucketIssue>,
+    // URL of the next page
+    next: Option<String>,
+}
+
+impl BitbucketIssue {
+    // Same fields as the github REST API, so the issues of all the sources are
+    // handled the same way. The state of the issue is its board column, the kind
+    // its type.
+    fn into_issue(self, repo: &str) -> Issue {
+        let user = |u: &User| serde_json::json!({ "login": u.login() });
+        let closed = !OPEN_STATES.contains(&self.state.as_str());
+
+        let mut labels = Vec::new();
+        if let Some(p) = PRIORITIES.iter().position(|p| *p == self.priority) {
+            labels.push(format!("P{}", p));
+        }
+        if let Some(ref component) = self.component {
+            labels.push(component.name.clone());
+        }
+        let state_reason = match self.state.as_str() {
+            "resolved" | "closed" => Some("completed"),
+            "duplicate" => Some("duplicate"),
+            "invalid" | "wontfix" => Some("not_planned"),
+            _ => None,
+        };
+
+        let json = serde_json::json!({
+            "title": self.title,
+            "body": self.content.and_then(|c| c.raw),
+            "html_url": self.links.html.href,
+            "number": self.id,
+            "repository_url": format!("https://bitbucket.org/{}", repo),
+            "source": "bitbucket",
+            "pull_request": null,
+            "user": self.reporter.as_ref().map(user),
+            "assignee": self.assignee.as_ref().map(user),
+            "assignees": self.assignee.as_ref().map(user).into_iter().collect::<Vec<_>>(),
+            "milestone": self.milestone.map(|m| serde_json::json!({ "title": m.name })),
+            "labels": labels.iter().map(|l| serde_json::json!({ "name": l })).collect::<Vec<_>>(),
+            "state": if closed { "closed" } else { "open" },
+            "state_reason": state_reason,
+            "author_association": null,
+            "reactions": { "+1": self.votes },
+            "created_at": self.created_on,
+            // The API does not tell when the issues have been closed
+            "closed_at": if closed { Some(self.updated_on) } else { None },
+            "updated_at": self.updated_on,
+        });
+
+        let mut issue: Issue =
+            serde_json::from_value(json).expect("Failed to convert Bitbucket issue");
+        issue.component = Some(repo.to_string());
+        issue.extra_fields = Some(ExtraFields {
+            board_column: Some(self.state),
+            issue_type: Some(self.kind),
+            ..Default::default()
+        });
+        issue
+    }
+}
+
+// Issue trackers of Bitbucket Cloud repositories, named 'workspace/repo'
+pub struct Bitbucket {
+    pub user: String,
+    // App password or API token
+    pub token: String,
+    pub repos: Vec<String>,
+}
+
+impl Bitbucket {
+    // Query of the issues API matching the state of the query
+    fn filter(query: &IssueQuery) -> Option<String> {
+        let states = |negate: bool| {
+            OPEN_STATES
+                .iter()
+                .map(|s| format!("state {} \"{}\"", if negate { "!=" } else { "=" }, s))
+                .collect::<Vec<String>>()
+        };
+
+        match query.state {
+            StateFilter::Open => Some(states(false).join(" OR ")),
+            StateFilter::Closed => Some(states(true).join(" AND ")),
+            StateFilter::All => None,
+        }
+    }
+
+    fn get_page(&self, url: &str) -> Option<Page> {
+        let credentials = STANDARD.encode(format!("{}:{}", self.user, self.token));
+        get_json(ureq::get(url).set("Authorization", &format!("Basic {}", credentials)))
+    }
+}
+
+impl Source for Bitbucket {
+    // Bitbucket pull requests are not issues, so they are never fetched
+    fn fetch(
+        &self,
+        query: &IssueQuery,
+        limit: Option<usize>,
+        _pull_requests: PullRequestFilter,
+        quiet: bool,
+    ) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        let filter = Bitbucket::filter(query)
+            .map(|q| format!("&q={}", encode(&q)))
+            .unwrap_or_default();
+        let progress = progress::bar(self.repos.len(), "repositories", quiet);
+
+        for repo in self.repos.iter() {
+            progress.set_message(format!("{} ({} issues)", repo, issues.len()));
+            progress.inc(1);
+
+            let limit = limit.unwrap_or(usize::MAX);
+            let mut count = 0;
+            let mut next = Some(format!(
+                "{}/repositories/{}/issues?pagelen={}{}",
+                API, repo, PER_PAGE, filter
+            ));
+
+            while let Some(url) = next {
+                let page = self.get_page(&url).expect("failed to get Bitbucket issues");
+
+                for bitbucket_issue in page.values.into_iter().take(limit - count) {
+                    issues.push(bitbucket_issue.into_issue(repo));
+                    count += 1;
+                }
+                next = if count < limit { page.next } else { None };
+            }
+        }
+
+        progress.finish_with_message(format!("{} Bitbucket issues collected", issues.len()));
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use issue::IssueStateJson;
+
+    fn bitbucket_issue(state: &str) -> BitbucketIssue {
+        serde_json::from_value(serde_json::json!({
+            "id": 4,
+            "title": "Crash on startup",
+            "content": { "raw": "Steps" },
+            "state": state,
+            "kind": "bug",
+            "priority": "critical",
+            "reporter": { "nickname": null, "display_name": "Alice" },
+            "assignee": { "nickname": "bob", "display_name": "Bob" },
+            "milestone": { "name": "1.0" },
+            "component": { "name": "core" },
+            "created_on": "2024-01-01T00:00:00Z",
+            "updated_on": "2024-01-05T00:00:00Z",
+            "links": { "html": { "href": "https://bitbucket.org/ws/repo/issues/4" } },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn converted_issues() {
+        let issue = bitbucket_issue("on hold").into_issue("ws/repo");
+
+        assert_eq!(issue.state, IssueStateJson::Open);
+        assert_eq!(issue.closed_at, None);
+        assert_eq!(issue.repository_url, "https://bitbucket.org/ws/repo");
+        assert_eq!(issue.component.as_deref(), Some("ws/repo"));
+        // Accounts without a nickname go by their display name
+        assert_eq!(issue.user.unwrap().login, "Alice");
+        assert_eq!(issue.assignees[0].login, "bob");
+        let labels: Vec<String> = issue.labels.unwrap().into_iter().map(|l| l.name).collect();
+        assert_eq!(labels, vec!["P1", "core"]);
+        let extra_fields = issue.extra_fields.unwrap();
+        assert_eq!(extra_fields.board_column.as_deref(), Some("on hold"));
+        assert_eq!(extra_fields.issue_type.as_deref(), Some("bug"));
+
+        let issue = bitbucket_issue("wontfix").into_issue("ws/repo");
+        assert_eq!(issue.state, IssueStateJson::Closed);
+        assert_eq!(issue.state_reason.as_deref(), Some("not_planned"));
+        assert_eq!(issue.closed_at, Some(issue.updated_at));
+    }
+
+    #[test]
+    fn state_filters() {
+        let query = |state| IssueQuery {
+            state,
+            ..IssueQuery::default()
+        };
+
+        assert_eq!(
+            Bitbucket::filter(&query(StateFilter::Open)).unwrap(),
+            "state = \"new\" OR state = \"open\" OR state = \"on hold\" OR state = \"submitted\""
+        );
+        assert_eq!(
+            Bitbucket::filter(&query(StateFilter::Closed)).unwrap(),
+            "state != \"new\" AND state != \"open\" AND state != \"on hold\" AND state != \"submitted\""
+        );
+        assert_eq!(Bitbucket::filter(&query(StateFilter::All)), None);
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use std::collections::HashSet;
+
+use config::CalendarConfig;
+
+// Working days, to count ages and SLAs in business days
+#[derive(Debug, Clone)]
+pub struct Calendar {
+    weekend: Vec<Weekday>,
+    holidays: HashSet<NaiveDate>,
+}
+
+impl Calendar {
+    pub fn from_config(config: &CalendarConfig) -> Result<Calendar, String> {
+        let weekend = config
+            .weekend
+            .iter()
+            .map(|d| {
+                d.parse::<Weekday>()
+                    .map_err(|_| format!("Invalid weekend day '{}'", d))
+            })
+            .collect::<Result<Vec<Weekday>, String>>()?;
+        let holidays = config
+            .holidays
+            .iter()
+            .map(|d| {
+                NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                    .map_err(|_| format!("Invalid holiday '{}', expected yyyy-mm-dd", d))
+            })
+            .collect::<Result<HashSet<NaiveDate>, String>>()?;
+
+        Ok(Calendar { weekend, holidays })
+    }
+
+    fn is_business_day(&self, day: NaiveDate) -> bool {
+        !self.weekend.contains(&day.weekday()) && !self.holidays.contains(&day)
+    }
+
+    // Business days after 'from' up to 'to' included
+    pub fn business_days(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        from.iter_days()
+            .skip(1)
+            .take_while(|d| *d <= to)
+            .filter(|d| self.is_business_day(*d))
+            .count() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn business_days() {
+        let calendar = Calendar::from_config(&CalendarConfig::default()).unwrap();
+
+        // From a Friday to the next Monday
+        assert_eq!(
+            calendar.business_days(day("2024-01-05"), day("2024-01-08")),
+            1
+        );
+        assert_eq!(
+            calendar.business_days(day("2024-01-01"), day("2024-01-15")),
+            10
+        );
+        assert_eq!(
+            calendar.business_days(day("2024-01-05"), day("2024-01-05")),
+            0
+        );
+        assert_eq!(
+            calendar.business_days(day("2024-01-08"), day("2024-01-05")),
+            0
+        );
+
+        let calendar = Calendar::from_config(&CalendarConfig {
+            weekend: vec!["friday".to_string(), "saturday".to_string()],
+            holidays: vec!["2024-01-08".to_string()],
+        })
+        .unwrap();
+        assert_eq!(
+            calendar.business_days(day("2024-01-04"), day("2024-01-08")),
+            1
+        );
+    }
+
+    #[test]
+    fn invalid_config() {
+        let config = CalendarConfig {
+            weekend: vec!["caturday".to_string()],
+            holidays: Vec::new(),
+        };
+        assert_eq!(
+            Calendar::from_config(&config).err(),
+            Some("Invalid weekend day 'caturday'".to_string())
+        );
+
+        let config = CalendarConfig {
+            weekend: Vec::new(),
+            holidays: vec!["01/08/2024".to_string()],
+        };
+        assert_eq!(
+            Calendar::from_config(&config).err(),
+            Some("Invalid holiday '01/08/2024', expected yyyy-mm-dd".to_string())
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use reqwest::blocking::{self, RequestBuilder};
+use reqwest::header::{HeaderValue, ACCEPT, AUTHORIZATION};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json;
+
+use std::io;
+
+pub use reqwest::header::HeaderMap;
+pub use reqwest::{Error, StatusCode};
+
+// Root of the REST and GraphQL APIs
+const API_URL: &str = "https://api.github.com/";
+
+// Headers, status and body of a response. The body is None if the request failed.
+pub type Response<T> = (HeaderMap, StatusCode, Option<T>);
+
+// Client of the github API, shared by all the requests of a run, including the
+// ones sent at the same time from different threads. They all go through the
+// connection pool of a single runtime, concurrent requests being multiplexed
+// over the same HTTP/2 connection instead of each opening its own.
+#[derive(Debug, Clone)]
+pub struct Github {
+    http: blocking::Client,
+}
+
+impl Github {
+    pub fn new<T: ToString>(token: T) -> io::Result<Github> {
+        let mut auth = HeaderValue::from_str(&format!("token {}", token.to_string()))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        auth.set_sensitive(true);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, auth);
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json"),
+        );
+
+        let http = blocking::Client::builder()
+            .user_agent(concat!("github-issues/", env!("CARGO_PKG_VERSION")))
+            .default_headers(headers)
+            .build()
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+
+        Ok(Github { http })
+    }
+
+    // 'endpoint' is relative to the root of the API, such as 'repos/owner/repo'
+    pub fn get<T: DeserializeOwned>(&self, endpoint: &str) -> Result<Response<T>, Error> {
+        send(self.http.get(format!("{}{}", API_URL, endpoint)))
+    }
+
+    pub fn post<B: Serialize, T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        body: &B,
+    ) -> Result<Response<T>, Error> {
+        send(
+            self.http
+                .post(format!("{}{}", API_URL, endpoint))
+                .json(body),
+        )
+    }
+}
+
+fn send<T: DeserializeOwned>(request: RequestBuilder) -> Result<Response<T>, Error> {
+    let response = request.send()?;
+    let headers = response.headers().clone();
+    let status = response.status();
+    let body = response.bytes()?;
+
+    let json = if status.is_success() {
+        match serde_json::from_slice(&body) {
+            Ok(json) => Some(json),
+            Err(e) => {
+                error!("Invalid response: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    Ok((headers, status, json))
+}
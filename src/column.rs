@@ -0,0 +1,353 @@
+use std::fmt;
+use std::str::FromStr;
+
+use issue::IssueCSV;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Component,
+    Id,
+    Title,
+    State,
+    Assignee,
+    Milestone,
+    Priority,
+    CreatedAt,
+    ClosedAt,
+    Url,
+    UpdatedAt,
+    LastComment,
+    Score,
+    UrgentKeywords,
+    Draft,
+    BaseBranch,
+    HeadBranch,
+    RequestedReviewers,
+    ReviewStatus,
+    MilestoneChanges,
+    OriginalMilestone,
+    MilestoneDue,
+    Overdue,
+    Reactions,
+    LinkedPrs,
+    Projects,
+    BoardColumn,
+    Status,
+    ProjectFields,
+    Type,
+    Parent,
+    ChildrenProgress,
+    Severity,
+    Estimate,
+    AgeDays,
+    Stale,
+    OpenedAgo,
+    ClosedAgo,
+    UpdatedAgo,
+    Comments,
+    FirstResponseHours,
+    Body,
+    Progress,
+    Blocks,
+    BlockedBy,
+    Closes,
+    Labels,
+    Votes,
+    Reporter,
+    External,
+    FirstSeen,
+    LastSeen,
+}
+
+// Columns exported by default, in order
+pub const DEFAULT_COLUMNS: &[Column] = &[
+    Column::Component,
+    Column::Id,
+    Column::Title,
+    Column::State,
+    Column::Assignee,
+    Column::Milestone,
+    Column::Priority,
+    Column::CreatedAt,
+    Column::ClosedAt,
+    Column::Url,
+    Column::UpdatedAt,
+    Column::LastComment,
+    Column::Score,
+];
+
+// Columns added to the default ones when exporting pull requests
+pub const PULL_REQUEST_COLUMNS: &[Column] = &[
+    Column::Draft,
+    Column::BaseBranch,
+    Column::HeadBranch,
+    Column::RequestedReviewers,
+    Column::ReviewStatus,
+];
+
+pub const ALL_COLUMNS: &[Column] = &[
+    Column::Component,
+    Column::Id,
+    Column::Title,
+    Column::State,
+    Column::Assignee,
+    Column::Milestone,
+    Column::Priority,
+    Column::CreatedAt,
+    Column::ClosedAt,
+    Column::Url,
+    Column::UpdatedAt,
+    Column::LastComment,
+    Column::Score,
+    Column::UrgentKeywords,
+    Column::Draft,
+    Column::BaseBranch,
+    Column::HeadBranch,
+    Column::RequestedReviewers,
+    Column::ReviewStatus,
+    Column::MilestoneChanges,
+    Column::OriginalMilestone,
+    Column::MilestoneDue,
+    Column::Overdue,
+    Column::Reactions,
+    Column::LinkedPrs,
+    Column::Projects,
+    Column::BoardColumn,
+    Column::Status,
+    Column::ProjectFields,
+    Column::Type,
+    Column::Parent,
+    Column::ChildrenProgress,
+    Column::Severity,
+    Column::Estimate,
+    Column::AgeDays,
+    Column::Stale,
+    Column::OpenedAgo,
+    Column::ClosedAgo,
+    Column::UpdatedAgo,
+    Column::Comments,
+    Column::FirstResponseHours,
+    Column::Body,
+    Column::Progress,
+    Column::Blocks,
+    Column::BlockedBy,
+    Column::Closes,
+    Column::Labels,
+    Column::Votes,
+    Column::Reporter,
+    Column::External,
+    Column::FirstSeen,
+    Column::LastSeen,
+];
+
+// Columns fetched in batches from the GraphQL API
+pub const GRAPHQL_COLUMNS: &[Column] = &[
+    Column::Reactions,
+    Column::LinkedPrs,
+    Column::Projects,
+    Column::BoardColumn,
+    Column::Status,
+    Column::ProjectFields,
+    Column::Type,
+    Column::Parent,
+    Column::ChildrenProgress,
+];
+
+impl Column {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Column::Component => "component",
+            Column::Id => "id",
+            Column::Title => "title",
+            Column::State => "state",
+            Column::Assignee => "assignee",
+            Column::Milestone => "milestone",
+            Column::Priority => "priority",
+            Column::CreatedAt => "created_at",
+            Column::ClosedAt => "closed_at",
+            Column::Url => "url",
+            Column::UpdatedAt => "updated_at",
+            Column::LastComment => "last_comment",
+            Column::Score => "score",
+            Column::UrgentKeywords => "urgent_keywords",
+            Column::Draft => "draft",
+            Column::BaseBranch => "base_branch",
+            Column::HeadBranch => "head_branch",
+            Column::RequestedReviewers => "requested_reviewers",
+            Column::ReviewStatus => "review_status",
+            Column::MilestoneChanges => "milestone_changes",
+            Column::OriginalMilestone => "original_milestone",
+            Column::MilestoneDue => "milestone_due",
+            Column::Overdue => "overdue",
+            Column::Reactions => "reactions",
+            Column::LinkedPrs => "linked_prs",
+            Column::Projects => "projects",
+            Column::BoardColumn => "board_column",
+            Column::Status => "status",
+            Column::ProjectFields => "project_fields",
+            Column::Type => "type",
+            Column::Parent => "parent",
+            Column::ChildrenProgress => "children_progress",
+            Column::Severity => "severity",
+            Column::Estimate => "estimate",
+            Column::AgeDays => "age_days",
+            Column::Stale => "stale",
+            Column::OpenedAgo => "opened_ago",
+            Column::ClosedAgo => "closed_ago",
+            Column::UpdatedAgo => "updated_ago",
+            Column::Comments => "comments",
+            Column::FirstResponseHours => "first_response_hours",
+            Column::Body => "body",
+            Column::Progress => "progress",
+            Column::Blocks => "blocks",
+            Column::BlockedBy => "blocked_by",
+            Column::Closes => "closes",
+            Column::Labels => "labels",
+            Column::Votes => "votes",
+            Column::Reporter => "reporter",
+            Column::External => "external",
+            Column::FirstSeen => "first_seen",
+            Column::LastSeen => "last_seen",
+        }
+    }
+}
+
+impl Column {
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            *self,
+            Column::Priority
+                | Column::Score
+                | Column::MilestoneChanges
+                | Column::Reactions
+                | Column::Estimate
+                | Column::AgeDays
+                | Column::Comments
+                | Column::Votes
+                | Column::FirstResponseHours
+        )
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ALL_COLUMNS
+            .iter()
+            .find(|c| c.name() == s)
+            .cloned()
+            .ok_or_else(|| format!("unknown column '{}'", s))
+    }
+}
+
+impl<'a> IssueCSV<'a> {
+    // Textual value of the given column
+    pub fn field(&self, column: Column) -> String {
+        match column {
+            Column::Component => self.component.clone(),
+            Column::Id => self.id.clone(),
+            Column::Title => self.title.to_string(),
+            Column::State => self.state.clone(),
+            Column::Assignee => self.assignee.clone().unwrap_or_default(),
+            Column::Milestone => self.milestone.unwrap_or("").to_string(),
+            Column::Priority => self.priority.map(|p| p.to_string()).unwrap_or_default(),
+            Column::CreatedAt => self.created_at.clone(),
+            Column::ClosedAt => self.closed_at.clone().unwrap_or_default(),
+            Column::Url => self.url.clone(),
+            Column::UpdatedAt => self.updated_at.clone(),
+            Column::LastComment => self.last_comment.clone(),
+            Column::Score => self.score.map(|s| s.to_string()).unwrap_or_default(),
+            Column::UrgentKeywords => self.urgent_keywords.join(", "),
+            Column::Draft => self.draft.map(|d| d.to_string()).unwrap_or_default(),
+            Column::BaseBranch => self.base_branch.unwrap_or("").to_string(),
+            Column::HeadBranch => self.head_branch.unwrap_or("").to_string(),
+            Column::RequestedReviewers => self.requested_reviewers.join(", "),
+            Column::ReviewStatus => self.review_status.unwrap_or("").to_string(),
+            Column::MilestoneChanges => self
+                .milestone_changes
+                .map(|c| c.to_string())
+                .unwrap_or_default(),
+            Column::OriginalMilestone => self.original_milestone.clone().unwrap_or_default(),
+            Column::MilestoneDue => self.milestone_due.clone().unwrap_or_default(),
+            Column::Overdue => self.overdue.to_string(),
+            Column::Reactions => self.reactions.map(|r| r.to_string()).unwrap_or_default(),
+            Column::LinkedPrs => self.linked_prs.join(", "),
+            Column::Projects => self.projects.join(", "),
+            Column::BoardColumn => self.board_column.unwrap_or("").to_string(),
+            Column::Status => self.status.unwrap_or("").to_string(),
+            Column::ProjectFields => self.project_fields.clone(),
+            Column::Type => self.issue_type.unwrap_or("").to_string(),
+            Column::Parent => self.parent.unwrap_or("").to_string(),
+            Column::ChildrenProgress => self.children_progress.clone().unwrap_or_default(),
+            Column::Severity => self.severity.unwrap_or("").to_string(),
+            Column::Estimate => self.estimate.map(|e| e.to_string()).unwrap_or_default(),
+            Column::AgeDays => self.age_days.map(|a| a.to_string()).unwrap_or_default(),
+            Column::Stale => self.stale.map(|s| s.to_string()).unwrap_or_default(),
+            Column::OpenedAgo => self.opened_ago.clone(),
+            Column::ClosedAgo => self.closed_ago.clone().unwrap_or_default(),
+            Column::UpdatedAgo => self.updated_ago.clone(),
+            Column::Comments => self.comments.to_string(),
+            Column::FirstResponseHours => self
+                .first_response_hours
+                .map(|h| format!("{:.1}", h))
+                .unwrap_or_default(),
+            Column::Body => self.body.clone().unwrap_or_default(),
+            Column::Progress => self.progress.clone().unwrap_or_default(),
+            Column::Blocks => self.blocks.join(", "),
+            Column::BlockedBy => self.blocked_by.join(", "),
+            Column::Closes => self.closes.join(", "),
+            Column::Labels => self.labels.clone(),
+            Column::Votes => self.votes.to_string(),
+            Column::Reporter => self.reporter.unwrap_or("").to_string(),
+            Column::External => self.external.map(|e| e.to_string()).unwrap_or_default(),
+            Column::FirstSeen => self.first_seen.clone().unwrap_or_default(),
+            Column::LastSeen => self.last_seen.clone().unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use client::Github;
+    use fixtures::issue;
+    use output::Options;
+
+    #[test]
+    fn names() {
+        for column in ALL_COLUMNS {
+            assert_eq!(column.name().parse::<Column>(), Ok(*column));
+        }
+        assert_eq!(
+            "assignees".parse::<Column>(),
+            Err("unknown column 'assignees'".to_string())
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let mut issue = issue(1, serde_json::json!({ "title": "Crash" }));
+        issue.estimate = Some(2.5);
+        let client = Github::new("token").unwrap();
+        let row = issue.csv(&client, &Options::default());
+
+        assert_eq!(row.field(Column::Id), "#1");
+        assert_eq!(row.field(Column::Title), "Crash");
+        assert_eq!(row.field(Column::State), "open");
+        assert_eq!(row.field(Column::Estimate), "2.5");
+        assert_eq!(row.field(Column::Comments), "0");
+        // Unset values are empty
+        assert_eq!(row.field(Column::Priority), "");
+        assert_eq!(row.field(Column::ClosedAt), "");
+    }
+}
@@ -0,0 +1,217 @@
+use chrono::{DateTime, Utc};
+use serde_json;
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use client::Github;
+use dirs::long_path;
+use issue::{Comment, Issue};
+use progress;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommentsFormat {
+    Json,
+    Markdown,
+}
+
+impl CommentsFormat {
+    fn extension(&self) -> &'static str {
+        match *self {
+            CommentsFormat::Json => "json",
+            CommentsFormat::Markdown => "md",
+        }
+    }
+}
+
+impl FromStr for CommentsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(CommentsFormat::Json),
+            "markdown" | "md" => Ok(CommentsFormat::Markdown),
+            _ => Err(format!("unknown comments format '{}'", s)),
+        }
+    }
+}
+
+// Issue and its discussion, as archived
+#[derive(Debug, Serialize)]
+struct Thread<'a> {
+    url: &'a str,
+    number: u32,
+    title: &'a str,
+    reporter: Option<&'a str>,
+    created_at: DateTime<Utc>,
+    body: &'a str,
+    comments: &'a [Comment],
+}
+
+fn write_markdown<W: Write>(mut wtr: W, thread: &Thread) -> io::Result<()> {
+    let author = |login: Option<&str>| login.unwrap_or("ghost").to_string();
+
+    writeln!(wtr, "# {}", thread.title)?;
+    writeln!(wtr)?;
+    writeln!(wtr, "{}", thread.url)?;
+    writeln!(wtr)?;
+    writeln!(
+        wtr,
+        "**{}** opened on {}",
+        author(thread.reporter),
+        thread.created_at.format("%Y-%m-%d %H:%M UTC")
+    )?;
+    writeln!(wtr)?;
+    writeln!(wtr, "{}", thread.body)?;
+
+    for comment in thread.comments.iter() {
+        writeln!(wtr)?;
+        writeln!(wtr, "---")?;
+        writeln!(wtr)?;
+        let date = comment
+            .created_at
+            .map(|d| format!(" on {}", d.format("%Y-%m-%d %H:%M UTC")))
+            .unwrap_or_default();
+        writeln!(
+            wtr,
+            "**{}** commented{}",
+            author(comment.user.as_ref().map(|u| u.login.as_str())),
+            date
+        )?;
+        writeln!(wtr)?;
+        writeln!(wtr, "{}", comment.body)?;
+    }
+
+    Ok(())
+}
+
+// Download the comments of each issue to its own file of 'dir', such as
+// 'repo-42.json', returning the number of files written
+pub fn export(
+    client: &Github,
+    issues: &[Issue],
+    dir: &Path,
+    format: CommentsFormat,
+    quiet: bool,
+) -> io::Result<usize> {
+    fs::create_dir_all(long_path(dir))?;
+
+    let progress = progress::bar(issues.len(), "issues", quiet);
+    progress.set_message("Downloading comments");
+
+    let mut written = 0;
+    for issue in issues.iter() {
+        progress.inc(1);
+        let comments = match issue.fetch_comments(client) {
+            Some(comments) => comments,
+            None => {
+                warn!("Failed to download the comments of {}", issue.html_url);
+                continue;
+            }
+        };
+        let thread = Thread {
+            url: &issue.html_url,
+            number: issue.number,
+            title: &issue.title,
+            reporter: issue.get_reporter(),
+            created_at: issue.created_at,
+            body: issue.body.as_deref().unwrap_or(""),
+            comments: &comments,
+        };
+
+        let name = format!(
+            "{}-{}.{}",
+            issue.get_repo(),
+            issue.number,
+            format.extension()
+        );
+        let mut wtr = BufWriter::new(File::create(long_path(&dir.join(name)))?);
+        match format {
+            CommentsFormat::Json => {
+                serde_json::to_writer_pretty(&mut wtr, &thread)?;
+                writeln!(wtr)?;
+            }
+            CommentsFormat::Markdown => write_markdown(&mut wtr, &thread)?,
+        }
+        wtr.flush()?;
+        written += 1;
+    }
+    progress.finish_and_clear();
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use issue::Assignee;
+
+    #[test]
+    fn formats() {
+        assert_eq!("md".parse::<CommentsFormat>(), Ok(CommentsFormat::Markdown));
+        assert_eq!("json".parse::<CommentsFormat>(), Ok(CommentsFormat::Json));
+        assert_eq!(
+            "html".parse::<CommentsFormat>(),
+            Err("unknown comments format 'html'".to_string())
+        );
+    }
+
+    #[test]
+    fn markdown_threads() {
+        let comments = vec![
+            Comment {
+                body: "Same here".to_string(),
+                user: Some(Assignee {
+                    login: "bob".to_string(),
+                }),
+                author_association: None,
+                created_at: Some("2024-01-02T08:30:00Z".parse().unwrap()),
+            },
+            // Deleted account
+            Comment {
+                body: "Fixed?".to_string(),
+                user: None,
+                author_association: None,
+                created_at: None,
+            },
+        ];
+        let thread = Thread {
+            url: "https://github.com/owner/repo/issues/1",
+            number: 1,
+            title: "Crash",
+            reporter: Some("alice"),
+            created_at: "2024-01-01T10:00:00Z".parse().unwrap(),
+            body: "Steps",
+            comments: &comments,
+        };
+
+        let mut output = Vec::new();
+        write_markdown(&mut output, &thread).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Crash
+
+https://github.com/owner/repo/issues/1
+
+**alice** opened on 2024-01-01 10:00 UTC
+
+Steps
+
+---
+
+**bob** commented on 2024-01-02 08:30 UTC
+
+Same here
+
+---
+
+**ghost** commented
+
+Fixed?
+"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use toml;
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use issue::IssueState;
+
+// Issues matching 'filter' are exported to the '<name>' output
+#[derive(Debug, Deserialize)]
+pub struct Route {
+    pub name: String,
+    pub filter: String,
+}
+
+// Closed issues having any of 'labels' are listed in the '<title>' section of the
+// changelog
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChangelogSection {
+    pub title: String,
+    pub labels: Vec<String>,
+}
+
+// Server sending the reports by email, over STARTTLS
+#[derive(Debug, Clone, Deserialize)]
+pub struct Smtp {
+    pub host: String,
+    // 587 by default
+    pub port: Option<u16>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    // 'Issues <issues@example.com>'
+    pub from: String,
+}
+
+// Days not counted by --business-days
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    // 'saturday', 'sunday'...
+    pub weekend: Vec<String>,
+    // 'yyyy-mm-dd' dates
+    pub holidays: Vec<String>,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> CalendarConfig {
+        CalendarConfig {
+            weekend: vec!["saturday".to_string(), "sunday".to_string()],
+            holidays: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Config {
+    // Names of the transforms to apply, in order
+    pub transforms: Vec<String>,
+    // 'map-labels': old label name -> new label name
+    pub map_labels: HashMap<String, String>,
+    // Prefix of the labels setting the component, such as 'area/'
+    pub component_label: Option<String>,
+    // Component name -> name it is exported as, several components being
+    // merged if they have the same new name
+    pub components: HashMap<String, String>,
+    // Label name -> variants of it used by some repositories, always applied first
+    pub normalize_labels: HashMap<String, Vec<String>>,
+    // 'compute-score': label name -> weight added to the score
+    pub compute_score: HashMap<String, f64>,
+    // 'redact': regular expressions whose matches are hidden from titles
+    pub redact: Vec<String>,
+    // 'urgent-keywords': keywords looked for in titles and descriptions
+    pub urgent_keywords: Vec<String>,
+    // Label name -> priority, replacing the default 'P0' to 'P5' labels
+    pub priorities: HashMap<String, u32>,
+    // Regular expression capturing the priority from label names
+    pub priority_pattern: Option<String>,
+    // Label name -> severity exported in the 'severity' column
+    pub severities: HashMap<String, String>,
+    // Label name -> estimate exported in the 'estimate' column
+    pub estimates: HashMap<String, f64>,
+    // 'P0' -> number of days allowed to close issues of this priority
+    pub sla: HashMap<String, u32>,
+    // Template of the links to the issues, e.g. to go through a proxy
+    pub url_template: Option<String>,
+    // Column name -> header used in the exported files
+    pub headers: HashMap<String, String>,
+    pub routes: Vec<Route>,
+    // Sections of the changelog, in order
+    pub changelog: Vec<ChangelogSection>,
+    pub smtp: Option<Smtp>,
+    // Column of the classic project boards -> state of the issues in it
+    pub board_states: HashMap<String, IssueState>,
+    pub calendar: CalendarConfig,
+    // Secret of the github webhooks, which are refused without it
+    pub webhook_secret: Option<String>,
+}
+
+impl Config {
+    pub fn from_path(path: &Path) -> Config {
+        let mut content = String::new();
+        File::open(path)
+            .and_then(|mut f| f.read_to_string(&mut content))
+            .expect("Failed to read config file");
+
+        toml::from_str(&content).expect("Failed to parse config file")
+    }
+}
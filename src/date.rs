@@ -0,0 +1,58 @@
+use chrono::{DateTime, Duration, Utc};
+
+// Duration rounded down to its largest unit: '3 days', '5 months'
+pub fn humanize(secs: i64) -> String {
+    let (count, unit) = match secs.max(0) {
+        s if s < 3600 => (s / 60, "minute"),
+        s if s < 86_400 => (s / 3600, "hour"),
+        s if s < 30 * 86_400 => (s / 86_400, "day"),
+        s if s < 365 * 86_400 => (s / (30 * 86_400), "month"),
+        s => (s / (365 * 86_400), "year"),
+    };
+
+    if count == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", count, unit)
+    }
+}
+
+// Durations going further back than chrono can represent stop at its first date
+pub fn ago(secs: u64) -> DateTime<Utc> {
+    i64::try_from(secs)
+        .ok()
+        .and_then(Duration::try_seconds)
+        .and_then(|d| Utc::now().checked_sub_signed(d))
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+// Timestamp, formatted as the API ones, of 'secs' seconds ago
+pub fn timestamp_ago(secs: u64) -> String {
+    ago(secs).format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn humanized_durations() {
+        assert_eq!(humanize(-5), "0 minutes");
+        assert_eq!(humanize(59), "0 minutes");
+        assert_eq!(humanize(60), "1 minute");
+        assert_eq!(humanize(3599), "59 minutes");
+        assert_eq!(humanize(2 * 3600 + 59 * 60), "2 hours");
+        assert_eq!(humanize(86_400), "1 day");
+        assert_eq!(humanize(29 * 86_400), "29 days");
+        assert_eq!(humanize(45 * 86_400), "1 month");
+        assert_eq!(humanize(364 * 86_400), "12 months");
+        assert_eq!(humanize(3 * 365 * 86_400), "3 years");
+    }
+
+    #[test]
+    fn ago_far_in_the_past() {
+        assert_eq!(ago(u64::MAX), DateTime::<Utc>::MIN_UTC);
+        assert_eq!(ago(i64::MAX as u64), DateTime::<Utc>::MIN_UTC);
+        assert!(ago(86_400) < Utc::now());
+    }
+}
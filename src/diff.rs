@@ -0,0 +1,330 @@
+use csv::{self, StringRecord};
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use column::Column;
+use dirs::long_path;
+use issue::IssueCSV;
+use output::{self, Options};
+
+// Columns compared with the previous export, if it has them
+const COMPARED: &[Column] = &[Column::Priority, Column::Assignee, Column::Milestone];
+
+const GREEN: &str = "\x1b[32m";
+const STRUCK: &str = "\x1b[9m";
+const HIGHLIGHT: &str = "\x1b[1;33m";
+const RESET: &str = "\x1b[0m";
+
+// Wrap the text in an escape sequence when writing to a terminal
+fn paint(text: &str, code: &str, options: &Options) -> String {
+    if options.color {
+        format!("{}{}{}", code, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+// Rows of a previous CSV or TSV export, and their headers
+fn read_export(path: &Path, options: &Options) -> io::Result<(StringRecord, Vec<StringRecord>)> {
+    let delimiter = if path.extension().and_then(|e| e.to_str()) == Some("tsv") {
+        b'\t'
+    } else {
+        options.delimiter
+    };
+
+    let mut rdr = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_path(long_path(path))?;
+    // Exports written with --excel start with a BOM
+    let headers: StringRecord = rdr
+        .headers()?
+        .iter()
+        .map(|h| h.trim_start_matches('\u{feff}'))
+        .collect();
+    let records = rdr
+        .records()
+        .collect::<Result<Vec<StringRecord>, csv::Error>>()?;
+
+    Ok((headers, records))
+}
+
+fn link(row: &IssueCSV) -> String {
+    format!("[{}{}]({}) {}", row.component, row.id, row.url, row.title)
+}
+
+fn write_list<W: Write>(wtr: &mut W, title: &str, items: &[String]) -> io::Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(wtr, "## {} ({})", title, items.len())?;
+    writeln!(wtr)?;
+    for item in items.iter() {
+        writeln!(wtr, "- {}", item)?;
+    }
+    writeln!(wtr)
+}
+
+// Compare the rows with a previous export: new, closed, reopened, changed and
+// no longer exported issues
+pub fn write_diff<W: Write>(
+    mut wtr: W,
+    old: &Path,
+    rows: &[IssueCSV],
+    options: &Options,
+) -> io::Result<()> {
+    let (headers, records) = read_export(old, options)?;
+    let index = |column: Column| headers.iter().position(|h| h == options.header(column));
+
+    let missing = |column: Column| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{} has no '{}' column",
+                old.display(),
+                options.header(column)
+            ),
+        )
+    };
+    let component = index(Column::Component).ok_or_else(|| missing(Column::Component))?;
+    let id = index(Column::Id).ok_or_else(|| missing(Column::Id))?;
+    let state = index(Column::State);
+    let title = index(Column::Title);
+
+    // Issues are matched by their URL, as the ids of the repositories merged into
+    // a component overlap, or by their component and id in exports without URLs
+    let url = index(Column::Url);
+    let key = |r: &StringRecord| match url {
+        Some(url) => r.get(url).map(str::to_string),
+        None => Some(format!("{}{}", r.get(component)?, r.get(id)?)),
+    };
+    let row_key = |row: &IssueCSV| match url {
+        Some(_) => row.url.clone(),
+        None => format!("{}{}", row.component, row.id),
+    };
+
+    let mut previous: HashMap<String, &StringRecord> =
+        records.iter().filter_map(|r| Some((key(r)?, r))).collect();
+
+    let mut new = Vec::new();
+    let mut closed = Vec::new();
+    let mut reopened = Vec::new();
+    let mut changed = Vec::new();
+
+    for row in rows.iter() {
+        let record = match previous.remove(&row_key(row)) {
+            Some(record) => record,
+            None => {
+                new.push(paint(&link(row), GREEN, options));
+                continue;
+            }
+        };
+
+        if let Some(old_state) = state.and_then(|i| record.get(i)) {
+            if old_state != "closed" && row.state == "closed" {
+                closed.push(paint(&link(row), STRUCK, options));
+            } else if old_state == "closed" && row.state != "closed" {
+                reopened.push(link(row));
+            }
+        }
+
+        let changes: Vec<String> = COMPARED
+            .iter()
+            .filter_map(|&column| {
+                let old_value = record.get(index(column)?)?;
+                // Compare with what would be written now
+                let new_value = if column.is_numeric() {
+                    row.field(column)
+                } else {
+                    options.sanitization.apply(row.field(column))
+                };
+
+                if old_value == new_value {
+                    None
+                } else {
+                    Some(format!(
+                        "{} '{}' -> '{}'",
+                        column.name(),
+                        old_value,
+                        paint(&new_value, HIGHLIGHT, options)
+                    ))
+                }
+            })
+            .collect();
+        if !changes.is_empty() {
+            changed.push(format!("{}: {}", link(row), changes.join(", ")));
+        }
+    }
+
+    // Issues left in the previous export, in its order
+    let gone: Vec<String> = records
+        .iter()
+        .filter_map(|r| {
+            if !previous.contains_key(&key(r)?) {
+                return None;
+            }
+            let name = title.and_then(|i| r.get(i)).unwrap_or("");
+            Some(format!("{}{} {}", r.get(component)?, r.get(id)?, name))
+        })
+        .collect();
+
+    writeln!(wtr, "# Changes since {}", old.display())?;
+    writeln!(wtr)?;
+    write_list(&mut wtr, "New issues", &new)?;
+    write_list(&mut wtr, "Newly closed", &closed)?;
+    write_list(&mut wtr, "Reopened", &reopened)?;
+    write_list(&mut wtr, "Changed", &changed)?;
+    write_list(&mut wtr, "No longer exported", &gone)
+}
+
+pub fn generate(old: &Path, rows: &[IssueCSV], options: &Options, output: &PathBuf) {
+    let mut wtr = output::open(output);
+
+    write_diff(&mut wtr, old, rows, options).expect("Failed to compare with previous export");
+    wtr.flush().expect("Failed to flush output");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    use client::Github;
+    use issue::Issue;
+
+    // Issues of two repositories merged into the same component
+    fn issue(repo: &str, number: u32, title: &str, state: &str, priority: Option<u32>) -> Issue {
+        let mut issue: Issue = serde_json::from_value(serde_json::json!({
+            "title": title,
+            "html_url": format!("https://github.com/owner/{}/issues/{}", repo, number),
+            "number": number,
+            "repository_url": format!("https://api.github.com/repos/owner/{}", repo),
+            "labels": [],
+            "state": state,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+        }))
+        .unwrap();
+        issue.priority = priority;
+        issue.component = Some("core".to_string());
+        issue
+    }
+
+    fn options() -> Options {
+        Options {
+            columns: vec![
+                Column::Component,
+                Column::Id,
+                Column::Title,
+                Column::State,
+                Column::Assignee,
+                Column::Milestone,
+                Column::Priority,
+                Column::Url,
+            ],
+            ..Options::default()
+        }
+    }
+
+    fn export(name: &str, content: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("github-issues-{}-{}.csv", name, process::id()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn diff(old: &Path, issues: &[Issue]) -> io::Result<String> {
+        let client = Github::new("token").unwrap();
+        let options = options();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, &options)).collect();
+
+        let mut output = Vec::new();
+        write_diff(&mut output, old, &rows, &options)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn changes_of_merged_repositories() {
+        let old = export(
+            "changes",
+            "component,id,title,state,assignee,milestone,priority,url
+core,#1,Crash,open,,,1,https://github.com/owner/repo/issues/1
+core,#1,Leak,open,,,,https://github.com/owner/lib/issues/1
+core,#2,Typo,closed,,,,https://github.com/owner/repo/issues/2
+core,#3,Gone,open,,,,https://github.com/owner/repo/issues/3
+",
+        );
+        let issues = vec![
+            issue("repo", 1, "Crash", "open", Some(2)),
+            issue("lib", 1, "Leak", "closed", None),
+            issue("repo", 2, "Typo", "open", None),
+            issue("repo", 4, "New", "open", None),
+        ];
+
+        let output = diff(&old, &issues).unwrap();
+        fs::remove_file(&old).unwrap();
+
+        assert_eq!(
+            output,
+            format!(
+                "# Changes since {}
+
+## New issues (1)
+
+- [core#4](https://github.com/owner/repo/issues/4) New
+
+## Newly closed (1)
+
+- [core#1](https://github.com/owner/lib/issues/1) Leak
+
+## Reopened (1)
+
+- [core#2](https://github.com/owner/repo/issues/2) Typo
+
+## Changed (1)
+
+- [core#1](https://github.com/owner/repo/issues/1) Crash: priority '1' -> '2'
+
+## No longer exported (1)
+
+- core#3 Gone
+
+",
+                old.display()
+            )
+        );
+    }
+
+    #[test]
+    fn matched_by_id_without_urls() {
+        let old = export("ids", "component,id,title,priority\ncore,#1,Crash,1\n");
+        let issues = vec![issue("repo", 1, "Crash", "open", Some(1))];
+
+        let output = diff(&old, &issues).unwrap();
+        fs::remove_file(&old).unwrap();
+
+        assert_eq!(output, format!("# Changes since {}\n\n", old.display()));
+    }
+
+    #[test]
+    fn invalid_exports() {
+        let old = export("invalid", "component,title\ncore,Crash\n");
+        let err = diff(&old, &[]).unwrap_err();
+        fs::remove_file(&old).unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            format!("{} has no 'id' column", old.display())
+        );
+
+        let missing = env::temp_dir().join("github-issues-missing-export.csv");
+        assert!(diff(&missing, &[]).is_err());
+    }
+}
@@ -0,0 +1,115 @@
+use directories::ProjectDirs;
+
+#[cfg(windows)]
+use std::path::Component;
+use std::path::{Path, PathBuf};
+
+// Per-user locations of the configuration and data files, following the XDG
+// conventions (or their equivalent on other platforms)
+#[derive(Debug)]
+pub struct Dirs {
+    config: PathBuf,
+    data: PathBuf,
+}
+
+// Windows limits paths to 260 characters, unless they are in the '\\?\' form
+// returned by canonicalize(). Only existing paths can be canonicalized: the
+// closest existing ancestor is, and the directories and files still to create
+// are appended to it. Paths going up with '..' after it are kept as they are,
+// as the '\\?\' form does not resolve them.
+#[cfg(windows)]
+pub fn long_path(path: &Path) -> PathBuf {
+    for ancestor in path.ancestors().skip(1) {
+        let existing = if ancestor.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            ancestor
+        };
+        let base = match existing.canonicalize() {
+            Ok(base) => base,
+            Err(_) => continue,
+        };
+
+        let rest = path.strip_prefix(ancestor).unwrap_or(path);
+        if rest.components().all(|c| matches!(c, Component::Normal(_))) {
+            return base.join(rest);
+        }
+        break;
+    }
+
+    path.to_path_buf()
+}
+
+#[cfg(not(windows))]
+pub fn long_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+impl Dirs {
+    pub fn new(config_dir: Option<PathBuf>, data_dir: Option<PathBuf>) -> Dirs {
+        let project = ProjectDirs::from("", "", "github-issues");
+        let default = |f: fn(&ProjectDirs) -> &Path| match project {
+            Some(ref p) => f(p).to_path_buf(),
+            None => PathBuf::from("."),
+        };
+
+        Dirs {
+            config: config_dir.unwrap_or_else(|| default(ProjectDirs::config_dir)),
+            data: data_dir.unwrap_or_else(|| default(ProjectDirs::data_dir)),
+        }
+    }
+
+    pub fn config_dir(&self) -> &Path {
+        &self.config
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data
+    }
+
+    pub fn config_file(&self) -> PathBuf {
+        self.config.join("config.toml")
+    }
+
+    pub fn token_file(&self) -> PathBuf {
+        self.config.join("token")
+    }
+
+    pub fn snapshot_dir(&self) -> PathBuf {
+        self.data.join("snapshots")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::env;
+
+    #[cfg(windows)]
+    #[test]
+    fn long_paths() {
+        let dir = env::temp_dir();
+        let long = long_path(&dir.join("missing").join("issues.csv"));
+        assert!(long.to_string_lossy().starts_with(r"\\?\"));
+        assert!(long.ends_with(r"missing\issues.csv"));
+
+        // Files of the current directory
+        assert!(long_path(Path::new("issues.csv")).is_absolute());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn long_paths() {
+        let path = env::temp_dir().join("missing").join("issues.csv");
+        assert_eq!(long_path(&path), path);
+    }
+
+    #[test]
+    fn directories() {
+        let dirs = Dirs::new(Some(PathBuf::from("conf")), Some(PathBuf::from("data")));
+        assert_eq!(dirs.config_file(), Path::new("conf").join("config.toml"));
+        assert_eq!(dirs.token_file(), Path::new("conf").join("token"));
+        assert_eq!(dirs.snapshot_dir(), Path::new("data").join("snapshots"));
+    }
+}
@@ -0,0 +1,79 @@
+use serde_json::Value;
+
+use std::io::{self, Write};
+
+use client::{Github, StatusCode};
+use github::{get_issue_count, IssueQuery, PullRequestFilter};
+
+// Largest page of the issues API
+const PAGE_SIZE: u32 = 100;
+
+// Scopes of classic tokens, fine-grained tokens have none
+fn token_scopes(client: &Github) -> Option<String> {
+    match client.get::<Value>("user") {
+        Ok((headers, _status, _json)) => headers
+            .get("x-oauth-scopes")
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string()),
+        Err(_) => None,
+    }
+}
+
+fn is_accessible(client: &Github, owner: &str, repo_name: &str) -> bool {
+    let repo_endpoint = format!("repos/{}/{}", owner, repo_name);
+    match client.get::<Value>(&repo_endpoint) {
+        Ok((_headers, status, _json)) => status == StatusCode::OK,
+        Err(_) => false,
+    }
+}
+
+// Report what a run would fetch without fetching the issues, returning false if
+// some components cannot be accessed
+pub fn dry_run<W: Write>(
+    mut wtr: W,
+    client: &Github,
+    owner: &str,
+    components: &[String],
+    query: &IssueQuery,
+    pull_requests: PullRequestFilter,
+) -> io::Result<bool> {
+    match token_scopes(client) {
+        Some(ref scopes) if scopes.is_empty() => writeln!(wtr, "Token scopes: none")?,
+        Some(scopes) => writeln!(wtr, "Token scopes: {}", scopes)?,
+        None => writeln!(wtr, "Token scopes: unknown, fine-grained token")?,
+    }
+
+    let mut accessible = 0;
+    let (mut issues, mut pages) = (0, 0);
+    for component in components.iter() {
+        if !is_accessible(client, owner, component) {
+            writeln!(wtr, "{}/{}: not accessible", owner, component)?;
+            continue;
+        }
+        accessible += 1;
+
+        match get_issue_count(client, owner, component, query, pull_requests) {
+            Some(count) => {
+                let component_pages = count.div_ceil(PAGE_SIZE).max(1);
+                writeln!(
+                    wtr,
+                    "{}/{}: {} issues, {} pages",
+                    owner, component, count, component_pages
+                )?;
+                issues += count;
+                pages += component_pages;
+            }
+            None => writeln!(wtr, "{}/{}: failed to count issues", owner, component)?,
+        }
+    }
+
+    writeln!(
+        wtr,
+        "Total: {} issues, {} pages, from {} of {} components",
+        issues,
+        pages,
+        accessible,
+        components.len()
+    )?;
+    Ok(accessible == components.len())
+}
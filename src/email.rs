@@ -0,0 +1,76 @@
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+use config::Smtp;
+use issue::IssueCSV;
+use output::{self, Format, Options};
+
+// Send the issues as a HTML report, along with a CSV attachment or a XLSX one if
+// 'format' is xlsx
+pub fn send_report(
+    smtp: &Smtp,
+    recipients: &[String],
+    subject: &str,
+    format: Format,
+    rows: &[IssueCSV],
+    options: &Options,
+) -> Result<(), String> {
+    let format = if format == Format::Xlsx {
+        Format::Xlsx
+    } else {
+        Format::Csv
+    };
+    let content_type = match format {
+        Format::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => "text/csv",
+    };
+
+    let mut html = Vec::new();
+    output::write(Format::Html, &mut html, rows, options).map_err(|e| e.to_string())?;
+    let mut attachment = Vec::new();
+    output::write(format, &mut attachment, rows, options).map_err(|e| e.to_string())?;
+
+    let from = smtp
+        .from
+        .parse()
+        .map_err(|e| format!("Invalid sender '{}': {}", smtp.from, e))?;
+    let mut builder = Message::builder().from(from).subject(subject);
+    for recipient in recipients.iter() {
+        let to = recipient
+            .parse()
+            .map_err(|e| format!("Invalid recipient '{}': {}", recipient, e))?;
+        builder = builder.to(to);
+    }
+
+    let email = builder
+        .multipart(
+            MultiPart::mixed()
+                .singlepart(SinglePart::html(
+                    String::from_utf8_lossy(&html).into_owned(),
+                ))
+                .singlepart(
+                    Attachment::new(format!("issues.{}", format.extension())).body(
+                        attachment,
+                        ContentType::parse(content_type).map_err(|e| e.to_string())?,
+                    ),
+                ),
+        )
+        .map_err(|e| e.to_string())?;
+
+    // The connection is always upgraded with STARTTLS
+    let mut transport = SmtpTransport::starttls_relay(&smtp.host).map_err(|e| e.to_string())?;
+    if let Some(port) = smtp.port {
+        transport = transport.port(port);
+    }
+    if let (Some(username), Some(password)) = (&smtp.username, &smtp.password) {
+        transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+
+    transport
+        .build()
+        .send(&email)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,1011 @@
+// Small expression language used to filter and sort issues:
+//
+//   state == open && (priority <= 1 || label == regression)
+//   -priority, component
+use chrono::Utc;
+
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use calendar::Calendar;
+use issue::Issue;
+use markdown::Relation;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Component,
+    Number,
+    Title,
+    State,
+    Assignee,
+    Milestone,
+    Overdue,
+    Priority,
+    Label,
+    CreatedAt,
+    ClosedAt,
+    UpdatedAt,
+    Score,
+    Severity,
+    AuthorAssociation,
+    Reporter,
+    Estimate,
+    Comments,
+    Votes,
+    UrgentKeyword,
+    Draft,
+    BaseBranch,
+    HeadBranch,
+    RequestedReviewer,
+    Reactions,
+    LinkedPr,
+    Project,
+    BoardColumn,
+    Status,
+    Type,
+    AgeDays,
+    Stale,
+    External,
+    Progress,
+    MilestoneDue,
+    FirstResponse,
+    Parent,
+    Blocks,
+    BlockedBy,
+    Closes,
+}
+
+const FIELDS: &[(&str, Field)] = &[
+    ("component", Field::Component),
+    ("number", Field::Number),
+    ("id", Field::Number),
+    ("title", Field::Title),
+    ("state", Field::State),
+    ("assignee", Field::Assignee),
+    ("milestone", Field::Milestone),
+    ("overdue", Field::Overdue),
+    ("priority", Field::Priority),
+    ("label", Field::Label),
+    ("created_at", Field::CreatedAt),
+    ("closed_at", Field::ClosedAt),
+    ("updated_at", Field::UpdatedAt),
+    ("score", Field::Score),
+    ("severity", Field::Severity),
+    ("author_association", Field::AuthorAssociation),
+    ("reporter", Field::Reporter),
+    ("estimate", Field::Estimate),
+    ("comments", Field::Comments),
+    ("votes", Field::Votes),
+    ("urgent_keyword", Field::UrgentKeyword),
+    ("draft", Field::Draft),
+    ("base_branch", Field::BaseBranch),
+    ("head_branch", Field::HeadBranch),
+    ("requested_reviewer", Field::RequestedReviewer),
+    ("reactions", Field::Reactions),
+    ("linked_pr", Field::LinkedPr),
+    ("project", Field::Project),
+    ("board_column", Field::BoardColumn),
+    ("status", Field::Status),
+    ("type", Field::Type),
+    ("age_days", Field::AgeDays),
+    ("age", Field::AgeDays),
+    ("stale", Field::Stale),
+    ("external", Field::External),
+    ("progress", Field::Progress),
+    ("milestone_due", Field::MilestoneDue),
+    ("first_response_hours", Field::FirstResponse),
+    ("first_response", Field::FirstResponse),
+    ("parent", Field::Parent),
+    ("blocks", Field::Blocks),
+    ("blocked_by", Field::BlockedBy),
+    ("closes", Field::Closes),
+];
+
+// Settings the values of some fields depend on
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Context<'a> {
+    // Count the age of the issues in business days
+    pub calendar: Option<&'a Calendar>,
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+}
+
+fn references(issue: &Issue, relation: Relation) -> Vec<Value> {
+    issue
+        .get_references(relation)
+        .iter()
+        .map(|r| Value::Text(r.to_lowercase()))
+        .collect()
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Field> {
+        FIELDS.iter().find(|&&(n, _)| n == name).map(|&(_, f)| f)
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(
+            *self,
+            Field::Number
+                | Field::Priority
+                | Field::Score
+                | Field::Estimate
+                | Field::Comments
+                | Field::Votes
+                | Field::Reactions
+                | Field::AgeDays
+                | Field::Progress
+                | Field::FirstResponse
+        )
+    }
+
+    // Only set once the issues have been hydrated using GraphQL
+    pub fn needs_graphql(&self) -> bool {
+        matches!(
+            *self,
+            Field::Reactions
+                | Field::LinkedPr
+                | Field::Project
+                | Field::BoardColumn
+                | Field::Status
+                | Field::Type
+                | Field::Parent
+        )
+    }
+
+    // Only set once the comments of the issues have been fetched
+    pub fn needs_first_response(&self) -> bool {
+        *self == Field::FirstResponse
+    }
+
+    // Values of the field for this issue: none if unset, one per label for 'label'
+    pub fn values(&self, issue: &Issue, context: &Context) -> Vec<Value> {
+        let text = |s: &str| Value::Text(s.to_lowercase());
+
+        match *self {
+            Field::Component => vec![text(&issue.get_component())],
+            Field::Number => vec![Value::Number(f64::from(issue.number))],
+            Field::Title => vec![text(&issue.title)],
+            Field::State => vec![text(&issue.get_state_str())],
+            Field::Assignee => issue.get_assignees().into_iter().map(text).collect(),
+            Field::Milestone => issue.milestone.iter().map(|m| text(&m.title)).collect(),
+            Field::Overdue => vec![text(&issue.is_overdue(Utc::now()).to_string())],
+            Field::Priority => issue
+                .get_priority()
+                .map(|p| Value::Number(f64::from(p)))
+                .into_iter()
+                .collect(),
+            Field::Label => match issue.labels {
+                Some(ref labels) => labels.iter().map(|l| text(&l.name)).collect(),
+                None => vec![],
+            },
+            Field::CreatedAt => vec![text(&issue.get_created_at())],
+            Field::ClosedAt => issue
+                .get_closed_at()
+                .map(|c| text(&c))
+                .into_iter()
+                .collect(),
+            Field::UpdatedAt => vec![text(&issue.get_updated_at())],
+            Field::Score => issue.score.map(Value::Number).into_iter().collect(),
+            Field::Severity => issue.severity.iter().map(|s| text(s)).collect(),
+            Field::AuthorAssociation => issue.author_association.iter().map(|a| text(a)).collect(),
+            Field::Reporter => issue.get_reporter().map(text).into_iter().collect(),
+            Field::Estimate => issue.estimate.map(Value::Number).into_iter().collect(),
+            Field::Comments => vec![Value::Number(f64::from(issue.comments))],
+            Field::Votes => vec![Value::Number(f64::from(issue.get_votes()))],
+            Field::UrgentKeyword => issue.urgent_keywords.iter().map(|k| text(k)).collect(),
+            Field::Draft => issue
+                .pull_request_details
+                .as_ref()
+                .and_then(|d| d.draft)
+                .map(|d| text(&d.to_string()))
+                .into_iter()
+                .collect(),
+            Field::BaseBranch => issue
+                .pull_request_details
+                .iter()
+                .map(|d| text(&d.base.name))
+                .collect(),
+            Field::HeadBranch => issue
+                .pull_request_details
+                .iter()
+                .map(|d| text(&d.head.name))
+                .collect(),
+            Field::RequestedReviewer => match issue.pull_request_details {
+                Some(ref d) => d
+                    .requested_reviewers
+                    .iter()
+                    .map(|r| text(&r.login))
+                    .collect(),
+                None => vec![],
+            },
+            Field::Reactions => issue
+                .extra_fields
+                .as_ref()
+                .map(|e| Value::Number(f64::from(e.reactions)))
+                .into_iter()
+                .collect(),
+            Field::LinkedPr => match issue.extra_fields {
+                Some(ref e) => e.linked_prs.iter().map(|p| text(p)).collect(),
+                None => vec![],
+            },
+            Field::Project => match issue.extra_fields {
+                Some(ref e) => e.projects.iter().map(|p| text(p)).collect(),
+                None => vec![],
+            },
+            Field::BoardColumn => issue.get_board_column().map(text).into_iter().collect(),
+            Field::Status => issue.get_status().map(text).into_iter().collect(),
+            Field::Type => issue.get_type().map(text).into_iter().collect(),
+            Field::AgeDays => issue
+                .get_age_days(context.calendar)
+                .map(|d| Value::Number(d as f64))
+                .into_iter()
+                .collect(),
+            Field::Stale => issue
+                .stale
+                .map(|s| text(&s.to_string()))
+                .into_iter()
+                .collect(),
+            Field::External => issue
+                .is_external()
+                .map(|e| text(&e.to_string()))
+                .into_iter()
+                .collect(),
+            // Percentage of checked items
+            Field::Progress => issue
+                .get_task_progress()
+                .map(|(done, total)| Value::Number(100.0 * done as f64 / total as f64))
+                .into_iter()
+                .collect(),
+            Field::MilestoneDue => issue
+                .get_milestone_due()
+                .map(|d| text(&d.format("%Y-%m-%d").to_string()))
+                .into_iter()
+                .collect(),
+            Field::FirstResponse => issue
+                .first_response
+                .map(|s| Value::Number(s as f64 / 3600.0))
+                .into_iter()
+                .collect(),
+            Field::Parent => issue
+                .extra_fields
+                .as_ref()
+                .and_then(|e| e.parent.as_deref())
+                .map(text)
+                .into_iter()
+                .collect(),
+            Field::Blocks => references(issue, Relation::Blocks),
+            Field::BlockedBy => references(issue, Relation::BlockedBy),
+            Field::Closes => references(issue, Relation::Closes),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    message: String,
+    source: String,
+    // Offset, in bytes, of the faulty part of the source
+    position: usize,
+}
+
+impl ParseError {
+    fn new(message: String, source: &str, position: usize) -> ParseError {
+        ParseError {
+            message,
+            source: source.to_string(),
+            position,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let column = self.source[..self.position].chars().count();
+
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "  {}", self.source)?;
+        write!(f, "  {}^", " ".repeat(column))
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    // Case insensitive substring
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && !"()!=<>&|\"~,".contains(c)
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some((pos, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, c)| c);
+
+        let token = match (c, next) {
+            (c, _) if c.is_whitespace() => continue,
+            ('(', _) => Token::LParen,
+            (')', _) => Token::RParen,
+            ('&', Some('&')) => {
+                chars.next();
+                Token::And
+            }
+            ('|', Some('|')) => {
+                chars.next();
+                Token::Or
+            }
+            ('=', Some('=')) => {
+                chars.next();
+                Token::Op(Op::Eq)
+            }
+            ('!', Some('=')) => {
+                chars.next();
+                Token::Op(Op::Ne)
+            }
+            ('<', Some('=')) => {
+                chars.next();
+                Token::Op(Op::Le)
+            }
+            ('>', Some('=')) => {
+                chars.next();
+                Token::Op(Op::Ge)
+            }
+            ('~', Some('=')) => {
+                chars.next();
+                Token::Op(Op::Contains)
+            }
+            ('!', _) => Token::Not,
+            ('<', _) => Token::Op(Op::Lt),
+            ('>', _) => Token::Op(Op::Gt),
+            ('"', _) => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c)) => s.push(c),
+                            None => break,
+                        },
+                        Some((_, c)) => s.push(c),
+                        None => {
+                            return Err(ParseError::new(
+                                "unterminated string".to_string(),
+                                source,
+                                pos,
+                            ))
+                        }
+                    }
+                }
+                Token::Quoted(s)
+            }
+            (c, _) if is_word_char(c) => {
+                let mut s = c.to_string();
+                while let Some(&(_, c)) = chars.peek() {
+                    if !is_word_char(c) {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                Token::Word(s)
+            }
+            (c, _) => {
+                return Err(ParseError::new(
+                    format!("unexpected character '{}'", c),
+                    source,
+                    pos,
+                ))
+            }
+        };
+
+        tokens.push((pos, token));
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    // 'none': the field is not set
+    Unset,
+    Value(Value),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, Op, Operand),
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|&(_, ref t)| t)
+    }
+
+    // Offset of the current token, or the end of the source
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|&(p, _)| p)
+            .unwrap_or_else(|| self.source.len())
+    }
+
+    fn error(&self, message: String) -> ParseError {
+        ParseError::new(message, self.source, self.offset())
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|&(_, ref t)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_and()?;
+
+        while self.peek() == Some(&Token::Or) {
+            self.advance();
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.parse_unary()?;
+
+        while self.peek() == Some(&Token::And) {
+            self.advance();
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        match self.peek().cloned() {
+            Some(Token::Not) => {
+                self.advance();
+                Ok(Expr::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::LParen) => {
+                self.advance();
+                let expr = self.parse_or()?;
+                match self.peek() {
+                    Some(&Token::RParen) => {
+                        self.advance();
+                        Ok(expr)
+                    }
+                    _ => Err(self.error("expected ')'".to_string())),
+                }
+            }
+            _ => self.parse_comparison(),
+        }
+    }
+
+    fn parse_field(&mut self) -> Result<Field, ParseError> {
+        match self.peek().cloned() {
+            Some(Token::Word(name)) => match Field::from_name(&name) {
+                Some(field) => {
+                    self.advance();
+                    Ok(field)
+                }
+                None => Err(self.error(format!(
+                    "unknown field '{}', expected one of: {}",
+                    name,
+                    FIELDS
+                        .iter()
+                        .map(|&(n, _)| n)
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                ))),
+            },
+            _ => Err(self.error("expected a field name".to_string())),
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, ParseError> {
+        let field = self.parse_field()?;
+
+        let op = match self.peek() {
+            Some(&Token::Op(op)) => op,
+            _ => {
+                return Err(self.error(
+                    "expected a comparison operator: ==, !=, <, <=, >, >= or ~=".to_string(),
+                ))
+            }
+        };
+        self.advance();
+
+        let (raw, quoted) = match self.peek().cloned() {
+            Some(Token::Word(s)) => (s, false),
+            Some(Token::Quoted(s)) => (s, true),
+            _ => return Err(self.error("expected a value".to_string())),
+        };
+
+        let operand = if !quoted && raw == "none" {
+            if op != Op::Eq && op != Op::Ne {
+                return Err(self.error("'none' can only be compared using == or !=".to_string()));
+            }
+            Operand::Unset
+        } else if field.is_numeric() {
+            // Accept 'P1' as priority
+            let number = if field == Field::Priority {
+                raw.trim_start_matches(|c: char| c == 'P' || c == 'p')
+            } else {
+                raw.as_str()
+            };
+
+            match number.parse::<f64>() {
+                Ok(n) => Operand::Value(Value::Number(n)),
+                Err(_) => {
+                    return Err(self.error(format!("expected a number, got '{}'", raw)));
+                }
+            }
+        } else {
+            Operand::Value(Value::Text(raw.to_lowercase()))
+        };
+        self.advance();
+
+        Ok(Expr::Compare(field, op, operand))
+    }
+}
+
+fn compare(value: &Value, op: Op, operand: &Value) -> bool {
+    match op {
+        Op::Eq => value == operand,
+        Op::Ne => value != operand,
+        Op::Lt => value < operand,
+        Op::Le => value <= operand,
+        Op::Gt => value > operand,
+        Op::Ge => value >= operand,
+        Op::Contains => match (value, operand) {
+            (&Value::Text(ref v), &Value::Text(ref o)) => v.contains(o.as_str()),
+            _ => false,
+        },
+    }
+}
+
+impl Expr {
+    fn any_field<F: Fn(Field) -> bool>(&self, f: &F) -> bool {
+        match *self {
+            Expr::And(ref a, ref b) | Expr::Or(ref a, ref b) => a.any_field(f) || b.any_field(f),
+            Expr::Not(ref e) => e.any_field(f),
+            Expr::Compare(field, _, _) => f(field),
+        }
+    }
+
+    fn eval(&self, issue: &Issue, context: &Context) -> bool {
+        match *self {
+            Expr::And(ref a, ref b) => a.eval(issue, context) && b.eval(issue, context),
+            Expr::Or(ref a, ref b) => a.eval(issue, context) || b.eval(issue, context),
+            Expr::Not(ref e) => !e.eval(issue, context),
+            Expr::Compare(field, op, ref operand) => {
+                let values = field.values(issue, context);
+
+                match (op, operand) {
+                    (Op::Eq, &Operand::Unset) => values.is_empty(),
+                    (Op::Ne, &Operand::Unset) => !values.is_empty(),
+                    (_, &Operand::Unset) => false,
+                    // 'label != bug' means none of the labels is 'bug'
+                    (Op::Ne, &Operand::Value(ref v)) => !values.iter().any(|x| x == v),
+                    (_, &Operand::Value(ref v)) => values.iter().any(|x| compare(x, op, v)),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    expr: Expr,
+}
+
+impl Filter {
+    pub fn matches(&self, issue: &Issue, context: &Context) -> bool {
+        self.expr.eval(issue, context)
+    }
+
+    pub fn needs_graphql(&self) -> bool {
+        self.expr.any_field(&|f| f.needs_graphql())
+    }
+
+    pub fn needs_first_response(&self) -> bool {
+        self.expr.any_field(&|f| f.needs_first_response())
+    }
+}
+
+impl FromStr for Filter {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            source: s,
+            tokens: tokenize(s)?,
+            pos: 0,
+        };
+
+        let expr = parser.parse_or()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("unexpected trailing input".to_string()));
+        }
+
+        Ok(Filter { expr })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SortKey {
+    field: Field,
+    descending: bool,
+}
+
+// Ordered list of fields to sort on, '-' prefixed ones in descending order
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortSpec {
+    keys: Vec<SortKey>,
+}
+
+impl SortSpec {
+    pub fn needs_graphql(&self) -> bool {
+        self.keys.iter().any(|k| k.field.needs_graphql())
+    }
+
+    pub fn needs_first_response(&self) -> bool {
+        self.keys.iter().any(|k| k.field.needs_first_response())
+    }
+
+    pub fn compare(&self, a: &Issue, b: &Issue, context: &Context) -> Ordering {
+        for key in self.keys.iter() {
+            let va = key.field.values(a, context);
+            let vb = key.field.values(b, context);
+
+            // Issues without value are always last
+            let cmp = match (va.first(), vb.first()) {
+                (None, None) => Ordering::Equal,
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (Some(x), Some(y)) => x.partial_cmp(y).unwrap_or(Ordering::Equal),
+            };
+
+            let cmp = if key.descending { cmp.reverse() } else { cmp };
+            if cmp != Ordering::Equal {
+                return cmp;
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+impl FromStr for SortSpec {
+    type Err = ParseError;
+
+    // Accepts both '-priority,component' and '[-priority, component]'
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let list = trimmed
+            .strip_prefix('[')
+            .and_then(|l| l.strip_suffix(']'))
+            .unwrap_or(trimmed);
+        let base = list.as_ptr() as usize - s.as_ptr() as usize;
+
+        let mut keys = Vec::new();
+        let mut offset = 0;
+
+        for part in list.split(',') {
+            let position = base + offset + (part.len() - part.trim_start().len());
+            offset += part.len() + 1;
+
+            let part = part.trim();
+            let (name, descending) = match part.strip_prefix('-') {
+                Some(name) => (name, true),
+                None => (part.trim_start_matches('+'), false),
+            };
+
+            match Field::from_name(name) {
+                Some(field) => keys.push(SortKey { field, descending }),
+                None => {
+                    return Err(ParseError::new(
+                        format!("unknown sort field '{}'", name),
+                        s,
+                        position,
+                    ))
+                }
+            }
+        }
+
+        Ok(SortSpec { keys })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use config::CalendarConfig;
+
+    fn issue(number: u32, labels: &[&str], priority: Option<u32>) -> Issue {
+        let labels: Vec<serde_json::Value> = labels
+            .iter()
+            .map(|l| serde_json::json!({ "name": l }))
+            .collect();
+        let mut issue: Issue = serde_json::from_value(serde_json::json!({
+            "title": format!("Crash number {}", number),
+            "html_url": format!("https://github.com/owner/repo/issues/{}", number),
+            "number": number,
+            "repository_url": "https://api.github.com/repos/owner/repo",
+            "labels": labels,
+            "state": "open",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+        }))
+        .unwrap();
+        issue.priority = priority;
+        issue
+    }
+
+    fn error(source: &str) -> String {
+        source.parse::<Filter>().unwrap_err().message
+    }
+
+    #[test]
+    fn tokenize_operators_and_words() {
+        let tokens: Vec<Token> = tokenize("!(priority<=P1)&&label~=\"data loss\"")
+            .unwrap()
+            .into_iter()
+            .map(|(_, t)| t)
+            .collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Not,
+                Token::LParen,
+                Token::Word("priority".to_string()),
+                Token::Op(Op::Le),
+                Token::Word("P1".to_string()),
+                Token::RParen,
+                Token::And,
+                Token::Word("label".to_string()),
+                Token::Op(Op::Contains),
+                Token::Quoted("data loss".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_positions_and_escapes() {
+        let tokens = tokenize(r#"title == "say \"hi\"""#).unwrap();
+        assert_eq!(tokens[0].0, 0);
+        assert_eq!(tokens[1].0, 6);
+        assert_eq!(tokens[2], (9, Token::Quoted("say \"hi\"".to_string())));
+    }
+
+    #[test]
+    fn tokenize_errors() {
+        let err = tokenize("title == \"open").unwrap_err();
+        assert_eq!(err.message, "unterminated string");
+        assert_eq!(err.position, 9);
+
+        let err = tokenize("state == open & priority == 1").unwrap_err();
+        assert_eq!(err.message, "unexpected character '&'");
+        assert_eq!(err.position, 14);
+    }
+
+    #[test]
+    fn filter_matches() {
+        let regression = issue(1, &["regression", "ui"], Some(1));
+        let feature = issue(2, &["feature"], None);
+
+        let matches = |source: &str, issue: &Issue| {
+            source
+                .parse::<Filter>()
+                .unwrap()
+                .matches(issue, &Context::default())
+        };
+
+        assert!(matches(
+            "state == open && (priority <= 1 || label == regression)",
+            &regression
+        ));
+        assert!(!matches(
+            "state == open && (priority <= 1 || label == regression)",
+            &feature
+        ));
+        assert!(matches("priority == P1", &regression));
+        assert!(matches("priority == none", &feature));
+        assert!(matches("priority != none", &regression));
+        assert!(matches("label != regression", &feature));
+        assert!(!matches("label != regression", &regression));
+        assert!(matches("title ~= \"CRASH number\"", &feature));
+        assert!(matches("!(label == feature)", &regression));
+        assert!(matches("number > 1 && age >= 0", &feature));
+        assert!(matches("blocks == none && stale == none", &feature));
+    }
+
+    #[test]
+    fn filter_errors() {
+        assert!(error("colour == red").starts_with("unknown field 'colour'"));
+        assert_eq!(error("== open"), "expected a field name");
+        assert_eq!(
+            error("state open"),
+            "expected a comparison operator: ==, !=, <, <=, >, >= or ~="
+        );
+        assert_eq!(error("state =="), "expected a value");
+        assert_eq!(
+            error("priority > none"),
+            "'none' can only be compared using == or !="
+        );
+        assert_eq!(error("priority <= high"), "expected a number, got 'high'");
+        assert_eq!(error("(state == open"), "expected ')'");
+        assert_eq!(error("state == open closed"), "unexpected trailing input");
+    }
+
+    #[test]
+    fn error_points_at_the_faulty_part() {
+        let err = "state == open && colour == red"
+            .parse::<Filter>()
+            .unwrap_err();
+        assert_eq!(err.position, 17);
+
+        let message = err.to_string();
+        let lines: Vec<&str> = message.lines().collect();
+        assert_eq!(lines[1], "  state == open && colour == red");
+        assert_eq!(lines[2], format!("  {}^", " ".repeat(17)));
+    }
+
+    #[test]
+    fn needs_fetching() {
+        let filter: Filter = "status == done".parse().unwrap();
+        assert!(filter.needs_graphql());
+        assert!(!filter.needs_first_response());
+
+        let sort: SortSpec = "-first_response".parse().unwrap();
+        assert!(!sort.needs_graphql());
+        assert!(sort.needs_first_response());
+    }
+
+    #[test]
+    fn sort_spec_lists() {
+        let expected = SortSpec {
+            keys: vec![
+                SortKey {
+                    field: Field::Priority,
+                    descending: true,
+                },
+                SortKey {
+                    field: Field::AgeDays,
+                    descending: false,
+                },
+            ],
+        };
+
+        assert_eq!("-priority,age".parse::<SortSpec>().unwrap(), expected);
+        assert_eq!(
+            "[-priority, +age_days]".parse::<SortSpec>().unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn sort_spec_errors() {
+        let err = "-priority, colour".parse::<SortSpec>().unwrap_err();
+        assert_eq!(err.message, "unknown sort field 'colour'");
+        assert_eq!(err.position, 11);
+
+        let err = "[priority,,title]".parse::<SortSpec>().unwrap_err();
+        assert_eq!(err.message, "unknown sort field ''");
+    }
+
+    #[test]
+    fn sort_spec_compare() {
+        let p0 = issue(1, &[], Some(0));
+        let p2 = issue(2, &[], Some(2));
+        let unset = issue(3, &[], None);
+
+        let ascending: SortSpec = "priority".parse().unwrap();
+        assert_eq!(
+            ascending.compare(&p0, &p2, &Context::default()),
+            Ordering::Less
+        );
+        assert_eq!(
+            ascending.compare(&unset, &p0, &Context::default()),
+            Ordering::Greater
+        );
+
+        // Issues without value stay last in descending order
+        let descending: SortSpec = "-priority,number".parse().unwrap();
+        assert_eq!(
+            descending.compare(&p0, &p2, &Context::default()),
+            Ordering::Greater
+        );
+        assert_eq!(
+            descending.compare(&unset, &p2, &Context::default()),
+            Ordering::Greater
+        );
+        assert_eq!(
+            descending.compare(&p2, &p2, &Context::default()),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn age_in_business_days() {
+        let old = issue(1, &[], None);
+        let days_off = |weekend: &[&str]| {
+            Calendar::from_config(&CalendarConfig {
+                weekend: weekend.iter().map(|d| d.to_string()).collect(),
+                holidays: Vec::new(),
+            })
+            .unwrap()
+        };
+
+        let never_working = days_off(&[
+            "monday",
+            "tuesday",
+            "wednesday",
+            "thursday",
+            "friday",
+            "saturday",
+            "sunday",
+        ]);
+        let filter: Filter = "age == 0".parse().unwrap();
+        assert!(filter.matches(
+            &old,
+            &Context {
+                calendar: Some(&never_working)
+            }
+        ));
+        assert!(!filter.matches(&old, &Context::default()));
+
+        // Weekends are not counted
+        let weekdays = days_off(&["saturday", "sunday"]);
+        let business = Field::AgeDays.values(
+            &old,
+            &Context {
+                calendar: Some(&weekdays),
+            },
+        );
+        let calendar = Field::AgeDays.values(&old, &Context::default());
+        assert!(business[0] < calendar[0]);
+    }
+}
@@ -0,0 +1,22 @@
+// Issues built by the unit tests
+use serde_json::{self, Value};
+
+use issue::Issue;
+
+// Open issue of 'owner/repo', 'fields' overriding the ones returned by the API
+pub fn issue(number: u32, fields: Value) -> Issue {
+    let mut json = serde_json::json!({
+        "title": format!("Issue {}", number),
+        "html_url": format!("https://github.com/owner/repo/issues/{}", number),
+        "number": number,
+        "repository_url": "https://api.github.com/repos/owner/repo",
+        "labels": [],
+        "state": "open",
+        "created_at": "2024-01-01T00:00:00Z",
+        "updated_at": "2024-01-02T00:00:00Z",
+    });
+    if let Value::Object(fields) = fields {
+        json.as_object_mut().unwrap().extend(fields);
+    }
+    serde_json::from_value(json).unwrap()
+}
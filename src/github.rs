@@ -0,0 +1,755 @@
+use chrono::{DateTime, Utc};
+
+use itertools::Itertools;
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use url::form_urlencoded;
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+use std::sync::atomic::{self, AtomicUsize};
+use std::thread;
+use std::time::Instant;
+
+use client::{self, Github, HeaderMap, Response};
+use issue::{
+    Comment, Issue, IssueEvent, IssueState, IssueStateJson, Milestone, PullRequestDetails, Review,
+};
+use progress;
+
+pub type Issues = Vec<Issue>;
+
+// Maximum number of items per page of the REST API
+const PER_PAGE: usize = 100;
+
+pub(crate) fn get_json<T>(response: Result<Response<T>, client::Error>) -> Option<T> {
+    match response {
+        Ok((_headers, _status, json)) => json,
+        Err(e) => {
+            error!("{}", e);
+            None
+        }
+    }
+}
+
+// Fetch an endpoint of the REST API, logging how long the request took
+pub(crate) fn get<T: DeserializeOwned>(client: &Github, endpoint: &str) -> Option<T> {
+    let start = Instant::now();
+    let response = client.get::<T>(endpoint);
+    debug!("GET {} took {:?}", endpoint, start.elapsed());
+    get_json(response)
+}
+
+// Endpoint of the next page, from the 'Link: <url>; rel="next"' header
+fn next_page(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get("link").and_then(|v| v.to_str().ok())?;
+    let url = link
+        .split(',')
+        .find(|l| l.contains("rel=\"next\""))?
+        .trim()
+        .strip_prefix('<')?
+        .split('>')
+        .next()?;
+
+    // Endpoints are relative to the root of the API
+    let path = url.split("://").nth(1)?;
+    path.find('/').map(|i| path[i + 1..].to_string())
+}
+
+// Fetch the pages of an endpoint, whose parameters must not set 'per_page', until
+// 'limit' items are fetched. 'on_page' is called after each page.
+pub(crate) fn get_pages<T, F>(
+    client: &Github,
+    endpoint: &str,
+    limit: Option<usize>,
+    mut on_page: F,
+) -> Option<Vec<T>>
+where
+    T: DeserializeOwned,
+    F: FnMut(),
+{
+    let separator = if endpoint.contains('?') { '&' } else { '?' };
+    let per_page = limit.map_or(PER_PAGE, |l| l.clamp(1, PER_PAGE));
+    let mut next = Some(format!("{}{}per_page={}", endpoint, separator, per_page));
+    let mut items = Vec::new();
+
+    while let Some(endpoint) = next.take() {
+        let start = Instant::now();
+        let response = client.get::<Vec<T>>(&endpoint);
+        debug!("GET {} took {:?}", endpoint, start.elapsed());
+
+        let (headers, _status, page) = match response {
+            Ok(response) => response,
+            Err(e) => {
+                error!("{}", e);
+                return None;
+            }
+        };
+        items.extend(page?);
+        on_page();
+
+        if limit.is_some_and(|l| items.len() >= l) {
+            break;
+        }
+        next = next_page(&headers);
+    }
+
+    Some(items)
+}
+
+// State of the fetched issues, as understood by the API
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum StateFilter {
+    Open,
+    Closed,
+    #[default]
+    All,
+}
+
+impl StateFilter {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            StateFilter::Open => "open",
+            StateFilter::Closed => "closed",
+            StateFilter::All => "all",
+        }
+    }
+}
+
+impl FromStr for StateFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(StateFilter::Open),
+            "closed" => Ok(StateFilter::Closed),
+            "all" => Ok(StateFilter::All),
+            _ => Err(format!("unknown state '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MilestoneFilter {
+    Title(String),
+    // Issues without milestone
+    Unset,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssigneeFilter {
+    Login(String),
+    // Issues without assignee
+    Unset,
+}
+
+// Restrict the exported issues
+#[derive(Debug, Default, Clone)]
+pub struct IssueQuery {
+    pub state: StateFilter,
+    // Issues must have all these labels
+    pub labels: Vec<String>,
+    // Issues must have none of these labels
+    pub exclude_labels: Vec<String>,
+    // Labels can only be filtered by the API if the transforms don't rename them
+    pub labels_in_api: bool,
+    pub milestone: Option<MilestoneFilter>,
+    pub assignee: Option<AssigneeFilter>,
+    // 'yyyy-mm-dd' dates, 'after' ones included and 'before' ones excluded
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+    pub closed_after: Option<String>,
+    pub closed_before: Option<String>,
+    // Keep open issues but only the closed ones closed since this time
+    pub closed_since: Option<DateTime<Utc>>,
+    pub title_match: Option<Regex>,
+    pub title_exclude: Option<Regex>,
+    pub min_comments: Option<u32>,
+}
+
+fn in_range(date: Option<&str>, after: Option<&str>, before: Option<&str>) -> bool {
+    match date {
+        _ if after.is_none() && before.is_none() => true,
+        None => false,
+        Some(date) => after.is_none_or(|a| date >= a) && before.is_none_or(|b| date < b),
+    }
+}
+
+pub(crate) fn encode(s: &str) -> String {
+    form_urlencoded::byte_serialize(s.as_bytes()).collect()
+}
+
+impl IssueQuery {
+    // Parameters of the issues API doing part of the filtering. The API filters
+    // milestones by their number, which is specific to each repository.
+    fn params(&self, milestone: Option<&str>) -> String {
+        let mut params = format!("&state={}", self.state.as_str());
+
+        if let Some(milestone) = milestone {
+            params.push_str(&format!("&milestone={}", milestone));
+        }
+
+        match self.assignee {
+            Some(AssigneeFilter::Login(ref login)) => {
+                params.push_str(&format!("&assignee={}", encode(login)))
+            }
+            Some(AssigneeFilter::Unset) => params.push_str("&assignee=none"),
+            None => {}
+        }
+
+        if self.labels_in_api && !self.labels.is_empty() {
+            let labels: Vec<String> = self.labels.iter().map(|l| encode(l)).collect();
+            params.push_str(&format!("&labels={}", labels.join(",")));
+        }
+
+        params
+    }
+
+    // Qualifiers of the search API matching the same issues as the issues API,
+    // the other filters being only applied on the fetched issues
+    fn qualifiers(&self, pull_requests: PullRequestFilter) -> Vec<String> {
+        let mut qualifiers = Vec::new();
+
+        match pull_requests {
+            PullRequestFilter::Exclude => qualifiers.push("is:issue".to_string()),
+            PullRequestFilter::Only => qualifiers.push("is:pr".to_string()),
+            PullRequestFilter::Include => {}
+        }
+        match self.state {
+            StateFilter::Open => qualifiers.push("is:open".to_string()),
+            StateFilter::Closed => qualifiers.push("is:closed".to_string()),
+            StateFilter::All => {}
+        }
+        match self.milestone {
+            Some(MilestoneFilter::Title(ref title)) => {
+                qualifiers.push(format!("milestone:\"{}\"", title))
+            }
+            Some(MilestoneFilter::Unset) => qualifiers.push("no:milestone".to_string()),
+            None => {}
+        }
+        match self.assignee {
+            Some(AssigneeFilter::Login(ref login)) => {
+                qualifiers.push(format!("assignee:{}", login))
+            }
+            Some(AssigneeFilter::Unset) => qualifiers.push("no:assignee".to_string()),
+            None => {}
+        }
+        if let Some(min) = self.min_comments {
+            qualifiers.push(format!("comments:>={}", min));
+        }
+        if self.labels_in_api {
+            qualifiers.extend(self.labels.iter().map(|l| format!("label:\"{}\"", l)));
+        }
+
+        qualifiers
+    }
+
+    pub fn matches(&self, issue: &Issue) -> bool {
+        let state = match self.state {
+            StateFilter::Open => issue.state == IssueStateJson::Open,
+            StateFilter::Closed => issue.state == IssueStateJson::Closed,
+            StateFilter::All => true,
+        };
+        let milestone = match self.milestone {
+            Some(MilestoneFilter::Title(ref title)) => {
+                issue.milestone.as_ref().map(|m| &m.title) == Some(title)
+            }
+            Some(MilestoneFilter::Unset) => issue.milestone.is_none(),
+            None => true,
+        };
+
+        let recent = match self.closed_since {
+            Some(since) if issue.state == IssueStateJson::Closed => {
+                issue.closed_at.is_some_and(|c| c >= since)
+            }
+            _ => true,
+        };
+
+        let assignee = match self.assignee {
+            Some(AssigneeFilter::Login(ref login)) => {
+                issue.get_assignees().contains(&login.as_str())
+            }
+            Some(AssigneeFilter::Unset) => issue.get_assignees().is_empty(),
+            None => true,
+        };
+
+        state
+            && milestone
+            && assignee
+            && recent
+            && self
+                .title_match
+                .as_ref()
+                .is_none_or(|re| re.is_match(&issue.title))
+            && !self
+                .title_exclude
+                .as_ref()
+                .is_some_and(|re| re.is_match(&issue.title))
+            && in_range(
+                Some(issue.get_created_at().as_str()),
+                self.created_after.as_deref(),
+                self.created_before.as_deref(),
+            )
+            && in_range(
+                issue.get_closed_at().as_deref(),
+                self.closed_after.as_deref(),
+                self.closed_before.as_deref(),
+            )
+            && self.min_comments.is_none_or(|min| issue.comments >= min)
+            && self.labels.iter().all(|l| issue.has_label(l))
+            && !self.exclude_labels.iter().any(|l| issue.has_label(l))
+    }
+}
+
+pub fn get_issues<F: FnMut()>(
+    client: &Github,
+    owner: &str,
+    repo_name: &str,
+    limit: Option<usize>,
+    query: &IssueQuery,
+    milestone: Option<&str>,
+    on_page: F,
+) -> Option<Issues> {
+    let issues_endpoint = format!(
+        "repos/{}/{}/issues?{}",
+        owner,
+        repo_name,
+        query.params(milestone).trim_start_matches('&')
+    );
+    get_pages(client, &issues_endpoint, limit, on_page)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationSubject {
+    pub title: String,
+    // API URL of the issue, ending with its number
+    pub url: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationRepository {
+    pub name: String,
+    pub full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Notification {
+    pub reason: String,
+    pub unread: bool,
+    pub updated_at: DateTime<Utc>,
+    pub subject: NotificationSubject,
+    pub repository: NotificationRepository,
+}
+
+impl Notification {
+    // Number of the issue this notification is about, if any
+    pub fn issue_number(&self) -> Option<u32> {
+        if self.subject.kind != "Issue" {
+            return None;
+        }
+
+        self.subject
+            .url
+            .as_ref()
+            .and_then(|url| url.rsplit('/').next())
+            .and_then(|n| n.parse().ok())
+    }
+}
+
+pub type Notifications = Vec<Notification>;
+
+pub fn get_issue(client: &Github, owner: &str, repo_name: &str, number: u32) -> Option<Issue> {
+    let issue_endpoint = format!("repos/{}/{}/issues/{}", owner, repo_name, number);
+    get(client, &issue_endpoint)
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchCount {
+    total_count: u32,
+}
+
+// Number of issues the issues API would return for this repository, counted by
+// the search API
+pub fn get_issue_count(
+    client: &Github,
+    owner: &str,
+    repo_name: &str,
+    query: &IssueQuery,
+    pull_requests: PullRequestFilter,
+) -> Option<u32> {
+    let mut qualifiers = vec![format!("repo:{}/{}", owner, repo_name)];
+    qualifiers.extend(query.qualifiers(pull_requests));
+    let search_endpoint = format!(
+        "search/issues?q={}&per_page=1",
+        encode(&qualifiers.join(" "))
+    );
+    get::<SearchCount>(client, &search_endpoint).map(|s| s.total_count)
+}
+
+// Unread notifications of the authenticated user
+pub fn get_notifications(client: &Github) -> Option<Notifications> {
+    get_pages(client, "notifications", None, || {})
+}
+
+pub type Milestones = Vec<Milestone>;
+
+pub fn get_milestones(client: &Github, owner: &str, repo_name: &str) -> Option<Milestones> {
+    let milestones_endpoint = format!("repos/{}/{}/milestones?state=all", owner, repo_name);
+    get_pages(client, &milestones_endpoint, None, || {})
+}
+
+pub type PullRequests = Vec<PullRequestDetails>;
+
+pub fn get_pull_requests(
+    client: &Github,
+    owner: &str,
+    repo_name: &str,
+    limit: Option<usize>,
+    state: StateFilter,
+) -> Option<PullRequests> {
+    let pulls_endpoint = format!(
+        "repos/{}/{}/pulls?state={}",
+        owner,
+        repo_name,
+        state.as_str()
+    );
+    get_pages(client, &pulls_endpoint, limit, || {})
+}
+
+pub type Comments = Vec<Comment>;
+
+pub fn get_comments(
+    client: &Github,
+    owner: &str,
+    repo_name: &str,
+    bug_id: u32,
+) -> Option<Comments> {
+    let comments_endpoint = format!("repos/{}/{}/issues/{}/comments", owner, repo_name, bug_id);
+    get_pages(client, &comments_endpoint, None, || {})
+}
+
+pub type IssueEvents = Vec<IssueEvent>;
+
+pub fn get_events(
+    client: &Github,
+    owner: &str,
+    repo_name: &str,
+    bug_id: u32,
+) -> Option<IssueEvents> {
+    let events_endpoint = format!("repos/{}/{}/issues/{}/events", owner, repo_name, bug_id);
+    get_pages(client, &events_endpoint, None, || {})
+}
+
+pub type Reviews = Vec<Review>;
+
+pub fn get_reviews(client: &Github, owner: &str, repo_name: &str, pr_id: u32) -> Option<Reviews> {
+    let reviews_endpoint = format!("repos/{}/{}/pulls/{}/reviews", owner, repo_name, pr_id);
+    get_pages(client, &reviews_endpoint, None, || {})
+}
+
+// Whether pull requests, which the API returns as issues, are exported
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PullRequestFilter {
+    Exclude,
+    Include,
+    Only,
+}
+
+impl PullRequestFilter {
+    pub fn keeps(&self, issue: &Issue) -> bool {
+        match *self {
+            PullRequestFilter::Exclude => !issue.is_pull_request(),
+            PullRequestFilter::Include => true,
+            PullRequestFilter::Only => issue.is_pull_request(),
+        }
+    }
+}
+
+fn get_component_issues(
+    client: &Github,
+    owner: &str,
+    component: &str,
+    limit: Option<usize>,
+    pull_requests: PullRequestFilter,
+    query: &IssueQuery,
+    pages: &AtomicUsize,
+) -> Vec<Issue> {
+    let milestone = match query.milestone {
+        Some(MilestoneFilter::Title(ref title)) => {
+            let milestones =
+                get_milestones(&client, owner, &component).expect("failed to get milestones");
+            match milestones.iter().find(|m| &m.title == title) {
+                Some(m) => Some(m.number.to_string()),
+                // No issue of this component can match
+                None => return Vec::new(),
+            }
+        }
+        Some(MilestoneFilter::Unset) => Some("none".to_string()),
+        None => None,
+    };
+
+    let mut component_issues: Vec<Issue> = get_issues(
+        &client,
+        owner,
+        &component,
+        limit,
+        query,
+        milestone.as_deref(),
+        || {
+            pages.fetch_add(1, atomic::Ordering::SeqCst);
+        },
+    )
+    .expect("failed to get issues")
+    .into_iter()
+    .filter(|i| pull_requests.keeps(i))
+    .take(limit.unwrap_or(usize::MAX))
+    .collect();
+
+    if pull_requests != PullRequestFilter::Exclude {
+        let mut details = get_pull_requests(&client, owner, &component, limit, query.state)
+            .expect("failed to get pull requests");
+
+        for issue in component_issues.iter_mut().filter(|i| i.is_pull_request()) {
+            if let Some(idx) = details.iter().position(|d| d.number == issue.number) {
+                issue.pull_request_details = Some(details.swap_remove(idx));
+            }
+        }
+    }
+
+    component_issues
+}
+
+// If 'limit' is set, only keep the first 'limit' issues of each component.
+// Components are fetched by up to 'jobs' threads sharing the client, so at most
+// 'jobs' requests are in flight over its connection.
+#[allow(clippy::too_many_arguments)]
+pub fn get_all_issues(
+    client: &Github,
+    owner: &str,
+    components: &[String],
+    limit: Option<usize>,
+    pull_requests: PullRequestFilter,
+    query: &IssueQuery,
+    jobs: usize,
+    quiet: bool,
+) -> Vec<Issue> {
+    let bar = progress::bar(components.len(), "components", quiet);
+    // Index of the next component to fetch, and pages and issues fetched so far
+    let next = &AtomicUsize::new(0);
+    let pages = &AtomicUsize::new(0);
+    let collected = &AtomicUsize::new(0);
+    let progress = &bar;
+
+    let mut fetched: Vec<Vec<Issue>> = components.iter().map(|_| Vec::new()).collect();
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.clamp(1, components.len().max(1)))
+            .map(|_| {
+                scope.spawn(move || {
+                    let mut done = Vec::new();
+                    loop {
+                        let idx = next.fetch_add(1, atomic::Ordering::SeqCst);
+                        let component = match components.get(idx) {
+                            Some(component) => component,
+                            None => break,
+                        };
+                        progress.set_message(format!(
+                            "{} ({} pages, {} issues)",
+                            component,
+                            pages.load(atomic::Ordering::SeqCst),
+                            collected.load(atomic::Ordering::SeqCst)
+                        ));
+
+                        let issues = get_component_issues(
+                            client,
+                            owner,
+                            component,
+                            limit,
+                            pull_requests,
+                            query,
+                            pages,
+                        );
+                        collected.fetch_add(issues.len(), atomic::Ordering::SeqCst);
+                        progress.inc(1);
+                        done.push((idx, issues));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            for (idx, issues) in worker.join().expect("fetching thread panicked") {
+                fetched[idx] = issues;
+            }
+        }
+    });
+
+    // In the order of the components, whichever thread fetched them
+    let issues: Vec<Issue> = fetched.into_iter().flatten().collect();
+    progress.finish_with_message(format!("{} issues collected", issues.len()));
+    issues
+}
+
+pub fn sort_issues(issues: Vec<Issue>) -> Vec<Issue> {
+    issues.into_iter().sorted_by(|a, b| {
+        let state_a = a.get_state();
+        let state_b = b.get_state();
+
+        // Put closed tasks last
+        match (&state_a, &state_b) {
+            (&IssueState::Closed, _) => return Ordering::Greater,
+            (_, &IssueState::Closed) => return Ordering::Less,
+            _ => {}
+        };
+
+        match (a.get_priority(), b.get_priority()) {
+            (Some(_a), None) => return Ordering::Less,
+            (None, Some(_b)) => return Ordering::Greater,
+            (Some(pa), Some(pb)) => return pa.cmp(&pb),
+            _ => {}
+        };
+
+        if state_a != state_b {
+            return state_a.cmp(&state_b);
+        }
+
+        if state_a == IssueState::Closed {
+            return b.closed_at.cmp(&a.closed_at);
+        }
+
+        let cmp = a.get_component().cmp(&b.get_component());
+        if cmp == Ordering::Less || cmp == Ordering::Greater {
+            return cmp;
+        }
+
+        let cmp = a.number.cmp(&b.number);
+        if cmp == Ordering::Less || cmp == Ordering::Greater {
+            return cmp;
+        }
+
+        Ordering::Equal
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::header::HeaderValue;
+    use serde_json;
+
+    use fixtures::issue;
+
+    fn link(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("link", HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn next_pages() {
+        assert_eq!(
+            next_page(&link(
+                "<https://api.github.com/repositories/1/issues?per_page=100&page=3>; rel=\"next\", \
+                 <https://api.github.com/repositories/1/issues?per_page=100&page=9>; rel=\"last\""
+            )),
+            Some("repositories/1/issues?per_page=100&page=3".to_string())
+        );
+        // Last page
+        assert_eq!(
+            next_page(&link(
+                "<https://api.github.com/repositories/1/issues?page=1>; rel=\"first\", \
+                 <https://api.github.com/repositories/1/issues?page=8>; rel=\"prev\""
+            )),
+            None
+        );
+        assert_eq!(next_page(&HeaderMap::new()), None);
+    }
+
+    fn query() -> IssueQuery {
+        IssueQuery {
+            state: StateFilter::Open,
+            labels: vec!["good first issue".to_string(), "bug".to_string()],
+            exclude_labels: vec!["wontfix".to_string()],
+            labels_in_api: true,
+            milestone: Some(MilestoneFilter::Title("1.0".to_string())),
+            assignee: Some(AssigneeFilter::Login("alice".to_string())),
+            title_exclude: Some(Regex::new("(?i)^wip").unwrap()),
+            ..IssueQuery::default()
+        }
+    }
+
+    #[test]
+    fn api_filters() {
+        let query = query();
+
+        assert_eq!(
+            query.params(Some("3")),
+            "&state=open&milestone=3&assignee=alice&labels=good+first+issue,bug"
+        );
+        assert_eq!(
+            query.qualifiers(PullRequestFilter::Exclude),
+            vec![
+                "is:issue",
+                "is:open",
+                "milestone:\"1.0\"",
+                "assignee:alice",
+                "label:\"good first issue\"",
+                "label:\"bug\"",
+            ]
+        );
+        assert_eq!(IssueQuery::default().params(None), "&state=all");
+    }
+
+    #[test]
+    fn matching_issues() {
+        let query = query();
+        let matching = serde_json::json!({
+            "labels": [{ "name": "bug" }, { "name": "good first issue" }],
+            "milestone": { "title": "1.0" },
+            "assignees": [{ "login": "bob" }, { "login": "alice" }],
+        });
+        let with = |field: &str, value: serde_json::Value| {
+            let mut fields = matching.clone();
+            fields[field] = value;
+            issue(1, fields)
+        };
+
+        assert!(query.matches(&issue(1, matching.clone())));
+        assert!(!query.matches(&with("title", serde_json::json!("WIP: crash"))));
+        assert!(!query.matches(&with("state", serde_json::json!("closed"))));
+        assert!(!query.matches(&with("milestone", serde_json::json!({ "title": "2.0" }))));
+        assert!(!query.matches(&with("assignees", serde_json::json!([{ "login": "bob" }]))));
+        assert!(!query.matches(&with(
+            "labels",
+            serde_json::json!([{ "name": "bug" }, { "name": "good first issue" }, { "name": "wontfix" }])
+        )));
+        assert!(!query.matches(&with("labels", serde_json::json!([{ "name": "bug" }]))));
+    }
+
+    #[test]
+    fn date_ranges() {
+        let closed = issue(
+            1,
+            serde_json::json!({ "state": "closed", "closed_at": "2024-03-01T00:00:00Z" }),
+        );
+        let since = |s: &str| IssueQuery {
+            closed_since: Some(s.parse().unwrap()),
+            ..IssueQuery::default()
+        };
+
+        assert!(since("2024-02-01T00:00:00Z").matches(&closed));
+        assert!(!since("2024-04-01T00:00:00Z").matches(&closed));
+        // Only closed issues are dropped
+        assert!(since("2024-04-01T00:00:00Z").matches(&issue(2, serde_json::Value::Null)));
+
+        // 'after' dates are included and 'before' ones excluded
+        let range = |date| in_range(date, Some("2024-01-01"), Some("2024-02-01"));
+        assert!(range(Some("2024-01-01")));
+        assert!(!range(Some("2024-02-01")));
+        assert!(!range(None));
+        assert!(in_range(None, None, None));
+    }
+}
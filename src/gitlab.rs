@@ -0,0 +1,263 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json;
+use ureq;
+
+use github::{encode, AssigneeFilter, IssueQuery, MilestoneFilter, PullRequestFilter, StateFilter};
+use issue::Issue;
+use progress;
+use source::{get_json, Source};
+
+// Maximum number of issues returned per page by the API
+const PER_PAGE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct User {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Milestone {
+    iid: u32,
+    title: String,
+    due_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitlabIssue {
+    iid: u32,
+    title: String,
+    description: Option<String>,
+    web_url: String,
+    // 'opened' or 'closed'
+    state: String,
+    author: Option<User>,
+    #[serde(default)]
+    assignees: Vec<User>,
+    milestone: Option<Milestone>,
+    #[serde(default)]
+    labels: Vec<String>,
+    user_notes_count: u32,
+    upvotes: u32,
+    created_at: DateTime<Utc>,
+    closed_at: Option<DateTime<Utc>>,
+    updated_at: DateTime<Utc>,
+}
+
+impl GitlabIssue {
+    // Same fields as the github REST API, so the issues of both sources are handled
+    // the same way
+    fn into_issue(self, repository_url: &str) -> Issue {
+        let user = |u: &User| serde_json::json!({ "login": u.username });
+
+        let json = serde_json::json!({
+            "title": self.title,
+            "body": self.description,
+            "html_url": self.web_url,
+            "number": self.iid,
+            "repository_url": repository_url,
+            "source": "gitlab",
+            "pull_request": null,
+            "user": self.author.as_ref().map(user),
+            "assignee": self.assignees.first().map(user),
+            "assignees": self.assignees.iter().map(user).collect::<Vec<_>>(),
+            "milestone": self.milestone.map(|m| serde_json::json!({
+                "number": m.iid,
+                "title": m.title,
+                "due_on": m.due_date.map(|d| format!("{}T00:00:00Z", d)),
+            })),
+            "labels": self.labels.iter().map(|l| serde_json::json!({ "name": l })).collect::<Vec<_>>(),
+            "state": if self.state == "closed" { "closed" } else { "open" },
+            "state_reason": null,
+            "author_association": null,
+            "comments": self.user_notes_count,
+            "reactions": { "+1": self.upvotes },
+            "created_at": self.created_at,
+            "closed_at": self.closed_at,
+            "updated_at": self.updated_at,
+        });
+
+        serde_json::from_value(json).expect("Failed to convert GitLab issue")
+    }
+}
+
+// Projects of a GitLab instance, named after their path such as 'group/project'
+pub struct Gitlab {
+    pub url: String,
+    pub token: String,
+    pub projects: Vec<String>,
+}
+
+impl Gitlab {
+    // Parameters of the issues API doing part of the filtering
+    fn params(&self, query: &IssueQuery) -> String {
+        let state = match query.state {
+            StateFilter::Open => "opened",
+            StateFilter::Closed => "closed",
+            StateFilter::All => "all",
+        };
+        let mut params = format!("&state={}", state);
+
+        match query.milestone {
+            Some(MilestoneFilter::Title(ref title)) => {
+                params.push_str(&format!("&milestone={}", encode(title)))
+            }
+            Some(MilestoneFilter::Unset) => params.push_str("&milestone=None"),
+            None => {}
+        }
+
+        match query.assignee {
+            Some(AssigneeFilter::Login(ref login)) => {
+                params.push_str(&format!("&assignee_username={}", encode(login)))
+            }
+            Some(AssigneeFilter::Unset) => params.push_str("&assignee_id=None"),
+            None => {}
+        }
+
+        if query.labels_in_api && !query.labels.is_empty() {
+            let labels: Vec<String> = query.labels.iter().map(|l| encode(l)).collect();
+            params.push_str(&format!("&labels={}", labels.join(",")));
+        }
+
+        params
+    }
+
+    fn get_issues(&self, project: &str, page: usize, params: &str) -> Option<Vec<GitlabIssue>> {
+        let endpoint = format!(
+            "{}/api/v4/projects/{}/issues?per_page={}&page={}{}",
+            self.url,
+            encode(project),
+            PER_PAGE,
+            page,
+            params
+        );
+
+        get_json(ureq::get(&endpoint).set("PRIVATE-TOKEN", &self.token))
+    }
+}
+
+impl Source for Gitlab {
+    // GitLab merge requests are not issues, so they are never fetched
+    fn fetch(
+        &self,
+        query: &IssueQuery,
+        limit: Option<usize>,
+        _pull_requests: PullRequestFilter,
+        quiet: bool,
+    ) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        let params = self.params(query);
+        let progress = progress::bar(self.projects.len(), "projects", quiet);
+
+        for project in self.projects.iter() {
+            progress.set_message(format!("{} ({} issues)", project, issues.len()));
+            progress.inc(1);
+
+            let repository_url = format!("{}/{}", self.url, project);
+            let limit = limit.unwrap_or(usize::MAX);
+            let mut count = 0;
+
+            for page in 1.. {
+                let page_issues = self
+                    .get_issues(project, page, &params)
+                    .expect("failed to get GitLab issues");
+                let last = page_issues.len() < PER_PAGE;
+
+                for gitlab_issue in page_issues.into_iter().take(limit - count) {
+                    let mut issue = gitlab_issue.into_issue(&repository_url);
+                    // Nested groups do not fit in the repository name
+                    issue.component = Some(project.clone());
+                    issues.push(issue);
+                    count += 1;
+                }
+                if last || count >= limit {
+                    break;
+                }
+            }
+        }
+
+        progress.finish_with_message(format!("{} GitLab issues collected", issues.len()));
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use issue::IssueStateJson;
+
+    fn gitlab() -> Gitlab {
+        Gitlab {
+            url: "https://gitlab.example.com".to_string(),
+            token: "token".to_string(),
+            projects: vec!["group/project".to_string()],
+        }
+    }
+
+    #[test]
+    fn api_filters() {
+        let query = IssueQuery {
+            state: StateFilter::Open,
+            labels: vec!["good first issue".to_string(), "bug".to_string()],
+            labels_in_api: true,
+            milestone: Some(MilestoneFilter::Title("1.0".to_string())),
+            assignee: Some(AssigneeFilter::Unset),
+            ..IssueQuery::default()
+        };
+        assert_eq!(
+            gitlab().params(&query),
+            "&state=opened&milestone=1.0&assignee_id=None&labels=good+first+issue,bug"
+        );
+
+        // The labels are filtered locally once renamed by the transforms
+        let query = IssueQuery {
+            labels: vec!["bug".to_string()],
+            milestone: Some(MilestoneFilter::Unset),
+            assignee: Some(AssigneeFilter::Login("alice".to_string())),
+            ..IssueQuery::default()
+        };
+        assert_eq!(
+            gitlab().params(&query),
+            "&state=all&milestone=None&assignee_username=alice"
+        );
+    }
+
+    #[test]
+    fn converted_issues() {
+        let gitlab_issue: GitlabIssue = serde_json::from_value(serde_json::json!({
+            "iid": 7,
+            "title": "Crash on startup",
+            "description": null,
+            "web_url": "https://gitlab.example.com/group/project/-/issues/7",
+            "state": "opened",
+            "author": { "username": "alice" },
+            "assignees": [{ "username": "bob" }, { "username": "carol" }],
+            "milestone": { "iid": 2, "title": "1.0", "due_date": "2024-03-01" },
+            "labels": ["bug"],
+            "user_notes_count": 3,
+            "upvotes": 5,
+            "created_at": "2024-01-01T00:00:00Z",
+            "closed_at": null,
+            "updated_at": "2024-01-02T00:00:00Z",
+        }))
+        .unwrap();
+        let issue = gitlab_issue.into_issue("https://gitlab.example.com/group/project");
+
+        assert_eq!(issue.number, 7);
+        assert_eq!(issue.source.as_deref(), Some("gitlab"));
+        assert_eq!(issue.state, IssueStateJson::Open);
+        assert_eq!(issue.user.unwrap().login, "alice");
+        // The first assignee is the main one
+        assert_eq!(issue.assignee.unwrap().login, "bob");
+        assert_eq!(issue.assignees.len(), 2);
+        let milestone = issue.milestone.unwrap();
+        assert_eq!(milestone.number, 2);
+        assert_eq!(
+            milestone.due_on.unwrap().to_rfc3339(),
+            "2024-03-01T00:00:00+00:00"
+        );
+        assert_eq!(issue.labels.unwrap()[0].name, "bug");
+        assert_eq!(issue.comments, 3);
+        assert_eq!(issue.reactions.unwrap().plus_one, 5);
+    }
+}
@@ -0,0 +1,356 @@
+use std::time::Instant;
+
+use client::Github;
+use github::get_json;
+use issue::{ExtraFields, Issue};
+
+// Maximum number of nodes github accepts in a single query
+const BATCH_SIZE: usize = 100;
+
+const QUERY: &str = "
+fragment fieldValues on ProjectV2Item {
+  project { title }
+  fieldValues(first: 20) {
+    nodes {
+      ... on ProjectV2ItemFieldSingleSelectValue { name field { ...fieldName } }
+      ... on ProjectV2ItemFieldTextValue { text field { ...fieldName } }
+      ... on ProjectV2ItemFieldNumberValue { number field { ...fieldName } }
+      ... on ProjectV2ItemFieldDateValue { date field { ...fieldName } }
+      ... on ProjectV2ItemFieldIterationValue { title field { ...fieldName } }
+    }
+  }
+}
+
+fragment fieldName on ProjectV2FieldCommon { name }
+
+query($ids: [ID!]!) {
+  nodes(ids: $ids) {
+    ... on Issue {
+      reactions { totalCount }
+      closedByPullRequestsReferences(first: 20) { nodes { url } }
+      projectItems(first: 20) { nodes { ...fieldValues } }
+      projectCards(first: 1) { nodes { column { name } } }
+      issueType { name }
+      parent { number repository { nameWithOwner } }
+      subIssuesSummary { total completed }
+    }
+    ... on PullRequest {
+      reactions { totalCount }
+      projectItems(first: 20) { nodes { ...fieldValues } }
+      projectCards(first: 1) { nodes { column { name } } }
+    }
+  }
+}
+";
+
+// Custom field of Projects v2 setting the status of the issues
+const STATUS_FIELD: &str = "Status";
+
+#[derive(Debug, Serialize)]
+struct Variables<'a> {
+    ids: Vec<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+struct Query<'a> {
+    query: &'static str,
+    variables: Variables<'a>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Count {
+    total_count: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Connection<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LinkedPullRequest {
+    url: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Project {
+    title: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FieldName {
+    name: String,
+}
+
+// Value of a custom field of a project item, depending on the type of the field.
+// Other types of fields, such as the built-in ones, have no field.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct FieldValue {
+    field: Option<FieldName>,
+    name: Option<String>,
+    text: Option<String>,
+    number: Option<f64>,
+    date: Option<String>,
+    title: Option<String>,
+}
+
+impl FieldValue {
+    // ('Estimate', '3')
+    fn into_pair(self) -> Option<(String, String)> {
+        let value = self
+            .name
+            .or(self.text)
+            .or(self.date)
+            .or(self.title)
+            .or_else(|| self.number.map(|n| n.to_string()))?;
+        Some((self.field?.name, value))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ProjectItem {
+    project: Project,
+    field_values: Connection<FieldValue>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ProjectColumn {
+    name: String,
+}
+
+// Card of a classic project, not in a column if the project has been archived
+#[derive(Debug, Default, Deserialize)]
+struct ProjectCard {
+    column: Option<ProjectColumn>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct IssueType {
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Repository {
+    name_with_owner: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Parent {
+    number: u32,
+    repository: Repository,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SubIssuesSummary {
+    total: u32,
+    completed: u32,
+}
+
+// Pull requests have no linked pull requests, type nor sub-issues
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Node {
+    reactions: Count,
+    closed_by_pull_requests_references: Connection<LinkedPullRequest>,
+    project_items: Connection<ProjectItem>,
+    project_cards: Connection<ProjectCard>,
+    issue_type: Option<IssueType>,
+    parent: Option<Parent>,
+    sub_issues_summary: SubIssuesSummary,
+}
+
+#[derive(Debug, Deserialize)]
+struct Nodes {
+    nodes: Vec<Option<Node>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    data: Option<Nodes>,
+}
+
+impl From<Node> for ExtraFields {
+    fn from(mut node: Node) -> ExtraFields {
+        let mut status = None;
+        let mut project_fields = Vec::new();
+        for item in node.project_items.nodes.iter_mut() {
+            for (name, value) in item
+                .field_values
+                .nodes
+                .drain(..)
+                .filter_map(FieldValue::into_pair)
+            {
+                // Issues in several projects get the status of the first one
+                if name == STATUS_FIELD {
+                    status = status.or(Some(value));
+                } else {
+                    project_fields.push((name, value));
+                }
+            }
+        }
+
+        ExtraFields {
+            reactions: node.reactions.total_count,
+            linked_prs: node
+                .closed_by_pull_requests_references
+                .nodes
+                .into_iter()
+                .map(|pr| pr.url)
+                .collect(),
+            projects: node
+                .project_items
+                .nodes
+                .into_iter()
+                .map(|item| item.project.title)
+                .collect(),
+            board_column: node
+                .project_cards
+                .nodes
+                .into_iter()
+                .find_map(|card| card.column)
+                .map(|column| column.name),
+            status,
+            project_fields,
+            issue_type: node.issue_type.map(|t| t.name),
+            parent: node
+                .parent
+                .map(|p| format!("{}#{}", p.repository.name_with_owner, p.number)),
+            sub_issues: (
+                node.sub_issues_summary.completed,
+                node.sub_issues_summary.total,
+            ),
+        }
+    }
+}
+
+fn get_nodes(client: &Github, issues: &[&mut Issue]) -> Option<Vec<Option<Node>>> {
+    let query = Query {
+        query: QUERY,
+        variables: Variables {
+            ids: issues.iter().map(|i| i.node_id.as_str()).collect(),
+        },
+    };
+
+    let start = Instant::now();
+    let response = client.post::<_, Response>("graphql", &query);
+    debug!(
+        "POST graphql for {} issues took {:?}",
+        issues.len(),
+        start.elapsed()
+    );
+    get_json(response).and_then(|r| r.data).map(|d| d.nodes)
+}
+
+// Fetch the fields only available through GraphQL, one query per batch of issues
+// instead of one request per issue.
+pub fn hydrate(client: &Github, issues: &mut [Issue]) {
+    // Issues of other sources than github have no node
+    let mut issues: Vec<&mut Issue> = issues
+        .iter_mut()
+        .filter(|i| !i.node_id.is_empty())
+        .collect();
+
+    for batch in issues.chunks_mut(BATCH_SIZE) {
+        let nodes = match get_nodes(client, batch) {
+            Some(nodes) => nodes,
+            None => {
+                warn!("Failed to fetch extra fields of {} issues", batch.len());
+                continue;
+            }
+        };
+
+        for (issue, node) in batch.iter_mut().zip(nodes.into_iter()) {
+            issue.extra_fields = node.map(ExtraFields::from);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    #[test]
+    fn extra_fields_from_nodes() {
+        let response: Response = serde_json::from_value(serde_json::json!({
+            "data": { "nodes": [
+                {
+                    "reactions": { "totalCount": 3 },
+                    "closedByPullRequestsReferences": { "nodes": [
+                        { "url": "https://github.com/owner/repo/pull/2" }
+                    ] },
+                    "projectItems": { "nodes": [
+                        {
+                            "project": { "title": "Roadmap" },
+                            "fieldValues": { "nodes": [
+                                { "name": "In progress", "field": { "name": "Status" } },
+                                { "number": 3.0, "field": { "name": "Estimate" } },
+                                { "text": "Crash" },
+                                {}
+                            ] }
+                        },
+                        {
+                            "project": { "title": "Triage" },
+                            "fieldValues": { "nodes": [
+                                { "name": "Todo", "field": { "name": "Status" } },
+                                { "date": "2024-03-01", "field": { "name": "Due" } }
+                            ] }
+                        }
+                    ] },
+                    "projectCards": { "nodes": [
+                        { "column": null },
+                        { "column": { "name": "Done" } }
+                    ] },
+                    "issueType": { "name": "Bug" },
+                    "parent": { "number": 12, "repository": { "nameWithOwner": "owner/repo" } },
+                    "subIssuesSummary": { "total": 7, "completed": 3 }
+                },
+                // Pull request
+                {
+                    "reactions": { "totalCount": 0 },
+                    "projectItems": { "nodes": [] },
+                    "projectCards": { "nodes": [] }
+                },
+                // Deleted issue
+                null
+            ] }
+        }))
+        .unwrap();
+        let mut nodes = response.data.unwrap().nodes.into_iter();
+
+        let issue = ExtraFields::from(nodes.next().unwrap().unwrap());
+        assert_eq!(issue.reactions, 3);
+        assert_eq!(
+            issue.linked_prs,
+            vec!["https://github.com/owner/repo/pull/2"]
+        );
+        assert_eq!(issue.projects, vec!["Roadmap", "Triage"]);
+        assert_eq!(issue.board_column.as_deref(), Some("Done"));
+        // Status of the first project
+        assert_eq!(issue.status.as_deref(), Some("In progress"));
+        assert_eq!(
+            issue.project_fields,
+            vec![
+                ("Estimate".to_string(), "3".to_string()),
+                ("Due".to_string(), "2024-03-01".to_string()),
+            ]
+        );
+        assert_eq!(issue.issue_type.as_deref(), Some("Bug"));
+        assert_eq!(issue.parent.as_deref(), Some("owner/repo#12"));
+        assert_eq!(issue.sub_issues, (3, 7));
+
+        let pr = ExtraFields::from(nodes.next().unwrap().unwrap());
+        assert!(pr.linked_prs.is_empty());
+        assert!(pr.projects.is_empty());
+        assert_eq!(pr.board_column, None);
+        assert_eq!(pr.issue_type, None);
+        assert_eq!(pr.sub_issues, (0, 0));
+
+        assert!(nodes.next().unwrap().is_none());
+    }
+}
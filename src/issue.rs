@@ -0,0 +1,925 @@
+use chrono::{DateTime, Utc};
+use url::Url;
+
+use std::collections::HashMap;
+
+use calendar::Calendar;
+use client::Github;
+use column::Column;
+use date;
+use github::{get_comments, get_events, get_reviews, Comments, IssueEvents};
+use markdown::{self, Relation};
+use output::Options;
+
+// Labels setting the state of open issues
+const UNDER_REVIEW_LABEL: &str = "under review";
+const BLOCKED_LABEL: &str = "blocked";
+const STATE_LABELS: &[&str] = &[UNDER_REVIEW_LABEL, BLOCKED_LABEL];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub url: String,
+    pub html_url: String,
+    pub diff_url: String,
+    pub patch_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Assignee {
+    pub login: String,
+}
+
+// Summary of the reactions returned by the REST API
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Reactions {
+    #[serde(rename = "+1")]
+    pub plus_one: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Milestone {
+    // Not set in issue events
+    #[serde(default)]
+    pub number: u32,
+    pub title: String,
+    #[serde(default)]
+    pub due_on: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Branch {
+    #[serde(rename = "ref")]
+    pub name: String,
+}
+
+// Fields only returned by the pull requests endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PullRequestDetails {
+    pub number: u32,
+    pub draft: Option<bool>,
+    pub head: Branch,
+    pub base: Branch,
+    pub requested_reviewers: Vec<Assignee>,
+}
+
+// Fields only available through the GraphQL API
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExtraFields {
+    pub reactions: u32,
+    // URLs of the pull requests closing the issue
+    pub linked_prs: Vec<String>,
+    // Titles of the projects the issue is part of
+    pub projects: Vec<String>,
+    // Column of the classic project board the issue is on
+    pub board_column: Option<String>,
+    // 'Status' field of the Projects v2 the issue is part of
+    pub status: Option<String>,
+    // Other custom fields of the Projects v2: name -> value
+    pub project_fields: Vec<(String, String)>,
+    // Native type of the issue, such as 'Bug' or 'Feature'
+    pub issue_type: Option<String>,
+    // Parent issue, as 'owner/repo#42'
+    pub parent: Option<String>,
+    // Number of closed and total sub-issues
+    pub sub_issues: (u32, u32),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Label {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub enum IssueStateJson {
+    #[serde(rename = "open")]
+    Open,
+    #[serde(rename = "closed")]
+    Closed,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueState {
+    Blocked,
+    #[serde(rename = "under review")]
+    UnderReview,
+    Open,
+    Closed,
+}
+
+// Review state of a pull request, from the latest review of each reviewer
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ReviewStatus {
+    Approved,
+    ChangesRequested,
+    Pending,
+}
+
+impl ReviewStatus {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ReviewStatus::Approved => "approved",
+            ReviewStatus::ChangesRequested => "changes requested",
+            ReviewStatus::Pending => "review pending",
+        }
+    }
+}
+
+// How a closed issue has been resolved
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Resolution {
+    Fixed,
+    Duplicate,
+    WontFix,
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Issue {
+    pub title: String,
+    pub body: Option<String>,
+    pub html_url: String,
+    #[serde(default)]
+    pub node_id: String,
+    pub number: u32,
+    pub repository_url: String,
+    // Tracker of the issues not fetched from github, such as 'gitlab'
+    #[serde(default)]
+    pub source: Option<String>,
+    pub pull_request: Option<PullRequest>,
+    // Author of the issue, missing from old snapshots
+    #[serde(default)]
+    pub user: Option<Assignee>,
+    pub assignee: Option<Assignee>,
+    #[serde(default)]
+    pub assignees: Vec<Assignee>,
+    pub milestone: Option<Milestone>,
+    pub labels: Option<Vec<Label>>,
+    pub state: IssueStateJson,
+    pub state_reason: Option<String>,
+    // Relation of the author with the repository: OWNER, MEMBER, CONTRIBUTOR, NONE...
+    pub author_association: Option<String>,
+    // Number of comments, missing from old snapshots
+    #[serde(default)]
+    pub comments: u32,
+    #[serde(default)]
+    pub reactions: Option<Reactions>,
+    pub created_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    // Computed by the 'compute-score' transform
+    #[serde(skip_deserializing)]
+    pub score: Option<f64>,
+    // Found by the 'urgent-keywords' transform
+    #[serde(skip_deserializing)]
+    pub urgent_keywords: Vec<String>,
+    // Set from the labels by the 'priorities' transform
+    #[serde(skip_deserializing)]
+    pub priority: Option<u32>,
+    // Set from the labels by the 'severities' transform
+    #[serde(skip_deserializing)]
+    pub severity: Option<String>,
+    // Set from the labels by the 'estimates' transform
+    #[serde(skip_deserializing)]
+    pub estimate: Option<f64>,
+    // Seconds between the creation and the first comment of a maintainer, only
+    // fetched if needed
+    #[serde(skip_deserializing)]
+    pub first_response: Option<i64>,
+    // Set from the column of the project board once hydrated
+    #[serde(skip)]
+    pub board_state: Option<IssueState>,
+    // Only set if stale issues are looked for
+    #[serde(skip)]
+    pub stale: Option<bool>,
+    // Times of the first and last snapshots including the issue, only set if
+    // exported
+    #[serde(skip)]
+    pub seen: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    // Set from the labels or the repository by the components transforms
+    #[serde(skip_deserializing)]
+    pub component: Option<String>,
+    // Labels used to set the priority, severity or estimate
+    #[serde(skip_deserializing)]
+    pub consumed_labels: Vec<String>,
+    // Only fetched when exporting pull requests
+    #[serde(skip_deserializing)]
+    pub pull_request_details: Option<PullRequestDetails>,
+    // Only fetched when exporting one of the GraphQL columns
+    #[serde(skip_deserializing)]
+    pub extra_fields: Option<ExtraFields>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IssueCSV<'a> {
+    pub component: String,
+    #[serde(skip)]
+    pub number: u32,
+    // 'owner/repo#42'
+    #[serde(skip)]
+    pub reference: String,
+    pub id: String,
+    pub title: &'a str,
+    pub state: String,
+    pub assignee: Option<String>,
+    pub milestone: Option<&'a str>,
+    pub milestone_due: Option<String>,
+    // Open issue whose milestone is past its due date
+    pub overdue: bool,
+    pub priority: Option<u32>,
+    // Rendered with the date format and timezone of the options
+    pub created_at: String,
+    pub closed_at: Option<String>,
+    // Link to the issue, rewritten by the URL template if any
+    pub url: String,
+    #[serde(skip)]
+    pub html_url: &'a str,
+    pub updated_at: String,
+    // Dates before rendering, for the formats having their own date type
+    #[serde(skip)]
+    pub dates: Dates,
+    pub last_comment: String,
+    pub score: Option<f64>,
+    pub urgent_keywords: &'a [String],
+    pub draft: Option<bool>,
+    pub base_branch: Option<&'a str>,
+    pub head_branch: Option<&'a str>,
+    pub requested_reviewers: Vec<&'a str>,
+    pub review_status: Option<&'static str>,
+    pub milestone_changes: Option<u32>,
+    pub original_milestone: Option<String>,
+    pub reactions: Option<u32>,
+    pub linked_prs: Vec<&'a str>,
+    pub projects: Vec<&'a str>,
+    pub board_column: Option<&'a str>,
+    pub status: Option<&'a str>,
+    // Exported as the 'type' column
+    #[serde(rename = "type")]
+    pub issue_type: Option<&'a str>,
+    pub parent: Option<&'a str>,
+    // Closed and total sub-issues, such as '3/7'. Empty without sub-issues.
+    pub children_progress: Option<String>,
+    // 'Estimate: 3, Team: audio'
+    pub project_fields: String,
+    pub severity: Option<&'a str>,
+    pub estimate: Option<f64>,
+    pub comments: u32,
+    pub first_response_hours: Option<f64>,
+    // Only set if the bodies are exported
+    pub body: Option<String>,
+    // Checked and total items of the task lists, such as '3/7'. Empty without
+    // task lists.
+    pub progress: Option<String>,
+    // Issues referenced from the body, as 'owner/repo#42'
+    pub blocks: Vec<String>,
+    pub blocked_by: Vec<String>,
+    pub closes: Vec<String>,
+    // Labels not already exported as the priority, severity, estimate or state
+    pub labels: String,
+    #[serde(skip)]
+    pub other_labels: Vec<&'a str>,
+    // '+1' reactions
+    pub votes: u32,
+    pub reporter: Option<&'a str>,
+    // None if the relation of the reporter with the repository is unknown
+    pub external: Option<bool>,
+    // Only for open issues
+    pub age_days: Option<i64>,
+    // Only set if stale issues are looked for
+    pub stale: Option<bool>,
+    // Time since the creation, closing and last update, such as '3 days'
+    pub opened_ago: String,
+    pub closed_ago: Option<String>,
+    pub updated_ago: String,
+    // Times of the first and last snapshots including the issue
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Dates {
+    pub created: DateTime<Utc>,
+    pub closed: Option<DateTime<Utc>>,
+    pub updated: DateTime<Utc>,
+    pub milestone_due: Option<DateTime<Utc>>,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Comment {
+    pub body: String,
+    #[serde(default)]
+    pub user: Option<Assignee>,
+    #[serde(default)]
+    pub author_association: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Review {
+    // Not set if the reviewer deleted their account
+    pub user: Option<Assignee>,
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IssueEvent {
+    pub event: String,
+    // Set for 'milestoned' and 'demilestoned' events
+    pub milestone: Option<Milestone>,
+    // Set for 'labeled' and 'unlabeled' events
+    #[serde(default)]
+    pub label: Option<Label>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub struct MilestoneHistory {
+    // Number of times the milestone has been changed or removed once set
+    pub changes: u32,
+    // First milestone the issue has been assigned to
+    pub original: Option<String>,
+}
+
+// Relations with the repository of the people maintaining it, as opposed to
+// external contributors and users
+fn is_maintainer(author_association: &str) -> bool {
+    matches!(author_association, "OWNER" | "MEMBER" | "COLLABORATOR")
+}
+
+// 'yyyy-mm-dd' in UTC
+fn day(date: &DateTime<Utc>) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+impl Issue {
+    pub fn is_pull_request(&self) -> bool {
+        self.pull_request.is_some()
+    }
+
+    // 'owner/repo#42'
+    pub fn get_reference(&self) -> String {
+        format!("{}/{}#{}", self.get_owner(), self.get_repo(), self.number)
+    }
+
+    // Logical component of the issue, its repository unless mapped to another name
+    pub fn get_component(&self) -> String {
+        self.component.clone().unwrap_or_else(|| self.get_repo())
+    }
+
+    pub fn get_repo(&self) -> String {
+        let url = Url::parse(&self.repository_url).expect("Failed to parse repo URL");
+        let path_segments = url
+            .path_segments()
+            .expect("Failed to extract path segments");
+
+        path_segments
+            .last()
+            .expect("missing path segment")
+            .to_string()
+    }
+
+    pub fn get_owner(&self) -> String {
+        let url = Url::parse(&self.repository_url).expect("Failed to parse repo URL");
+        let path_segments: Vec<&str> = url
+            .path_segments()
+            .expect("Failed to extract path segments")
+            .collect();
+
+        path_segments[path_segments.len() - 2].to_string()
+    }
+
+    // Only fetch the last comment and review status if they are exported
+    pub fn csv(&self, client: &Github, options: &Options) -> IssueCSV {
+        let history = if options.columns.contains(&Column::MilestoneChanges)
+            || options.columns.contains(&Column::OriginalMilestone)
+        {
+            Some(self.get_milestone_history(client))
+        } else {
+            None
+        };
+
+        let now = Utc::now();
+
+        IssueCSV {
+            component: self.get_component(),
+            number: self.number,
+            reference: self.get_reference(),
+            id: format!("#{}", self.number),
+            title: &self.title,
+            state: self.get_state_str(),
+            assignee: if options.primary_assignee_only {
+                self.assignee.as_ref().map(|a| a.login.clone())
+            } else {
+                let assignees = self.get_assignees();
+                if assignees.is_empty() {
+                    None
+                } else {
+                    Some(assignees.join(&options.assignee_separator))
+                }
+            },
+            milestone: {
+                match self.milestone {
+                    Some(ref m) => Some(&m.title),
+                    None => None,
+                }
+            },
+            milestone_due: self.get_milestone_due().map(|d| options.format_date(&d)),
+            overdue: self.is_overdue(now),
+            priority: self.get_priority(),
+            created_at: options.format_date(&self.created_at),
+            closed_at: self.closed_at.as_ref().map(|d| options.format_date(d)),
+            url: self.get_url(options.url_template.as_deref()),
+            html_url: &self.html_url,
+            updated_at: options.format_date(&self.updated_at),
+            dates: Dates {
+                created: self.created_at,
+                closed: self.closed_at,
+                updated: self.updated_at,
+                milestone_due: self.get_milestone_due(),
+                first_seen: self.seen.map(|(first, _)| first),
+                last_seen: self.seen.map(|(_, last)| last),
+            },
+            last_comment: if options.columns.contains(&Column::LastComment) {
+                self.get_last_comment(client)
+            } else {
+                String::new()
+            },
+            score: self.score,
+            urgent_keywords: &self.urgent_keywords,
+            draft: self.pull_request_details.as_ref().and_then(|d| d.draft),
+            base_branch: self
+                .pull_request_details
+                .as_ref()
+                .map(|d| d.base.name.as_str()),
+            head_branch: self
+                .pull_request_details
+                .as_ref()
+                .map(|d| d.head.name.as_str()),
+            requested_reviewers: match self.pull_request_details {
+                Some(ref d) => d
+                    .requested_reviewers
+                    .iter()
+                    .map(|r| r.login.as_str())
+                    .collect(),
+                None => Vec::new(),
+            },
+            review_status: if options.columns.contains(&Column::ReviewStatus) {
+                self.get_review_status(client).map(|s| s.as_str())
+            } else {
+                None
+            },
+            milestone_changes: history.as_ref().map(|h| h.changes),
+            original_milestone: history.and_then(|h| h.original),
+            reactions: self.extra_fields.as_ref().map(|e| e.reactions),
+            linked_prs: match self.extra_fields {
+                Some(ref e) => e.linked_prs.iter().map(|p| p.as_str()).collect(),
+                None => Vec::new(),
+            },
+            projects: match self.extra_fields {
+                Some(ref e) => e.projects.iter().map(|p| p.as_str()).collect(),
+                None => Vec::new(),
+            },
+            board_column: self.get_board_column(),
+            status: self.get_status(),
+            issue_type: self.get_type(),
+            parent: self.extra_fields.as_ref().and_then(|e| e.parent.as_deref()),
+            children_progress: match self.extra_fields {
+                Some(ExtraFields {
+                    sub_issues: (completed, total),
+                    ..
+                }) if total > 0 => Some(format!("{}/{}", completed, total)),
+                _ => None,
+            },
+            project_fields: match self.extra_fields {
+                Some(ref e) => e
+                    .project_fields
+                    .iter()
+                    .map(|&(ref name, ref value)| format!("{}: {}", name, value))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                None => String::new(),
+            },
+            severity: self.severity.as_deref(),
+            estimate: self.estimate,
+            comments: self.comments,
+            first_response_hours: self.first_response.map(|s| s as f64 / 3600.0),
+            body: if options.columns.contains(&Column::Body) {
+                Some(markdown::excerpt(
+                    self.body.as_deref().unwrap_or(""),
+                    options.body_length,
+                ))
+            } else {
+                None
+            },
+            progress: self
+                .get_task_progress()
+                .map(|(done, total)| format!("{}/{}", done, total)),
+            blocks: self.get_references(Relation::Blocks),
+            blocked_by: self.get_references(Relation::BlockedBy),
+            closes: self.get_references(Relation::Closes),
+            labels: self.get_other_labels().join(&options.label_separator),
+            other_labels: self.get_other_labels(),
+            votes: self.get_votes(),
+            reporter: self.get_reporter(),
+            external: self.is_external(),
+            age_days: self.get_age_days(options.calendar.as_ref()),
+            stale: options.stale_since.map(|since| self.is_stale(since)),
+            opened_ago: date::humanize((now - self.created_at).num_seconds()),
+            closed_ago: self
+                .closed_at
+                .map(|d| date::humanize((now - d).num_seconds())),
+            updated_ago: date::humanize((now - self.updated_at).num_seconds()),
+            first_seen: self.seen.map(|(first, _)| options.format_date(&first)),
+            last_seen: self.seen.map(|(_, last)| options.format_date(&last)),
+        }
+    }
+
+    // Template placeholders: {url}, {path}, {owner}, {repo} and {number}
+    pub fn get_url(&self, template: Option<&str>) -> String {
+        let template = match template {
+            Some(t) => t,
+            None => return self.html_url.clone(),
+        };
+        let path = Url::parse(&self.html_url)
+            .map(|u| u.path().to_string())
+            .unwrap_or_default();
+
+        template
+            .replace("{url}", &self.html_url)
+            .replace("{path}", &path)
+            .replace("{owner}", &self.get_owner())
+            .replace("{repo}", &self.get_repo())
+            .replace("{number}", &self.number.to_string())
+    }
+
+    // None if the body has no task list
+    pub fn get_task_progress(&self) -> Option<(usize, usize)> {
+        let (done, total) = markdown::task_progress(self.body.as_deref().unwrap_or(""));
+        if total == 0 {
+            None
+        } else {
+            Some((done, total))
+        }
+    }
+
+    // Issues referenced with this relation, '#42' being resolved in the repository
+    // of the issue
+    pub fn get_references(&self, relation: Relation) -> Vec<String> {
+        markdown::references(self.body.as_deref().unwrap_or(""))
+            .into_iter()
+            .filter(|&(r, _)| r == relation)
+            .map(|(_, reference)| {
+                let repo = reference
+                    .repo
+                    .unwrap_or_else(|| format!("{}/{}", self.get_owner(), self.get_repo()));
+                format!("{}#{}", repo, reference.number)
+            })
+            .collect()
+    }
+
+    pub fn get_other_labels(&self) -> Vec<&str> {
+        match self.labels {
+            Some(ref labels) => labels
+                .iter()
+                .map(|l| l.name.as_str())
+                .filter(|l| {
+                    !STATE_LABELS.contains(l) && !self.consumed_labels.iter().any(|c| c == l)
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn get_votes(&self) -> u32 {
+        self.reactions.as_ref().map_or(0, |r| r.plus_one)
+    }
+
+    pub fn get_reporter(&self) -> Option<&str> {
+        self.user.as_ref().map(|u| u.login.as_str())
+    }
+
+    // Whether the issue has been filed by someone not maintaining the repository
+    pub fn is_external(&self) -> Option<bool> {
+        self.author_association.as_ref().map(|a| !is_maintainer(a))
+    }
+
+    pub fn get_milestone_due(&self) -> Option<DateTime<Utc>> {
+        self.milestone.as_ref().and_then(|m| m.due_on)
+    }
+
+    // Open issue not updated since 'since'
+    pub fn is_stale(&self, since: DateTime<Utc>) -> bool {
+        self.state == IssueStateJson::Open && self.updated_at < since
+    }
+
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.state == IssueStateJson::Open && self.get_milestone_due().is_some_and(|d| d < now)
+    }
+
+    // All the assignees, the primary one first
+    pub fn get_assignees(&self) -> Vec<&str> {
+        if self.assignees.is_empty() {
+            self.assignee.iter().map(|a| a.login.as_str()).collect()
+        } else {
+            self.assignees.iter().map(|a| a.login.as_str()).collect()
+        }
+    }
+
+    pub fn get_priority(&self) -> Option<u32> {
+        self.priority
+    }
+
+    pub fn get_state(&self) -> IssueState {
+        if self.state == IssueStateJson::Closed {
+            return IssueState::Closed;
+        }
+
+        if let Some(state) = self.board_state {
+            return state;
+        }
+
+        if let Some(labels) = self.labels.as_ref() {
+            if labels.iter().any(|l| l.name == UNDER_REVIEW_LABEL) {
+                return IssueState::UnderReview;
+            } else if labels.iter().any(|l| l.name == BLOCKED_LABEL) {
+                return IssueState::Blocked;
+            }
+        }
+
+        IssueState::Open
+    }
+
+    pub fn get_board_column(&self) -> Option<&str> {
+        self.extra_fields
+            .as_ref()
+            .and_then(|e| e.board_column.as_deref())
+    }
+
+    pub fn get_type(&self) -> Option<&str> {
+        self.extra_fields
+            .as_ref()
+            .and_then(|e| e.issue_type.as_deref())
+    }
+
+    pub fn get_status(&self) -> Option<&str> {
+        self.extra_fields.as_ref().and_then(|e| e.status.as_deref())
+    }
+
+    // 'states': column of the project board -> state of the issues in it
+    pub fn set_board_state(&mut self, states: &HashMap<String, IssueState>) {
+        self.board_state = self.get_board_column().and_then(|c| states.get(c)).copied();
+    }
+
+    pub fn has_label(&self, name: &str) -> bool {
+        match self.labels {
+            Some(ref labels) => labels.iter().any(|l| l.name == name),
+            None => false,
+        }
+    }
+
+    pub fn get_resolution(&self) -> Option<Resolution> {
+        if self.state != IssueStateJson::Closed {
+            return None;
+        }
+
+        let reason = self.state_reason.as_deref();
+
+        if self.has_label("duplicate") || reason == Some("duplicate") {
+            Some(Resolution::Duplicate)
+        } else if self.has_label("wontfix") || reason == Some("not_planned") {
+            Some(Resolution::WontFix)
+        } else if self.has_label("fixed") || reason == Some("completed") {
+            Some(Resolution::Fixed)
+        } else {
+            Some(Resolution::Other)
+        }
+    }
+
+    pub fn get_state_str(&self) -> String {
+        match self.get_state() {
+            IssueState::Open => "open".to_string(),
+            IssueState::Closed => "closed".to_string(),
+            IssueState::Blocked => "blocked".to_string(),
+            IssueState::UnderReview => "under review".to_string(),
+        }
+    }
+
+    // Dates are compared as 'yyyy-mm-dd' strings by the filters and reports
+    pub fn get_created_at(&self) -> String {
+        day(&self.created_at)
+    }
+
+    // Days since the creation of open issues, only counting business days if a
+    // calendar is given
+    pub fn get_age_days(&self, calendar: Option<&Calendar>) -> Option<i64> {
+        if self.state == IssueStateJson::Closed {
+            return None;
+        }
+
+        let today = Utc::now().date_naive();
+        if let Some(calendar) = calendar {
+            return Some(calendar.business_days(self.created_at.date_naive(), today));
+        }
+
+        Some((today - self.created_at.date_naive()).num_days())
+    }
+
+    pub fn get_updated_at(&self) -> String {
+        day(&self.updated_at)
+    }
+
+    pub fn get_closed_at(&self) -> Option<String> {
+        self.closed_at.as_ref().map(day)
+    }
+
+    pub fn get_review_status(&self, client: &Github) -> Option<ReviewStatus> {
+        if !self.is_pull_request() {
+            return None;
+        }
+
+        let reviews = get_reviews(client, &self.get_owner(), &self.get_repo(), self.number)
+            .unwrap_or_default();
+
+        // Reviews are listed chronologically, only keep the latest one of each reviewer.
+        // Comments neither approve nor request changes.
+        let mut latest: HashMap<&str, &str> = HashMap::new();
+        for review in reviews.iter() {
+            if let Some(ref user) = review.user {
+                match review.state.as_str() {
+                    "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED" => {
+                        latest.insert(&user.login, &review.state);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if latest.values().any(|s| *s == "CHANGES_REQUESTED") {
+            Some(ReviewStatus::ChangesRequested)
+        } else if latest.values().any(|s| *s == "APPROVED") {
+            Some(ReviewStatus::Approved)
+        } else {
+            Some(ReviewStatus::Pending)
+        }
+    }
+
+    // Events and comments can only be fetched for the issues of github
+    fn fetch_events(&self, client: &Github) -> Option<IssueEvents> {
+        if self.source.is_some() {
+            return None;
+        }
+        get_events(client, &self.get_owner(), &self.get_repo(), self.number)
+    }
+
+    pub fn fetch_comments(&self, client: &Github) -> Option<Comments> {
+        if self.source.is_some() {
+            return None;
+        }
+        get_comments(client, &self.get_owner(), &self.get_repo(), self.number)
+    }
+
+    pub fn get_milestone_history(&self, client: &Github) -> MilestoneHistory {
+        let events = self.fetch_events(client).unwrap_or_default();
+
+        let changes = events.iter().filter(|e| e.event == "demilestoned").count() as u32;
+        let original = events
+            .iter()
+            .find(|e| e.event == "milestoned")
+            .and_then(|e| e.milestone.as_ref())
+            .or_else(|| self.milestone.as_ref())
+            .map(|m| m.title.clone());
+
+        MilestoneHistory { changes, original }
+    }
+
+    // Changes of the state of the issue since its creation, as 'yyyy-mm-dd' dates.
+    // Only the creation and closing are known if the events cannot be fetched.
+    pub fn get_state_history(&self, client: &Github) -> Vec<(String, IssueState)> {
+        let mut history = vec![(self.get_created_at(), IssueState::Open)];
+
+        let events = match self.fetch_events(client) {
+            Some(events) => events,
+            None => {
+                if let Some(closed_at) = self.get_closed_at() {
+                    history.push((closed_at, IssueState::Closed));
+                }
+                return history;
+            }
+        };
+
+        let mut closed = false;
+        let mut blocked = false;
+        let mut under_review = false;
+
+        for event in events.iter() {
+            let label = event.label.as_ref().map(|l| l.name.as_str());
+            match (event.event.as_str(), label) {
+                ("closed", _) => closed = true,
+                ("reopened", _) => closed = false,
+                ("labeled", Some("blocked")) => blocked = true,
+                ("unlabeled", Some("blocked")) => blocked = false,
+                ("labeled", Some("under review")) => under_review = true,
+                ("unlabeled", Some("under review")) => under_review = false,
+                _ => continue,
+            }
+
+            // Same precedence as get_state()
+            let state = if closed {
+                IssueState::Closed
+            } else if under_review {
+                IssueState::UnderReview
+            } else if blocked {
+                IssueState::Blocked
+            } else {
+                IssueState::Open
+            };
+            history.push((day(&event.created_at), state));
+        }
+
+        history
+    }
+
+    // Owners, members and collaborators of the repository answering the issue,
+    // other than its reporter
+    pub fn set_first_response(&mut self, client: &Github) {
+        let comments = self.fetch_comments(client).unwrap_or_default();
+        let reporter = self.get_reporter().map(|r| r.to_string());
+
+        self.first_response = comments
+            .iter()
+            .filter(|c| c.author_association.as_deref().is_some_and(is_maintainer))
+            .filter(|c| c.user.as_ref().map(|u| &u.login) != reporter.as_ref())
+            .find_map(|c| c.created_at)
+            .map(|answered| (answered - self.created_at).num_seconds());
+    }
+
+    pub fn get_last_comment(&self, client: &Github) -> String {
+        let comments = self.fetch_comments(client).unwrap_or_default();
+
+        match comments.last() {
+            Some(last) => last.body.to_string(),
+            None => "".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    fn issue(author_association: Option<&str>) -> Issue {
+        serde_json::from_value(serde_json::json!({
+            "title": "Crash",
+            "html_url": "https://github.com/owner/repo/issues/1",
+            "number": 1,
+            "repository_url": "https://api.github.com/repos/owner/repo",
+            "labels": [],
+            "state": "open",
+            "author_association": author_association,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn external_reporters() {
+        // The same people whose comments are first responses
+        for association in ["OWNER", "MEMBER", "COLLABORATOR"] {
+            assert!(is_maintainer(association));
+            assert_eq!(issue(Some(association)).is_external(), Some(false));
+        }
+        for association in ["CONTRIBUTOR", "FIRST_TIME_CONTRIBUTOR", "NONE"] {
+            assert!(!is_maintainer(association));
+            assert_eq!(issue(Some(association)).is_external(), Some(true));
+        }
+        assert_eq!(issue(None).is_external(), None);
+    }
+
+    #[test]
+    fn url_templates() {
+        let issue = issue(None);
+
+        assert_eq!(
+            issue.get_url(None),
+            "https://github.com/owner/repo/issues/1"
+        );
+        assert_eq!(
+            issue.get_url(Some("https://proxy.example/?u={url}")),
+            "https://proxy.example/?u=https://github.com/owner/repo/issues/1"
+        );
+        assert_eq!(
+            issue.get_url(Some("https://ghe.example{path}")),
+            "https://ghe.example/owner/repo/issues/1"
+        );
+        assert_eq!(
+            issue.get_url(Some("https://tracker.example/{owner}/{repo}/{number}")),
+            "https://tracker.example/owner/repo/1"
+        );
+    }
+}
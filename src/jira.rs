@@ -0,0 +1,314 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json;
+use ureq;
+
+use std::collections::HashMap;
+
+use github::{encode, IssueQuery, PullRequestFilter, StateFilter};
+use issue::{ExtraFields, Issue};
+use progress;
+use source::{get_json, Source};
+
+// Maximum number of issues returned per page by the API
+const PER_PAGE: usize = 100;
+
+const FIELDS: &str =
+    "summary,description,status,priority,assignee,reporter,labels,issuetype,fixVersions,created,updated,resolutiondate";
+
+// Jira priorities, from the most urgent, exported as the 'P0' to 'P4' labels
+const PRIORITIES: &[&str] = &["Highest", "High", "Medium", "Low", "Lowest"];
+
+#[derive(Debug, Deserialize)]
+struct Named {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusCategory {
+    // 'new', 'indeterminate' or 'done'
+    key: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Status {
+    name: String,
+    status_category: StatusCategory,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct User {
+    // Jira Cloud does not expose user names
+    display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Version {
+    name: String,
+    release_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Fields {
+    summary: String,
+    description: Option<String>,
+    status: Status,
+    priority: Option<Named>,
+    assignee: Option<User>,
+    reporter: Option<User>,
+    #[serde(default)]
+    labels: Vec<String>,
+    issuetype: Option<Named>,
+    #[serde(default)]
+    fix_versions: Vec<Version>,
+    // Such as '2024-03-01T10:12:42.000+0100'
+    created: String,
+    updated: String,
+    resolutiondate: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JiraIssue {
+    // 'PROJ-42'
+    key: String,
+    fields: Fields,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResults {
+    issues: Vec<JiraIssue>,
+    next_page_token: Option<String>,
+}
+
+fn parse_date(date: &str) -> DateTime<Utc> {
+    DateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S%.f%z")
+        .expect("Failed to parse Jira date")
+        .with_timezone(&Utc)
+}
+
+impl JiraIssue {
+    // Same fields as the github REST API, so the issues of both sources are handled
+    // the same way. The project is the component, the first fix version the
+    // milestone and the status the board column.
+    fn into_issue(self, url: &str) -> Issue {
+        let (project, number) = match self.key.rfind('-') {
+            Some(idx) => (&self.key[..idx], self.key[idx + 1..].parse().unwrap_or(0)),
+            None => (self.key.as_str(), 0),
+        };
+        let fields = self.fields;
+        let user = |u: &User| serde_json::json!({ "login": u.display_name });
+
+        let mut labels = fields.labels;
+        if let Some(p) = fields
+            .priority
+            .as_ref()
+            .and_then(|p| PRIORITIES.iter().position(|n| *n == p.name))
+        {
+            labels.push(format!("P{}", p));
+        }
+
+        let json = serde_json::json!({
+            "title": fields.summary,
+            "body": fields.description,
+            "html_url": format!("{}/browse/{}", url, self.key),
+            "number": number,
+            "repository_url": format!("{}/projects/{}", url, project),
+            "source": "jira",
+            "pull_request": null,
+            "user": fields.reporter.as_ref().map(user),
+            "assignee": fields.assignee.as_ref().map(user),
+            "assignees": fields.assignee.as_ref().map(user).into_iter().collect::<Vec<_>>(),
+            "milestone": fields.fix_versions.first().map(|v| serde_json::json!({
+                "title": v.name,
+                "due_on": v.release_date.map(|d| format!("{}T00:00:00Z", d)),
+            })),
+            "labels": labels.iter().map(|l| serde_json::json!({ "name": l })).collect::<Vec<_>>(),
+            "state": if fields.status.status_category.key == "done" { "closed" } else { "open" },
+            "state_reason": null,
+            "author_association": null,
+            "created_at": parse_date(&fields.created),
+            "closed_at": fields.resolutiondate.as_deref().map(parse_date),
+            "updated_at": parse_date(&fields.updated),
+        });
+
+        let mut issue: Issue = serde_json::from_value(json).expect("Failed to convert Jira issue");
+        issue.component = Some(project.to_string());
+        issue.extra_fields = Some(ExtraFields {
+            board_column: Some(fields.status.name.clone()),
+            status: Some(fields.status.name),
+            issue_type: fields.issuetype.map(|t| t.name),
+            ..Default::default()
+        });
+        issue
+    }
+}
+
+// Issues of a Jira Cloud site matching a JQL query
+pub struct Jira {
+    pub url: String,
+    // Email of the owner of the API token
+    pub user: String,
+    pub token: String,
+    pub jql: String,
+}
+
+impl Jira {
+    // The query restricted to the state and labels of the query
+    fn jql(&self, query: &IssueQuery) -> String {
+        let mut jql = format!("({})", self.jql);
+
+        match query.state {
+            StateFilter::Open => jql.push_str(" AND statusCategory != Done"),
+            StateFilter::Closed => jql.push_str(" AND statusCategory = Done"),
+            StateFilter::All => {}
+        }
+        if query.labels_in_api {
+            for label in query.labels.iter() {
+                jql.push_str(&format!(" AND labels = \"{}\"", label.replace('"', "\\\"")));
+            }
+        }
+
+        jql
+    }
+
+    fn search(&self, jql: &str, page: Option<&str>) -> Option<SearchResults> {
+        let mut endpoint = format!(
+            "{}/rest/api/2/search/jql?jql={}&fields={}&maxResults={}",
+            self.url,
+            encode(jql),
+            FIELDS,
+            PER_PAGE
+        );
+        if let Some(page) = page {
+            endpoint.push_str(&format!("&nextPageToken={}", encode(page)));
+        }
+        let credentials = STANDARD.encode(format!("{}:{}", self.user, self.token));
+
+        get_json(
+            ureq::get(&endpoint)
+                .set("Authorization", &format!("Basic {}", credentials))
+                .set("Accept", "application/json"),
+        )
+    }
+}
+
+impl Source for Jira {
+    fn fetch(
+        &self,
+        query: &IssueQuery,
+        limit: Option<usize>,
+        _pull_requests: PullRequestFilter,
+        quiet: bool,
+    ) -> Vec<Issue> {
+        let jql = self.jql(query);
+        let limit = limit.unwrap_or(usize::MAX);
+        let mut issues: Vec<Issue> = Vec::new();
+        // Issues kept per project
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let progress = progress::spinner(quiet);
+        let mut page = None;
+
+        loop {
+            progress.set_message(format!("Jira ({} issues)", issues.len()));
+            let results = self
+                .search(&jql, page.as_deref())
+                .expect("failed to get Jira issues");
+
+            for jira_issue in results.issues.into_iter() {
+                let issue = jira_issue.into_issue(&self.url);
+                let count = counts.entry(issue.get_component()).or_insert(0);
+                if *count < limit {
+                    *count += 1;
+                    issues.push(issue);
+                }
+            }
+
+            page = results.next_page_token;
+            if page.is_none() {
+                break;
+            }
+        }
+
+        progress.finish_with_message(format!("{} Jira issues collected", issues.len()));
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use issue::IssueStateJson;
+
+    #[test]
+    fn converted_issues() {
+        let jira_issue: JiraIssue = serde_json::from_value(serde_json::json!({
+            "key": "PROJ-42",
+            "fields": {
+                "summary": "Crash on startup",
+                "status": { "name": "Done", "statusCategory": { "key": "done" } },
+                "priority": { "name": "High" },
+                "assignee": { "displayName": "Bob" },
+                "labels": ["backend"],
+                "issuetype": { "name": "Bug" },
+                "fixVersions": [{ "name": "1.0", "releaseDate": "2024-03-01" }, { "name": "1.1" }],
+                "created": "2024-03-01T10:12:42.000+0100",
+                "updated": "2024-03-02T10:00:00.000+0000",
+                "resolutiondate": "2024-03-02T10:00:00.000+0000",
+            },
+        }))
+        .unwrap();
+        let issue = jira_issue.into_issue("https://example.atlassian.net");
+
+        assert_eq!(issue.number, 42);
+        assert_eq!(
+            issue.html_url,
+            "https://example.atlassian.net/browse/PROJ-42"
+        );
+        assert_eq!(issue.component.as_deref(), Some("PROJ"));
+        assert_eq!(issue.state, IssueStateJson::Closed);
+        assert_eq!(issue.created_at.to_rfc3339(), "2024-03-01T09:12:42+00:00");
+        assert!(issue.closed_at.is_some());
+        assert_eq!(issue.assignee.unwrap().login, "Bob");
+        // The first fix version is the milestone
+        assert_eq!(issue.milestone.unwrap().title, "1.0");
+        let labels: Vec<String> = issue.labels.unwrap().into_iter().map(|l| l.name).collect();
+        assert_eq!(labels, vec!["backend", "P1"]);
+        let extra_fields = issue.extra_fields.unwrap();
+        assert_eq!(extra_fields.board_column.as_deref(), Some("Done"));
+        assert_eq!(extra_fields.issue_type.as_deref(), Some("Bug"));
+    }
+
+    #[test]
+    fn queries() {
+        let jira = Jira {
+            url: "https://example.atlassian.net".to_string(),
+            user: "alice@example.com".to_string(),
+            token: "token".to_string(),
+            jql: "project = PROJ OR project = OPS".to_string(),
+        };
+        let query = IssueQuery {
+            state: StateFilter::Open,
+            labels: vec!["good \"first\" issue".to_string()],
+            labels_in_api: true,
+            ..IssueQuery::default()
+        };
+
+        assert_eq!(
+            jira.jql(&query),
+            "(project = PROJ OR project = OPS) AND statusCategory != Done \
+             AND labels = \"good \\\"first\\\" issue\""
+        );
+        assert_eq!(
+            jira.jql(&IssueQuery::default()),
+            "(project = PROJ OR project = OPS)"
+        );
+    }
+}
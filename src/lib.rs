@@ -0,0 +1,66 @@
+extern crate base64;
+extern crate chrono;
+extern crate directories;
+extern crate hmac;
+#[macro_use]
+extern crate log;
+
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+extern crate url;
+
+extern crate csv;
+
+extern crate indicatif;
+extern crate itertools;
+
+extern crate lettre;
+extern crate open;
+extern crate ratatui;
+
+extern crate regex;
+extern crate reqwest;
+#[macro_use]
+extern crate rusqlite;
+extern crate rust_xlsxwriter;
+extern crate sha2;
+extern crate toml;
+extern crate ureq;
+extern crate zip;
+
+pub mod bitbucket;
+pub mod calendar;
+pub mod client;
+pub mod column;
+pub mod comments;
+pub mod config;
+pub mod date;
+pub mod diff;
+pub mod dirs;
+pub mod dry_run;
+pub mod email;
+pub mod expr;
+pub mod github;
+pub mod gitlab;
+pub mod graphql;
+pub mod issue;
+pub mod jira;
+pub mod markdown;
+pub mod notify;
+pub mod output;
+pub mod preflight;
+pub mod progress;
+pub mod report;
+pub mod route;
+pub mod serve;
+pub mod snapshot;
+pub mod source;
+pub mod transform;
+pub mod tui;
+pub mod verify;
+
+#[cfg(test)]
+mod fixtures;
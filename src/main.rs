@@ -1,360 +1,1204 @@
-extern crate github_rs;
-use github_rs::client::{Executor, Github};
-use github_rs::{HeaderMap, StatusCode};
+extern crate github_issues;
+use github_issues::bitbucket::Bitbucket;
+use github_issues::calendar::Calendar;
+use github_issues::client::Github;
+use github_issues::column::{Column, DEFAULT_COLUMNS, GRAPHQL_COLUMNS, PULL_REQUEST_COLUMNS};
+use github_issues::comments::{self, CommentsFormat};
+use github_issues::config::Config;
+use github_issues::date::{self, timestamp_ago};
+use github_issues::diff;
+use github_issues::dirs::Dirs;
+use github_issues::dry_run;
+use github_issues::email;
+use github_issues::expr::{Context, Filter, SortSpec};
+use github_issues::github::{
+    sort_issues, AssigneeFilter, IssueQuery, MilestoneFilter, PullRequestFilter, StateFilter,
+};
+use github_issues::gitlab::Gitlab;
+use github_issues::graphql;
+use github_issues::issue::Issue;
+use github_issues::jira::Jira;
+use github_issues::notify;
+use github_issues::output::bundle::{self, Metadata};
+use github_issues::output::{self, Format, GroupBy, Options, Quoting, Sanitization, Timezone};
+use github_issues::preflight;
+use github_issues::progress;
+use github_issues::report::triage::Missing;
+use github_issues::report::{self, Report};
+use github_issues::route::Router;
+use github_issues::serve::{self, Pages, SharedPages};
+use github_issues::snapshot;
+use github_issues::source::{GithubSource, Source};
+use github_issues::transform::Pipeline;
+use github_issues::tui;
+use github_issues::verify;
+
+extern crate chrono;
+use chrono::NaiveDate;
+
+extern crate env_logger;
+use env_logger::Builder;
 
 #[macro_use]
-extern crate serde_derive;
-extern crate serde_json;
+extern crate log;
+use log::LevelFilter;
 
-extern crate url;
-use url::Url;
+extern crate regex;
+use regex::Regex;
 
 extern crate structopt;
 use structopt::StructOpt;
 
-extern crate csv;
-
-extern crate itertools;
-use itertools::Itertools;
-
-use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::iter;
+use std::mem;
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::process;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PullRequest {
-    url: String,
-    html_url: String,
-    diff_url: String,
-    patch_url: String,
+#[derive(StructOpt)]
+#[structopt(
+    name = "github-issues",
+    about = "Aggregate issues from various github repositories"
+)]
+struct Opt {
+    #[structopt(help = "github auth token, or - to use the one stored in the config directory")]
+    token: String,
+    #[structopt(help = "owner of github components")]
+    owner: String,
+    #[structopt(
+        help = "output file, or - for the standard output (default: issues.<format>)",
+        short = "o",
+        long = "output",
+        parse(from_os_str)
+    )]
+    output: Option<PathBuf>,
+    #[structopt(
+        help = "output format: csv, tsv, html, json, xlsx, sqlite, prometheus, table, dot, mermaid, ics, board, jira-csv or ado-csv (default: csv)",
+        short = "f",
+        long = "format"
+    )]
+    format: Option<Format>,
+    #[structopt(
+        help = "field delimiter of the CSV output",
+        long = "delimiter",
+        default_value = ",",
+        parse(try_from_str = "parse_delimiter")
+    )]
+    delimiter: u8,
+    #[structopt(
+        help = "quoting of CSV fields: necessary, always, non-numeric or never",
+        long = "quote",
+        default_value = "necessary"
+    )]
+    quoting: Quoting,
+    #[structopt(
+        help = "write CSV files for Excel: UTF-8 BOM and CRLF line endings",
+        long = "excel"
+    )]
+    excel: bool,
+    #[structopt(
+        help = "neutralize CSV fields spreadsheets would run as formulas: prefix, strip or off",
+        long = "sanitize",
+        default_value = "prefix"
+    )]
+    sanitization: Sanitization,
+    #[structopt(
+        help = "configuration file (default: config.toml in the config directory)",
+        short = "c",
+        long = "config",
+        parse(from_os_str)
+    )]
+    config: Option<PathBuf>,
+    #[structopt(
+        help = "directory containing the configuration and token files",
+        long = "config-dir",
+        env = "GITHUB_ISSUES_CONFIG_DIR",
+        parse(from_os_str)
+    )]
+    config_dir: Option<PathBuf>,
+    #[structopt(
+        help = "directory containing the default snapshots and the time of the last Slack notification",
+        long = "data-dir",
+        env = "GITHUB_ISSUES_DATA_DIR",
+        parse(from_os_str)
+    )]
+    data_dir: Option<PathBuf>,
+    #[structopt(
+        help = "also write a zip archive bundling all the report formats",
+        long = "bundle",
+        parse(from_os_str)
+    )]
+    bundle: Option<PathBuf>,
+    #[structopt(
+        help = "check the issues stored in this SQLite database still exist, instead of exporting issues",
+        long = "verify",
+        parse(from_os_str)
+    )]
+    verify: Option<PathBuf>,
+    #[structopt(
+        help = "list the changes since this previous CSV or TSV export, instead of exporting issues",
+        long = "diff",
+        parse(from_os_str)
+    )]
+    diff: Option<PathBuf>,
+    #[structopt(
+        help = "store the exported issues in a new snapshot of this directory, the per-user one unless a directory is given",
+        long = "snapshot-dir",
+        parse(from_os_str)
+    )]
+    snapshot_dir: Option<Option<PathBuf>>,
+    #[structopt(
+        help = "count the issues matching this filter in each snapshot of --snapshot-dir, instead of exporting issues",
+        long = "history",
+        raw(requires = "\"snapshot_dir\"")
+    )]
+    history: Option<Filter>,
+    #[structopt(
+        help = "fetch the issues and write the output again at this interval, such as 15m",
+        long = "watch",
+        parse(try_from_str = "parse_duration")
+    )]
+    watch: Option<u64>,
+    #[structopt(
+        help = "serve the issues as HTML, JSON and CSV on this port or ip:port, refreshing them periodically",
+        long = "serve",
+        parse(try_from_str = "serve::parse_address")
+    )]
+    serve: Option<SocketAddr>,
+    #[structopt(
+        help = "post a summary of the issues to this Slack incoming webhook URL once written",
+        long = "notify-slack"
+    )]
+    notify_slack: Option<String>,
+    #[structopt(
+        help = "browse the issues in a terminal UI, with search, filters and sorting",
+        long = "tui",
+        raw(conflicts_with_all = "&[\"report\", \"diff\", \"serve\", \"watch\"]")
+    )]
+    tui: bool,
+    #[structopt(
+        help = "check the components can be accessed and count the issues to fetch, without fetching them",
+        long = "dry-run"
+    )]
+    dry_run: bool,
+    #[structopt(
+        help = "only log errors and do not show the progress of the fetch",
+        short = "q",
+        long = "quiet",
+        raw(conflicts_with = "\"verbose\"")
+    )]
+    quiet: bool,
+    #[structopt(
+        help = "log more details, -v logging the API requests and -vv the issues",
+        short = "v",
+        long = "verbose",
+        parse(from_occurrences)
+    )]
+    verbose: u8,
+    #[structopt(help = "do not color the table and diff formats", long = "no-color")]
+    no_color: bool,
+    #[structopt(
+        help = "email the issues to this address once written, using the smtp server of the configuration",
+        long = "email-to",
+        raw(number_of_values = "1")
+    )]
+    email_to: Vec<String>,
+    #[structopt(
+        help = "only verify the N issues verified the longest time ago",
+        long = "verify-sample"
+    )]
+    verify_sample: Option<usize>,
+    #[structopt(
+        help = "comma-separated list of columns to export (default: all)",
+        long = "columns",
+        raw(use_delimiter = "true", require_delimiter = "true")
+    )]
+    columns: Vec<Column>,
+    #[structopt(
+        help = "only keep issues matching this expression, e.g. 'state == open && priority <= 1'",
+        long = "filter"
+    )]
+    filter: Option<Filter>,
+    #[structopt(
+        help = "comma-separated fields to sort on, '-' prefixed ones descending, e.g. '-priority,component'",
+        long = "sort",
+        raw(alias = "\"sort-by\"")
+    )]
+    sort: Option<SortSpec>,
+    #[structopt(
+        help = "split the output into sections: component, milestone, assignee, priority, board_column or status",
+        long = "group-by"
+    )]
+    group_by: Option<GroupBy>,
+    #[structopt(
+        help = "generate a report instead of exporting issues: burndown, changelog, digest, dupes, flow, gantt, hierarchy, lead-time, notifications, resolution, sla, stats, triage or workload",
+        long = "report"
+    )]
+    report: Option<Report>,
+    #[structopt(
+        help = "comma-separated fields checked by the triage report: priority, milestone and/or assignee (default: all)",
+        long = "triage-missing",
+        raw(use_delimiter = "true", require_delimiter = "true")
+    )]
+    triage_missing: Vec<Missing>,
+    #[structopt(
+        help = "period covered by the digest report, such as 7d",
+        long = "since",
+        default_value = "7d",
+        parse(try_from_str = "parse_duration")
+    )]
+    since: u64,
+    #[structopt(
+        help = "minimum similarity, from 0 to 1, of the titles listed by the dupes report",
+        long = "dupes-threshold",
+        default_value = "0.8"
+    )]
+    dupes_threshold: f64,
+    #[structopt(
+        help = "maximum number of issues fetched per component",
+        long = "limit"
+    )]
+    limit: Option<usize>,
+    #[structopt(
+        help = "only fetch issues from the first N components",
+        long = "components-limit"
+    )]
+    components_limit: Option<usize>,
+    #[structopt(
+        help = "maximum number of repositories fetched at once",
+        long = "jobs",
+        default_value = "4"
+    )]
+    jobs: usize,
+    #[structopt(
+        help = "also export pull requests, adding their draft status, branches and reviewers",
+        long = "include-prs",
+        raw(conflicts_with = "\"only_prs\"")
+    )]
+    include_prs: bool,
+    #[structopt(help = "only export pull requests", long = "only-prs")]
+    only_prs: bool,
+    #[structopt(
+        help = "do not check API access, token and clock skew before fetching issues",
+        long = "skip-checks"
+    )]
+    skip_checks: bool,
+    #[structopt(
+        help = "separator between the assignees of an issue",
+        long = "assignee-separator",
+        default_value = ", "
+    )]
+    assignee_separator: String,
+    #[structopt(
+        help = "separator between the labels of an issue",
+        long = "label-separator",
+        default_value = ", "
+    )]
+    label_separator: String,
+    #[structopt(
+        help = "area path of the Azure DevOps project, the area of each work item being named after its component",
+        long = "area-path"
+    )]
+    area_path: Option<String>,
+    #[structopt(
+        help = "only export the primary assignee of each issue",
+        long = "primary-assignee-only"
+    )]
+    primary_assignee_only: bool,
+    #[structopt(
+        help = "split the output into numbered files of at most N issues",
+        long = "max-rows-per-file",
+        parse(try_from_str = "parse_max_rows")
+    )]
+    max_rows_per_file: Option<usize>,
+    #[structopt(
+        help = "only fetch issues in this state: open, closed or all",
+        long = "state",
+        default_value = "all"
+    )]
+    state: StateFilter,
+    #[structopt(
+        help = "only export issues with this label, can be repeated",
+        long = "label",
+        raw(number_of_values = "1")
+    )]
+    labels: Vec<String>,
+    #[structopt(
+        help = "do not export issues with this label, can be repeated",
+        long = "exclude-label",
+        raw(number_of_values = "1")
+    )]
+    exclude_labels: Vec<String>,
+    #[structopt(help = "only export issues of this milestone", long = "milestone")]
+    milestone: Option<String>,
+    #[structopt(
+        help = "only export issues without milestone",
+        long = "no-milestone",
+        raw(conflicts_with = "\"milestone\"")
+    )]
+    no_milestone: bool,
+    #[structopt(help = "only export issues assigned to this user", long = "assignee")]
+    assignee: Option<String>,
+    #[structopt(
+        help = "only export issues without assignee",
+        long = "unassigned",
+        raw(conflicts_with = "\"assignee\"")
+    )]
+    unassigned: bool,
+    #[structopt(
+        help = "only export issues created on or after this yyyy-mm-dd date",
+        long = "created-after",
+        parse(try_from_str = "parse_date")
+    )]
+    created_after: Option<String>,
+    #[structopt(
+        help = "only export issues created before this yyyy-mm-dd date",
+        long = "created-before",
+        parse(try_from_str = "parse_date")
+    )]
+    created_before: Option<String>,
+    #[structopt(
+        help = "only export issues closed on or after this yyyy-mm-dd date",
+        long = "closed-after",
+        parse(try_from_str = "parse_date")
+    )]
+    closed_after: Option<String>,
+    #[structopt(
+        help = "only export issues closed before this yyyy-mm-dd date",
+        long = "closed-before",
+        parse(try_from_str = "parse_date")
+    )]
+    closed_before: Option<String>,
+    #[structopt(
+        help = "keep open issues but only the closed ones closed within this duration, such as 30d or 2w",
+        long = "closed-within",
+        parse(try_from_str = "parse_duration")
+    )]
+    closed_within: Option<u64>,
+    #[structopt(
+        help = "only export issues whose title matches this regular expression",
+        long = "title-match"
+    )]
+    title_match: Option<Regex>,
+    #[structopt(
+        help = "don't export issues whose title matches this regular expression",
+        long = "title-exclude"
+    )]
+    title_exclude: Option<Regex>,
+    #[structopt(
+        help = "only export issues with at least this number of comments",
+        long = "min-comments"
+    )]
+    min_comments: Option<u32>,
+    #[structopt(
+        help = "only export issues of this type, such as Bug, fetched using GraphQL",
+        long = "type"
+    )]
+    issue_type: Option<String>,
+    #[structopt(
+        help = "list the sub-issues under their parent in HTML outputs",
+        long = "nest-sub-issues"
+    )]
+    nest_sub_issues: bool,
+    #[structopt(
+        help = "fetch the comments of the issues to find the first response of a maintainer",
+        long = "first-response"
+    )]
+    first_response: bool,
+    #[structopt(
+        help = "download the comments of the issues to one file per issue in this directory, instead of exporting them",
+        long = "export-comments",
+        parse(from_os_str)
+    )]
+    export_comments: Option<PathBuf>,
+    #[structopt(
+        help = "format of the downloaded comments: json or markdown",
+        long = "comments-format",
+        default_value = "json"
+    )]
+    comments_format: CommentsFormat,
+    #[structopt(
+        help = "flag open issues not updated within this duration as stale, such as 180d",
+        long = "stale-after",
+        parse(try_from_str = "parse_duration")
+    )]
+    stale_after: Option<u64>,
+    #[structopt(
+        help = "export the first characters of the issue bodies, 200 unless a number is given",
+        long = "include-body"
+    )]
+    include_body: Option<Option<usize>>,
+    #[structopt(
+        help = "add the reporter of the issues and whether they are not members of the organization",
+        long = "flag-external"
+    )]
+    flag_external: bool,
+    #[structopt(
+        help = "timezone of the exported dates: utc, local or an offset such as +02:00",
+        long = "timezone",
+        default_value = "utc"
+    )]
+    timezone: Timezone,
+    #[structopt(
+        help = "strftime format of the exported dates, such as '%Y-%m-%d %H:%M'",
+        long = "date-format",
+        default_value = "%Y-%m-%d",
+        parse(try_from_str = "output::parse_date_format")
+    )]
+    date_format: String,
+    #[structopt(
+        help = "count ages and SLAs in business days, skipping the weekend and holidays of the calendar",
+        long = "business-days"
+    )]
+    business_days: bool,
+    #[structopt(
+        help = "set the component from the labels starting with this prefix, such as area/",
+        long = "component-from-label"
+    )]
+    component_from_label: Option<String>,
+    #[structopt(
+        help = "also export the issues of this GitLab project, such as group/project, can be repeated",
+        long = "gitlab-project",
+        raw(number_of_values = "1")
+    )]
+    gitlab_projects: Vec<String>,
+    #[structopt(
+        help = "URL of the GitLab instance",
+        long = "gitlab-url",
+        default_value = "https://gitlab.com"
+    )]
+    gitlab_url: String,
+    #[structopt(help = "GitLab access token", long = "gitlab-token")]
+    gitlab_token: Option<String>,
+    #[structopt(
+        help = "also export the Jira issues matching this JQL query, such as 'project = ABC'",
+        long = "jql"
+    )]
+    jql: Option<String>,
+    #[structopt(
+        help = "URL of the Jira Cloud site, such as https://example.atlassian.net",
+        long = "jira-url"
+    )]
+    jira_url: Option<String>,
+    #[structopt(help = "email of the owner of the Jira API token", long = "jira-user")]
+    jira_user: Option<String>,
+    #[structopt(help = "Jira API token", long = "jira-token")]
+    jira_token: Option<String>,
+    #[structopt(
+        help = "also export the issues of this Bitbucket Cloud repository, such as workspace/repo, can be repeated",
+        long = "bitbucket-repo",
+        raw(number_of_values = "1")
+    )]
+    bitbucket_repos: Vec<String>,
+    #[structopt(help = "Bitbucket user name", long = "bitbucket-user")]
+    bitbucket_user: Option<String>,
+    #[structopt(help = "Bitbucket app password", long = "bitbucket-token")]
+    bitbucket_token: Option<String>,
+    #[structopt(help = "github components to look for issues")]
+    components: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Assignee {
-    login: String,
-}
+// Characters of the bodies exported by --include-body
+const DEFAULT_BODY_LENGTH: usize = 200;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Milestone {
-    title: String,
-}
+// Refresh interval of the served pages, in seconds
+const DEFAULT_REFRESH: u64 = 15 * 60;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Label {
-    name: String,
+fn parse_delimiter(s: &str) -> Result<u8, String> {
+    match s.as_bytes() {
+        [c] => Ok(*c),
+        _ => Err(format!(
+            "delimiter must be a single ASCII character: '{}'",
+            s
+        )),
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
-enum IssueStateJson {
-    #[serde(rename = "open")]
-    Open,
-    #[serde(rename = "closed")]
-    Closed,
+fn parse_max_rows(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!(
+            "maximum number of rows must be a positive number: '{}'",
+            s
+        )),
+    }
 }
 
-#[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
-enum IssueState {
-    Blocked,
-    UnderReview,
-    Open,
-    Closed,
-}
+// '30d' -> number of seconds in 30 days, 'm' being minutes
+fn parse_duration(s: &str) -> Result<u64, String> {
+    let err = || {
+        format!(
+            "duration must be a number of minutes, hours, days or weeks, such as 30d: '{}'",
+            s
+        )
+    };
+    let unit = match s.chars().last() {
+        Some('m') => 60,
+        Some('h') => 3600,
+        Some('d') => 86_400,
+        Some('w') => 7 * 86_400,
+        _ => return Err(err()),
+    };
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Issue {
-    title: String,
-    html_url: String,
-    number: u32,
-    repository_url: String,
-    pull_request: Option<PullRequest>,
-    assignee: Option<Assignee>,
-    milestone: Option<Milestone>,
-    labels: Option<Vec<Label>>,
-    state: IssueStateJson,
-    created_at: String,
-    closed_at: Option<String>,
-    updated_at: String,
+    s[..s.len() - 1]
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(unit))
+        .ok_or_else(err)
 }
 
-#[derive(Debug, Serialize)]
-struct IssueCSV<'a> {
-    component: String,
-    id: String,
-    title: &'a str,
-    state: String,
-    assignee: Option<&'a str>,
-    milestone: Option<&'a str>,
-    priority: Option<u32>,
-    created_at: &'a str,
-    closed_at: Option<&'a str>,
-    url: &'a str,
-    updated_at: &'a str,
-    last_comment: String,
+// Directory of --snapshot-dir
+fn snapshot_dir(opt: &Opt) -> Option<PathBuf> {
+    opt.snapshot_dir
+        .as_ref()
+        .map(|dir| dir.clone().unwrap_or_else(|| dirs(opt).snapshot_dir()))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct Comment {
-    body: String,
+// Per-user directories, unless changed by --config-dir and --data-dir
+fn dirs(opt: &Opt) -> Dirs {
+    Dirs::new(opt.config_dir.clone(), opt.data_dir.clone())
 }
 
-fn strip_date(d: &str) -> &str {
-    // Keep only 'yyyy-mm-dd'
-    &d[..10]
+// Dates are compared as 'yyyy-mm-dd' strings by the filters
+fn parse_date(s: &str) -> Result<String, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map(|d| d.format("%Y-%m-%d").to_string())
+        .map_err(|_| format!("date must be formatted as yyyy-mm-dd: '{}'", s))
 }
 
-impl Issue {
-    fn is_pull_request(&self) -> bool {
-        self.pull_request.is_some()
+fn main() {
+    let opt = Opt::from_args();
+
+    // RUST_LOG overrides the level set on the command line
+    let level = match (opt.quiet, opt.verbose) {
+        (true, _) => LevelFilter::Error,
+        (false, 0) => LevelFilter::Info,
+        (false, 1) => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    Builder::new()
+        .filter_level(level)
+        .format_timestamp(None)
+        .parse_default_env()
+        .init();
+
+    let dirs = dirs(&opt);
+
+    let mut config = match opt.config {
+        Some(ref path) => Config::from_path(path),
+        None if dirs.config_file().exists() => Config::from_path(&dirs.config_file()),
+        None => Config::default(),
+    };
+    if opt.component_from_label.is_some() {
+        config.component_label = opt.component_from_label.clone();
     }
+    let pipeline = Pipeline::from_config(&config).expect("Failed to set up transforms");
+    let router = Router::from_config(&config).expect("Failed to set up routes");
+    let calendar = if opt.business_days {
+        Some(Calendar::from_config(&config.calendar).expect("Failed to set up calendar"))
+    } else {
+        None
+    };
 
-    fn get_component(&self) -> String {
-        let url = Url::parse(&self.repository_url).expect("Failed to parse repo URL");
-        let path_segments = url
-            .path_segments()
-            .expect("Failed to extract path segments");
+    if let Some(ref filter) = opt.history {
+        let dir = snapshot_dir(&opt).expect("--history needs --snapshot-dir");
+        let context = Context {
+            calendar: calendar.as_ref(),
+        };
+        snapshot::write_history(io::stdout(), &dir, &pipeline, filter, &context)
+            .expect("Failed to read snapshots");
+        return;
+    }
 
-        path_segments
-            .last()
-            .expect("missing path segment")
+    let token = if opt.token == "-" {
+        fs::read_to_string(dirs.token_file())
+            .expect("Failed to read stored token")
+            .trim()
             .to_string()
-    }
+    } else {
+        opt.token.clone()
+    };
 
-    fn get_owner(&self) -> String {
-        let url = Url::parse(&self.repository_url).expect("Failed to parse repo URL");
-        let path_segments: Vec<&str> = url
-            .path_segments()
-            .expect("Failed to extract path segments")
-            .collect();
+    let client = Github::new(token).unwrap();
 
-        path_segments[path_segments.len() - 2].to_string()
+    if !opt.skip_checks {
+        let problems = preflight::check(&client);
+        if !problems.is_empty() {
+            for problem in problems.iter() {
+                error!("{}", problem);
+            }
+            process::exit(1);
+        }
     }
 
-    fn csv(&self, client: &Github) -> IssueCSV {
-        IssueCSV {
-            component: self.get_component(),
-            id: format!("#{}", self.number),
-            title: &self.title,
-            state: self.get_state_str(),
-            assignee: {
-                match self.assignee {
-                    Some(ref a) => Some(&a.login),
-                    None => None,
-                }
-            },
-            milestone: {
-                match self.milestone {
-                    Some(ref m) => Some(&m.title),
-                    None => None,
-                }
-            },
-            priority: self.get_priority(),
-            created_at: self.get_created_at(),
-            closed_at: self.get_closed_at(),
-            url: &self.html_url,
-            updated_at: self.get_updated_at(),
-            last_comment: self.get_last_comment(client),
-        }
+    if opt.dry_run {
+        let ok = dry_run::dry_run(
+            io::stdout(),
+            &client,
+            &opt.owner,
+            components(&opt),
+            &issue_query(&opt, &config),
+            pull_request_filter(&opt),
+        )
+        .expect("Failed to write dry run");
+        process::exit(if ok { 0 } else { 1 });
     }
 
-    fn get_priority(&self) -> Option<u32> {
-        if self.labels.is_none() {
-            return None;
-        }
+    if let Some(ref db) = opt.verify {
+        verify::verify(io::stdout(), &client, db, opt.verify_sample)
+            .expect("Failed to verify issues");
+        return;
+    }
 
-        for label in self.labels.as_ref().unwrap() {
-            match label.name.as_str() {
-                "P0" => return Some(0),
-                "P1" => return Some(1),
-                "P2" => return Some(2),
-                "P3" => return Some(3),
-                "P4" => return Some(4),
-                "P5" => return Some(5),
-                _ => continue,
-            }
-        }
+    let github = GithubSource {
+        client: &client,
+        owner: &opt.owner,
+        components: components(&opt),
+        jobs: opt.jobs,
+    };
+    let gitlab = if opt.gitlab_projects.is_empty() {
+        None
+    } else {
+        Some(Gitlab {
+            url: opt.gitlab_url.trim_end_matches('/').to_string(),
+            token: opt
+                .gitlab_token
+                .clone()
+                .expect("--gitlab-project needs --gitlab-token"),
+            projects: opt.gitlab_projects.clone(),
+        })
+    };
+    let jira = opt.jql.as_ref().map(|jql| Jira {
+        url: opt
+            .jira_url
+            .as_ref()
+            .expect("--jql needs --jira-url")
+            .trim_end_matches('/')
+            .to_string(),
+        user: opt.jira_user.clone().expect("--jql needs --jira-user"),
+        token: opt.jira_token.clone().expect("--jql needs --jira-token"),
+        jql: jql.clone(),
+    });
+    let bitbucket = if opt.bitbucket_repos.is_empty() {
         None
+    } else {
+        Some(Bitbucket {
+            user: opt
+                .bitbucket_user
+                .clone()
+                .expect("--bitbucket-repo needs --bitbucket-user"),
+            token: opt
+                .bitbucket_token
+                .clone()
+                .expect("--bitbucket-repo needs --bitbucket-token"),
+            repos: opt.bitbucket_repos.clone(),
+        })
+    };
+    let mut sources: Vec<&dyn Source> = vec![&github];
+    if let Some(ref gitlab) = gitlab {
+        sources.push(gitlab);
+    }
+    if let Some(ref jira) = jira {
+        sources.push(jira);
+    }
+    if let Some(ref bitbucket) = bitbucket {
+        sources.push(bitbucket);
     }
 
-    fn get_state(&self) -> IssueState {
-        if self.state == IssueStateJson::Closed {
-            return IssueState::Closed;
-        }
+    let (updates, received) = mpsc::channel();
+    let pages = opt.serve.map(|address| {
+        let pages = SharedPages::default();
+        serve::spawn(
+            address,
+            pages.clone(),
+            config.webhook_secret.clone(),
+            updates.clone(),
+        )
+        .expect("Failed to start server");
+        pages
+    });
+    // Served pages are refreshed every 15 minutes by default
+    let interval = opt
+        .watch
+        .or_else(|| pages.as_ref().map(|_| DEFAULT_REFRESH));
 
-        if let Some(labels) = self.labels.as_ref() {
-            if labels.iter().any(|l| l.name == "under review") {
-                return IssueState::UnderReview;
-            } else if labels.iter().any(|l| l.name == "blocked") {
-                return IssueState::Blocked;
-            }
+    let interval = match interval {
+        Some(interval) => interval,
+        None => {
+            let mut issues = fetch(&opt, &config, &pipeline, &sources);
+            run(
+                &opt,
+                &config,
+                &router,
+                calendar.as_ref(),
+                &client,
+                None,
+                &mut issues,
+            );
+            return;
         }
+    };
 
-        IssueState::Open
-    }
+    let query = issue_query(&opt, &config);
+    loop {
+        let mut issues = fetch(&opt, &config, &pipeline, &sources);
+        run(
+            &opt,
+            &config,
+            &router,
+            calendar.as_ref(),
+            &client,
+            pages.as_ref(),
+            &mut issues,
+        );
+        info!("Refreshing in {} seconds", interval);
 
-    fn get_state_str(&self) -> String {
-        match self.get_state() {
-            IssueState::Open => "open".to_string(),
-            IssueState::Closed => "closed".to_string(),
-            IssueState::Blocked => "blocked".to_string(),
-            IssueState::UnderReview => "under review".to_string(),
+        // Issues reported by webhooks are updated until the next refresh
+        let refresh = Instant::now() + Duration::from_secs(interval);
+        while let Some(timeout) = refresh.checked_duration_since(Instant::now()) {
+            let update = match received.recv_timeout(timeout) {
+                Ok(update) => update,
+                Err(_) => break,
+            };
+            // Several issues of a repository are often changed at once, so only
+            // fetch it once for all the deliveries received in the meantime
+            let repos: BTreeSet<(String, String)> = iter::once(update)
+                .chain(received.try_iter())
+                .filter(|u| u.concerns(&opt.owner, components(&opt)))
+                .map(|u| (u.owner, u.repo))
+                .collect();
+            if repos.is_empty() {
+                continue;
+            }
+
+            serve::refresh_repos(
+                &client,
+                &pipeline,
+                &query,
+                pull_request_filter(&opt),
+                opt.limit,
+                &mut issues,
+                &repos,
+            );
+            // Only the served pages are regenerated, snapshots and notifications
+            // waiting for the next refresh
+            let hydrated = select(&opt, &config, calendar.as_ref(), &client, &mut issues);
+            render(
+                &opt,
+                &config,
+                &router,
+                calendar.as_ref(),
+                &client,
+                pages.as_ref(),
+                issues.as_mut_slice(),
+                hydrated,
+            );
         }
     }
+}
 
-    fn get_created_at(&self) -> &str {
-        strip_date(&self.created_at)
+fn components(opt: &Opt) -> &[String] {
+    match opt.components_limit {
+        Some(n) if n < opt.components.len() => &opt.components[..n],
+        _ => &opt.components[..],
     }
+}
 
-    fn get_updated_at(&self) -> &str {
-        strip_date(&self.updated_at)
+fn pull_request_filter(opt: &Opt) -> PullRequestFilter {
+    if opt.only_prs {
+        PullRequestFilter::Only
+    } else if opt.include_prs {
+        PullRequestFilter::Include
+    } else {
+        PullRequestFilter::Exclude
     }
+}
 
-    fn get_closed_at(&self) -> Option<&str> {
-        match self.closed_at {
-            None => None,
-            Some(ref d) => Some(strip_date(&d)),
-        }
+fn issue_query(opt: &Opt, config: &Config) -> IssueQuery {
+    IssueQuery {
+        state: opt.state,
+        labels: opt.labels.clone(),
+        exclude_labels: opt.exclude_labels.clone(),
+        labels_in_api: config.normalize_labels.is_empty()
+            && !config.transforms.iter().any(|t| t == "map-labels"),
+        milestone: if opt.no_milestone {
+            Some(MilestoneFilter::Unset)
+        } else {
+            opt.milestone.clone().map(MilestoneFilter::Title)
+        },
+        assignee: if opt.unassigned {
+            Some(AssigneeFilter::Unset)
+        } else {
+            opt.assignee.clone().map(AssigneeFilter::Login)
+        },
+        created_after: opt.created_after.clone(),
+        created_before: opt.created_before.clone(),
+        closed_after: opt.closed_after.clone(),
+        closed_before: opt.closed_before.clone(),
+        closed_since: opt.closed_within.map(date::ago),
+        title_match: opt.title_match.clone(),
+        title_exclude: opt.title_exclude.clone(),
+        min_comments: opt.min_comments,
     }
+}
 
-    fn get_last_comment(&self, client: &Github) -> String {
-        let comments = get_comments(
-            client,
-            &self.get_owner(),
-            &self.get_component(),
-            self.number,
-        );
+// Fetch the issues matching the query, once transformed
+fn fetch(opt: &Opt, config: &Config, pipeline: &Pipeline, sources: &[&dyn Source]) -> Vec<Issue> {
+    let query = issue_query(opt, config);
+    let issues = sources
+        .iter()
+        .flat_map(|s| s.fetch(&query, opt.limit, pull_request_filter(opt), opt.quiet))
+        .collect();
+    let mut issues = pipeline.apply(issues);
+    issues.retain(|i| query.matches(i));
+    issues
+}
 
-        match comments.unwrap().pop() {
-            Some(last) => last.body.to_string(),
-            None => "".to_string(),
+// Fetch the GraphQL fields, and set the state of the issues from the project boards
+fn hydrate(config: &Config, client: &Github, issues: &mut [Issue]) {
+    graphql::hydrate(client, issues);
+
+    if !config.board_states.is_empty() {
+        for issue in issues.iter_mut() {
+            issue.set_board_state(&config.board_states);
         }
     }
 }
 
-type Issues = Vec<Issue>;
+// Whether the expressions use the first responses, which are then fetched before
+// filtering
+fn needs_first_response(opt: &Opt) -> bool {
+    opt.filter
+        .as_ref()
+        .is_some_and(|f| f.needs_first_response())
+        || opt.sort.as_ref().is_some_and(|s| s.needs_first_response())
+}
 
-fn get_json<T>(
-    response: Result<(HeaderMap, StatusCode, Option<T>), github_rs::errors::Error>,
-) -> Option<T> {
-    match response {
-        Ok((_headers, _status, json)) => json,
-        Err(e) => {
-            println!("{}", e);
-            None
-        }
+fn set_first_responses(opt: &Opt, client: &Github, issues: &mut [Issue]) {
+    let progress = progress::bar(issues.len(), "issues", opt.quiet);
+    progress.set_message("Fetching first responses");
+    for issue in issues.iter_mut() {
+        issue.set_first_response(client);
+        progress.inc(1);
     }
+    progress.finish_and_clear();
 }
 
-fn get_issues(client: &Github, owner: &str, repo_name: &str) -> Option<Issues> {
-    let issues_endpoint = format!(
-        "repos/{}/{}/issues?state=all&per_page=500",
-        owner, repo_name
-    );
-    let response = client
-        .get()
-        .custom_endpoint(&issues_endpoint)
-        .execute::<Issues>();
-    get_json(response)
-}
+// Filter and sort the issues, returning whether they have been hydrated
+fn select(
+    opt: &Opt,
+    config: &Config,
+    calendar: Option<&Calendar>,
+    client: &Github,
+    issues: &mut Vec<Issue>,
+) -> bool {
+    // Hydrate before filtering if the expressions need it, after otherwise so only
+    // the exported issues are queried
+    let hydrated = opt.filter.as_ref().is_some_and(|f| f.needs_graphql())
+        || opt.sort.as_ref().is_some_and(|s| s.needs_graphql())
+        || opt.issue_type.is_some()
+        || !config.board_states.is_empty();
+    if hydrated {
+        hydrate(config, client, issues);
+    }
+    if needs_first_response(opt) {
+        set_first_responses(opt, client, issues);
+    }
+    if let Some(after) = opt.stale_after {
+        let since = date::ago(after);
+        for issue in issues.iter_mut() {
+            issue.stale = Some(issue.is_stale(since));
+        }
+    }
 
-type Comments = Vec<Comment>;
+    if let Some(ref issue_type) = opt.issue_type {
+        issues.retain(|i| {
+            i.get_type()
+                .is_some_and(|t| t.eq_ignore_ascii_case(issue_type))
+        });
+    }
 
-fn get_comments(client: &Github, owner: &str, repo_name: &str, bug_id: u32) -> Option<Comments> {
-    let comments_endpoint = format!("repos/{}/{}/issues/{}/comments", owner, repo_name, bug_id,);
-    let response = client
-        .get()
-        .custom_endpoint(&comments_endpoint)
-        .execute::<Comments>();
-    get_json(response)
-}
+    // Ages are counted in business days with --business-days, as in the columns
+    let context = Context { calendar };
+    if let Some(ref filter) = opt.filter {
+        issues.retain(|i| filter.matches(i, &context));
+    }
 
-#[derive(StructOpt)]
-#[structopt(
-    name = "github-issues",
-    about = "Aggregate issues from various github repositories"
-)]
-struct Opt {
-    #[structopt(help = "github auth token")]
-    token: String,
-    #[structopt(help = "owner of github components")]
-    owner: String,
-    #[structopt(
-        help = "output file",
-        short = "o",
-        long = "output",
-        default_value = "issues.csv",
-        parse(from_os_str)
-    )]
-    output: PathBuf,
-    #[structopt(help = "github components to look for issues")]
-    components: Vec<String>,
+    match opt.sort {
+        Some(ref spec) => issues.sort_by(|a, b| spec.compare(a, b, &context)),
+        None => *issues = sort_issues(mem::take(issues)),
+    }
+
+    hydrated
 }
 
-fn get_all_issues(client: &Github, owner: &str, components: &[String]) -> Vec<Issue> {
-    let mut issues: Vec<Issue> = Vec::new();
+// Write the output of the fetched issues, or update the served pages
+fn run(
+    opt: &Opt,
+    config: &Config,
+    router: &Router,
+    calendar: Option<&Calendar>,
+    client: &Github,
+    pages: Option<&SharedPages>,
+    issues: &mut Vec<Issue>,
+) {
+    let hydrated = select(opt, config, calendar, client, issues);
 
-    for component in components {
-        issues.append(&mut get_issues(&client, owner, &component).expect("failed to get issues"));
+    // Otherwise only fetched for the selected issues
+    if opt.first_response && !needs_first_response(opt) {
+        set_first_responses(opt, client, issues);
     }
 
-    // Filter out pull requests
-    let issues = issues.into_iter().filter(|i| !i.is_pull_request());
+    if let Some(ref dir) = opt.export_comments {
+        let written = comments::export(client, issues, dir, opt.comments_format, opt.quiet)
+            .expect("Failed to export comments");
+        info!(
+            "Comments of {} issues written to {}",
+            written,
+            dir.display()
+        );
+        return;
+    }
 
-    issues.sorted_by(|a, b| {
-        let state_a = a.get_state();
-        let state_b = b.get_state();
+    if let Some(dir) = snapshot_dir(opt) {
+        snapshot::store(&dir, issues).expect("Failed to store snapshot");
+    }
 
-        // Put closed tasks last
-        match (&state_a, &state_b) {
-            (&IssueState::Closed, _) => return Ordering::Greater,
-            (_, &IssueState::Closed) => return Ordering::Less,
-            _ => {}
+    if let Some(report) = opt.report {
+        // Reports are printed on the standard output by default
+        let path = opt.output.clone().unwrap_or_else(|| PathBuf::from("-"));
+        let options = report::Options {
+            config,
+            format: opt.format,
+            triage: opt.triage_missing.clone(),
+            since: timestamp_ago(opt.since),
+            calendar: calendar.cloned(),
+            dupes_threshold: opt.dupes_threshold,
+            first_response: opt.first_response,
         };
+        report::generate(report, client, issues, &options, &path);
+        return;
+    }
 
-        match (a.get_priority(), b.get_priority()) {
-            (Some(_a), None) => return Ordering::Less,
-            (None, Some(_b)) => return Ordering::Greater,
-            (Some(pa), Some(pb)) => return pa.cmp(&pb),
-            _ => {}
-        };
+    render(
+        opt, config, router, calendar, client, pages, issues, hydrated,
+    );
+}
 
-        if state_a != state_b {
-            return state_a.cmp(&state_b);
+// Export the selected issues, or update the served pages
+#[allow(clippy::too_many_arguments)]
+fn render(
+    opt: &Opt,
+    config: &Config,
+    router: &Router,
+    calendar: Option<&Calendar>,
+    client: &Github,
+    pages: Option<&SharedPages>,
+    issues: &mut [Issue],
+    hydrated: bool,
+) {
+    let pull_requests = pull_request_filter(opt);
+    let format = opt.format.unwrap_or(Format::Csv);
+    let output_path = opt.output.clone().unwrap_or_else(|| {
+        // Diffs and tables are printed on the standard output by default, like reports
+        if opt.diff.is_some() || format == Format::Table {
+            PathBuf::from("-")
+        } else {
+            PathBuf::from(format!("issues.{}", format.extension()))
         }
+    });
 
-        if state_a == IssueState::Closed {
-            return b.get_closed_at().cmp(&a.get_closed_at());
+    let columns = if opt.columns.is_empty() {
+        let mut columns = DEFAULT_COLUMNS.to_vec();
+        if pull_requests != PullRequestFilter::Exclude {
+            columns.extend_from_slice(PULL_REQUEST_COLUMNS);
         }
-
-        let cmp = a.get_component().cmp(&b.get_component());
-        if cmp == Ordering::Less || cmp == Ordering::Greater {
-            return cmp;
+        if opt.stale_after.is_some() {
+            columns.push(Column::Stale);
+        }
+        if opt.include_body.is_some() {
+            columns.push(Column::Body);
+        }
+        if opt.first_response {
+            columns.push(Column::FirstResponseHours);
         }
+        if opt.flag_external {
+            columns.push(Column::Reporter);
+            columns.push(Column::External);
+        }
+        columns
+    } else {
+        opt.columns.clone()
+    };
+
+    let options = Options {
+        columns,
+        headers: config
+            .headers
+            .iter()
+            .map(|(column, header)| {
+                let column = column.parse::<Column>().expect("Invalid column in headers");
+                (column, header.clone())
+            })
+            .collect(),
+        delimiter: opt.delimiter,
+        quoting: opt.quoting,
+        excel: opt.excel,
+        sanitization: opt.sanitization,
+        assignee_separator: opt.assignee_separator.clone(),
+        label_separator: opt.label_separator.clone(),
+        primary_assignee_only: opt.primary_assignee_only,
+        max_rows_per_file: opt.max_rows_per_file,
+        url_template: config.url_template.clone(),
+        group_by: opt.group_by,
+        stale_since: opt.stale_after.map(date::ago),
+        body_length: opt.include_body.flatten().unwrap_or(DEFAULT_BODY_LENGTH),
+        nest_sub_issues: opt.nest_sub_issues,
+        timezone: opt.timezone,
+        date_format: opt.date_format.clone(),
+        calendar: calendar.cloned(),
+        // Only color terminals, following https://no-color.org/
+        color: !opt.no_color
+            && output::is_stdout(&output_path)
+            && io::stdout().is_terminal()
+            && env::var_os("NO_COLOR").is_none(),
+        area_path: opt.area_path.clone(),
+    };
+
+    let grouped_by_graphql = options
+        .group_by
+        .is_some_and(|g| GRAPHQL_COLUMNS.contains(&g.column()));
+    if !hydrated
+        && (grouped_by_graphql
+            || options.nest_sub_issues
+            // Issue types are imported to Jira and Azure DevOps
+            || format == Format::JiraCsv
+            || format == Format::AdoCsv
+            || options.columns.iter().any(|c| GRAPHQL_COLUMNS.contains(c)))
+    {
+        hydrate(config, client, issues);
+    }
 
-        let cmp = a.number.cmp(&b.number);
-        if cmp == Ordering::Less || cmp == Ordering::Greater {
-            return cmp;
+    if options.columns.contains(&Column::FirstSeen) || options.columns.contains(&Column::LastSeen) {
+        // Including the snapshot just stored, if any
+        match snapshot_dir(opt) {
+            Some(ref dir) if dir.exists() => {
+                snapshot::set_seen(dir, issues).expect("Failed to read snapshots")
+            }
+            _ => warn!("first_seen and last_seen are only set with --snapshot-dir"),
         }
+    }
 
-        Ordering::Equal
-    })
-}
+    let rows = output::rows(client, issues, &options, opt.quiet);
+    if opt.tui {
+        // Including the one just stored, if any
+        let snapshots = match snapshot_dir(opt) {
+            Some(ref dir) if dir.exists() => {
+                let pipeline = Pipeline::from_config(config).expect("Failed to set up transforms");
+                snapshot::load_all(dir, &pipeline).expect("Failed to read snapshots")
+            }
+            _ => Vec::new(),
+        };
+        tui::browse(&rows, &snapshots).expect("Failed to run terminal UI");
+        return;
+    }
+
+    if let Some(ref old) = opt.diff {
+        diff::generate(old, &rows, &options, &output_path);
+        return;
+    }
 
-fn generate_csv(client: &Github, issues: Vec<Issue>, output: &PathBuf) {
-    let mut wtr = csv::Writer::from_path(&output).expect("Failed to create output file");
+    if let Some(pages) = pages {
+        let rendered = Pages::render(&rows, &options).expect("Failed to render pages");
+        *pages.lock().expect("Failed to lock pages") = rendered;
+        return;
+    }
+
+    if router.is_empty() {
+        output::generate(format, &rows, &options, &output_path);
+    } else {
+        let context = Context { calendar };
+        for (name, rows) in router.split(issues, &rows, &context) {
+            let path = match name {
+                Some(name) => {
+                    output_path.with_file_name(format!("{}.{}", name, format.extension()))
+                }
+                None => output_path.clone(),
+            };
+            output::generate(format, &rows, &options, &path);
+        }
+    }
 
-    for issue in issues.into_iter() {
-        println!("{:?} {}", issue, issue.get_component());
-        wtr.serialize(issue.csv(client))
-            .expect("Failed to add record");
+    if let Some(ref path) = opt.bundle {
+        let metadata = Metadata::new(&opt.owner, components(opt), rows.len());
+        bundle::write_bundle(&rows, &options, &metadata, path).expect("Failed to write bundle");
     }
 
-    wtr.flush().expect("Failed to flush output");
+    if !opt.email_to.is_empty() {
+        let smtp = config
+            .smtp
+            .as_ref()
+            .expect("--email-to needs a [smtp] section in the configuration");
+        let subject = format!("Issues of {}: {} issues", opt.owner, rows.len());
+        email::send_report(smtp, &opt.email_to, &subject, format, &rows, &options)
+            .expect("Failed to send email");
+    }
+
+    if let Some(ref webhook) = opt.notify_slack {
+        let last_run = dirs(opt).data_dir().join("slack-last-run");
+        notify::notify_slack(webhook, issues, &last_run).expect("Failed to notify slack");
+    }
 }
 
-fn main() {
-    let opt = Opt::from_args();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_durations() {
+        assert_eq!(parse_duration("15m"), Ok(15 * 60));
+        assert_eq!(parse_duration("2h"), Ok(2 * 3600));
+        assert_eq!(parse_duration("30d"), Ok(30 * 86_400));
+        assert_eq!(parse_duration("1w"), Ok(7 * 86_400));
+        assert_eq!(parse_duration("0d"), Ok(0));
+        assert_eq!(
+            parse_duration("30500568904943w"),
+            Ok(30_500_568_904_943 * 7 * 86_400)
+        );
+    }
 
-    let client = Github::new(opt.token).unwrap();
-    let issues = get_all_issues(&client, &opt.owner, &opt.components);
+    #[test]
+    fn parse_invalid_durations() {
+        for s in [
+            "",
+            "d",
+            "30",
+            "30s",
+            "1.5d",
+            "-1d",
+            "d30",
+            "30 d",
+            "30é",
+            // Overflowing durations
+            "99999999999999999w",
+            "213503982334602d",
+            "18446744073709551616m",
+        ] {
+            assert!(parse_duration(s).is_err(), "'{}' was accepted", s);
+        }
+    }
 
-    generate_csv(&client, issues, &opt.output);
+    #[test]
+    fn parse_dates() {
+        assert_eq!(parse_date("2024-02-29"), Ok("2024-02-29".to_string()));
+        for s in ["2023-02-29", "2024-13-01", "2024/01/01", "", "today"] {
+            assert!(parse_date(s).is_err(), "'{}' was accepted", s);
+        }
+    }
 }
@@ -0,0 +1,206 @@
+use regex::Regex;
+
+use std::sync::OnceLock;
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("Invalid regex"))
+}
+
+// Plain text of a Markdown body, on a single line
+fn strip(body: &str) -> String {
+    static COMMENT: OnceLock<Regex> = OnceLock::new();
+    static PREFIX: OnceLock<Regex> = OnceLock::new();
+    static LINK: OnceLock<Regex> = OnceLock::new();
+    static EMPHASIS: OnceLock<Regex> = OnceLock::new();
+
+    let body = regex(&COMMENT, r"(?s)<!--.*?-->").replace_all(body, "");
+    let lines: Vec<String> = body
+        .lines()
+        // Code fences, the code itself is kept
+        .filter(|l| !l.trim_start().starts_with("```"))
+        .map(|l| {
+            // Titles, quotes, list items and task list boxes
+            let l = regex(
+                &PREFIX,
+                r"^\s*(?:#{1,6}|>+|[-*+]|\d+\.)\s+(?:\[[ xX]\]\s+)?",
+            )
+            .replace(l, "");
+            // Only keep the text of links and images
+            let l = regex(&LINK, r"!?\[([^\]]*)\]\([^)]*\)").replace_all(&l, "$1");
+            regex(&EMPHASIS, r"\*\*|__|~~|[*`]")
+                .replace_all(&l, "")
+                .into_owned()
+        })
+        .collect();
+
+    lines.join(" ")
+}
+
+// First 'max_chars' characters of the text of a Markdown body, newlines and
+// repeated spaces collapsed
+pub fn excerpt(body: &str, max_chars: usize) -> String {
+    let text = strip(body)
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    if text.chars().count() > max_chars {
+        let mut truncated: String = text.chars().take(max_chars).collect();
+        truncated.push('…');
+        truncated
+    } else {
+        text
+    }
+}
+
+// Lines of a body which are not part of code blocks
+fn outside_code(body: &str) -> Vec<&str> {
+    let mut in_code = false;
+    body.lines()
+        .filter(|line| {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+                false
+            } else {
+                !in_code
+            }
+        })
+        .collect()
+}
+
+// Number of checked items and total number of items of the task lists of a body
+pub fn task_progress(body: &str) -> (usize, usize) {
+    static TASK: OnceLock<Regex> = OnceLock::new();
+
+    let mut done = 0;
+    let mut total = 0;
+    for line in outside_code(body) {
+        if let Some(caps) = regex(&TASK, r"^\s*(?:[-*+]|\d+\.)\s+\[([ xX])\]").captures(line) {
+            total += 1;
+            if &caps[1] != " " {
+                done += 1;
+            }
+        }
+    }
+
+    (done, total)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Relation {
+    // 'fixes #1', 'closes #1', 'resolves #1'...
+    Closes,
+    // 'blocks #1'
+    Blocks,
+    // 'blocked by #1', 'depends on #1'
+    BlockedBy,
+}
+
+// 'owner/repo#42', or '#42' for an issue of the same repository
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub repo: Option<String>,
+    pub number: u32,
+}
+
+impl Reference {
+    fn parse(s: &str) -> Option<Reference> {
+        let (repo, number) = s.split_at(s.find('#')?);
+        Some(Reference {
+            repo: if repo.is_empty() {
+                None
+            } else {
+                Some(repo.to_string())
+            },
+            number: number[1..].parse().ok()?,
+        })
+    }
+}
+
+// References to other issues, such as 'fixes #12' or 'blocked by owner/repo#3, #4'
+pub fn references(body: &str) -> Vec<(Relation, Reference)> {
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+
+    let re = regex(
+        &REFERENCE,
+        r"(?i)\b(close[sd]?|fix(?:e[sd])?|resolve[sd]?|blocked by|depends on|blocks)\s*:?\s+((?:[\w.-]+/[\w.-]+)?#\d+(?:\s*,\s*(?:[\w.-]+/[\w.-]+)?#\d+)*)",
+    );
+    let mut references = Vec::new();
+    for line in outside_code(body) {
+        for caps in re.captures_iter(line) {
+            let keyword = caps[1].to_lowercase();
+            let relation = if keyword == "blocks" {
+                Relation::Blocks
+            } else if keyword == "blocked by" || keyword == "depends on" {
+                Relation::BlockedBy
+            } else {
+                Relation::Closes
+            };
+            references.extend(
+                caps[2]
+                    .split(',')
+                    .filter_map(|r| Reference::parse(r.trim()))
+                    .map(|r| (relation, r)),
+            );
+        }
+    }
+
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(repo: Option<&str>, number: u32) -> Reference {
+        Reference {
+            repo: repo.map(String::from),
+            number,
+        }
+    }
+
+    #[test]
+    fn excerpts() {
+        let body = "# Title\n\n<!-- hidden\ncomment -->Some **bold** and [link](http://x) `code`.\n> quoted\n```\nlet x = 1;\n```\n- [x] done item";
+
+        assert_eq!(
+            excerpt(body, 100),
+            "Title Some bold and link code. quoted let x = 1; done item"
+        );
+        assert_eq!(excerpt(body, 10), "Title Some…");
+        assert_eq!(excerpt("  Short\n\n body ", 10), "Short body");
+        assert_eq!(excerpt("", 10), "");
+    }
+
+    #[test]
+    fn references_by_relation() {
+        let body =
+            "Fixes #12\nblocked by owner/repo#3, #4 and Depends on: other/lib#5\nThis blocks #7.";
+
+        assert_eq!(
+            references(body),
+            vec![
+                (Relation::Closes, reference(None, 12)),
+                (Relation::BlockedBy, reference(Some("owner/repo"), 3)),
+                (Relation::BlockedBy, reference(None, 4)),
+                (Relation::BlockedBy, reference(Some("other/lib"), 5)),
+                (Relation::Blocks, reference(None, 7)),
+            ]
+        );
+    }
+
+    #[test]
+    fn references_ignored() {
+        // Code blocks, bare numbers and keywords inside other words
+        let body = "```\nfixes #1\n```\nsee #2\nprefixes #3\nfixes 4\ncloses #x";
+        assert_eq!(references(body), vec![]);
+    }
+
+    #[test]
+    fn task_progress_counts() {
+        let body = "- [x] design\n* [X] code\n1. [ ] test\n  + [ ] release\n- [] not a task\n```\n- [x] in code\n```";
+        assert_eq!(task_progress(body), (2, 4));
+        assert_eq!(task_progress("No task list"), (0, 0));
+        assert_eq!(task_progress(""), (0, 0));
+    }
+}
@@ -0,0 +1,123 @@
+use chrono::{DateTime, Utc};
+use serde_json;
+use ureq;
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use issue::{Issue, IssueState};
+
+// Issues of these priorities are listed when they are opened
+const URGENT_PRIORITY: u32 = 1;
+
+#[derive(Debug, Serialize)]
+struct Message<'a> {
+    text: &'a str,
+}
+
+// Slack uses '<url|text>' for links, so these must be escaped in the text
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// Counts of the issues, and the urgent issues opened since 'since' if known
+pub fn summary(issues: &[Issue], since: Option<DateTime<Utc>>) -> String {
+    let closed = issues
+        .iter()
+        .filter(|i| i.get_state() == IssueState::Closed)
+        .count();
+    let mut text = format!(
+        "*{} issues*: {} open, {} closed",
+        issues.len(),
+        issues.len() - closed,
+        closed
+    );
+
+    let since = match since {
+        Some(since) => since,
+        None => return text,
+    };
+    let urgent: Vec<&Issue> = issues
+        .iter()
+        .filter(|i| i.get_state() != IssueState::Closed)
+        .filter(|i| i.get_priority().is_some_and(|p| p <= URGENT_PRIORITY))
+        .filter(|i| i.created_at > since)
+        .collect();
+
+    if !urgent.is_empty() {
+        text.push_str("\nNew urgent issues since the last run:");
+        for issue in urgent {
+            text.push_str(&format!(
+                "\n• <{}|{}#{}> P{} {}",
+                issue.html_url,
+                issue.get_component(),
+                issue.number,
+                issue.get_priority().unwrap_or_default(),
+                escape(&issue.title)
+            ));
+        }
+    }
+
+    text
+}
+
+// Post the summary to a Slack incoming webhook. The time of the run is stored in
+// 'last_run' so the next one only lists the issues opened after it.
+pub fn notify_slack(webhook: &str, issues: &[Issue], last_run: &Path) -> io::Result<()> {
+    let now = Utc::now();
+    let since = fs::read_to_string(last_run)
+        .ok()
+        .and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok())
+        .map(|d| d.with_timezone(&Utc));
+    let text = summary(issues, since);
+
+    let body = serde_json::to_string(&Message { text: &text })?;
+    ureq::post(webhook)
+        .set("Content-Type", "application/json")
+        .send_string(&body)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+    if let Some(dir) = last_run.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(last_run, now.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    #[test]
+    fn summaries() {
+        let mut issues = vec![
+            issue(1, serde_json::json!({ "title": "Crash <on> start & exit" })),
+            issue(2, serde_json::json!({ "state": "closed" })),
+            issue(
+                3,
+                serde_json::json!({ "created_at": "2023-12-01T00:00:00Z" }),
+            ),
+            issue(4, serde_json::json!({})),
+        ];
+        issues[0].priority = Some(0);
+        issues[1].priority = Some(0);
+        // Opened before the last run
+        issues[2].priority = Some(1);
+        issues[3].priority = Some(2);
+
+        assert_eq!(summary(&issues, None), "*4 issues*: 3 open, 1 closed");
+        let since = "2023-12-15T00:00:00Z".parse().unwrap();
+        assert_eq!(
+            summary(&issues, Some(since)),
+            "*4 issues*: 3 open, 1 closed
+New urgent issues since the last run:
+• <https://github.com/owner/repo/issues/1|repo#1> P0 Crash &lt;on&gt; start &amp; exit"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use csv;
+
+use std::io::{self, Write};
+
+use issue::IssueCSV;
+
+// States of the Agile process, blocked issues being active
+fn state(state: &str) -> &'static str {
+    match state {
+        "blocked" => "Active",
+        "under review" => "Resolved",
+        "closed" => "Closed",
+        _ => "New",
+    }
+}
+
+// CSV for the bulk import of work items of Azure DevOps. Areas are named after
+// the components, below 'area_path' if set.
+pub fn write_ado_csv<W: Write>(
+    wtr: W,
+    rows: &[IssueCSV],
+    area_path: Option<&str>,
+) -> io::Result<()> {
+    let mut wtr = csv::Writer::from_writer(wtr);
+
+    // New work items are imported with an empty ID
+    wtr.write_record(&[
+        "ID",
+        "Work Item Type",
+        "Title",
+        "State",
+        "Tags",
+        "Area Path",
+    ])?;
+
+    for row in rows.iter() {
+        let mut tags = row.other_labels.clone();
+        if row.state == "blocked" {
+            tags.push("Blocked");
+        }
+        let area = match area_path {
+            Some(root) => format!("{}\\{}", root, row.component),
+            None => row.component.clone(),
+        };
+
+        wtr.write_record(&[
+            "",
+            row.issue_type.unwrap_or("Issue"),
+            row.title,
+            state(&row.state),
+            &tags.join("; "),
+            &area,
+        ])?;
+    }
+
+    wtr.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use client::Github;
+    use fixtures::issue;
+    use output::Options;
+
+    #[test]
+    fn ado_csv() {
+        let issues = vec![
+            issue(
+                1,
+                serde_json::json!({
+                    "title": "Crash, on start",
+                    "labels": [{ "name": "crash" }, { "name": "blocked" }, { "name": "ui" }],
+                }),
+            ),
+            issue(
+                2,
+                serde_json::json!({ "labels": [{ "name": "under review" }] }),
+            ),
+            issue(3, serde_json::json!({ "state": "closed" })),
+        ];
+        let client = Github::new("token").unwrap();
+        let options = Options::default();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, &options)).collect();
+
+        let mut output = Vec::new();
+        write_ado_csv(&mut output, &rows, Some("Product")).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "ID,Work Item Type,Title,State,Tags,Area Path
+,Issue,\"Crash, on start\",Active,crash; ui; Blocked,Product\\repo
+,Issue,Issue 2,Resolved,,Product\\repo
+,Issue,Issue 3,Closed,,Product\\repo
+"
+        );
+
+        let mut output = Vec::new();
+        write_ado_csv(&mut output, &rows[2..], None).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .ends_with(",Issue,Issue 3,Closed,,repo\n"));
+    }
+}
@@ -0,0 +1,168 @@
+use std::io::{self, Write};
+
+use issue::IssueCSV;
+use output::graph::fill_color;
+use output::html::escape;
+use output::{groups, Options};
+
+// Lanes of the board when the issues are not grouped
+const STATES: &[&str] = &["open", "blocked", "under review", "closed"];
+
+const HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Issues</title>
+<style>
+body { font-family: sans-serif; margin: 1em; }
+#board { display: flex; gap: 1em; align-items: flex-start; overflow-x: auto; }
+.lane { flex: 0 0 18em; background: #eee; border-radius: 4px; padding: 0.5em; }
+.lane h2 { font-size: 1.1em; margin: 0 0 0.5em; }
+.card { display: block; border: 1px solid #ccc; border-radius: 4px; margin-bottom: 0.5em;
+  padding: 0.5em; color: inherit; text-decoration: none; }
+.card:hover { border-color: #888; }
+.card .id { color: #555; font-size: 0.9em; }
+.card .meta { color: #555; font-size: 0.85em; margin-top: 0.3em; }
+</style>
+</head>
+<body>
+<div id="board">
+"#;
+
+const FOOTER: &str = r#"</div>
+</body>
+</html>
+"#;
+
+fn write_card<W: Write>(wtr: &mut W, row: &IssueCSV) -> io::Result<()> {
+    writeln!(
+        wtr,
+        "<a class=\"card\" href=\"{}\" style=\"background: {}\">",
+        escape(&row.url),
+        fill_color(row)
+    )?;
+    writeln!(
+        wtr,
+        "<div class=\"id\">{}{}</div>",
+        escape(&row.component),
+        escape(&row.id)
+    )?;
+    writeln!(wtr, "<div>{}</div>", escape(row.title))?;
+
+    let meta: Vec<String> = row
+        .priority
+        .map(|p| format!("P{}", p))
+        .into_iter()
+        .chain(row.assignee.clone())
+        .chain(row.milestone.map(|m| m.to_string()))
+        .collect();
+    if !meta.is_empty() {
+        writeln!(
+            wtr,
+            "<div class=\"meta\">{}</div>",
+            escape(&meta.join(" · "))
+        )?;
+    }
+
+    writeln!(wtr, "</a>")
+}
+
+fn write_lane<W: Write>(wtr: &mut W, name: &str, rows: &[&IssueCSV]) -> io::Result<()> {
+    writeln!(wtr, "<div class=\"lane\">")?;
+    writeln!(wtr, "<h2>{} ({})</h2>", escape(name), rows.len())?;
+    for row in rows.iter() {
+        write_card(wtr, row)?;
+    }
+    writeln!(wtr, "</div>")
+}
+
+// Read-only kanban board, with a lane per state or per group
+pub fn write_board<W: Write>(mut wtr: W, rows: &[IssueCSV], options: &Options) -> io::Result<()> {
+    wtr.write_all(HEADER.as_bytes())?;
+
+    if options.group_by.is_some() {
+        for (name, rows) in groups(rows, options.group_by) {
+            write_lane(&mut wtr, &name, &rows)?;
+        }
+    } else {
+        // Empty lanes are kept so the board always has the same shape
+        for state in STATES.iter() {
+            let rows: Vec<&IssueCSV> = rows.iter().filter(|r| r.state == *state).collect();
+            write_lane(&mut wtr, state, &rows)?;
+        }
+    }
+
+    wtr.write_all(FOOTER.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use client::Github;
+    use fixtures::issue;
+    use issue::Issue;
+    use output::GroupBy;
+
+    fn board(issues: &[Issue], options: &Options) -> String {
+        let client = Github::new("token").unwrap();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, options)).collect();
+
+        let mut output = Vec::new();
+        write_board(&mut output, &rows, options).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn issues() -> Vec<Issue> {
+        let mut issues = vec![
+            issue(
+                1,
+                serde_json::json!({
+                    "title": "Crash in <video>",
+                    "assignee": { "login": "alice" },
+                    "milestone": { "title": "1.0" },
+                }),
+            ),
+            issue(2, serde_json::json!({ "labels": [{ "name": "blocked" }] })),
+            issue(3, serde_json::json!({ "state": "closed" })),
+        ];
+        issues[0].priority = Some(1);
+        issues
+    }
+
+    #[test]
+    fn state_lanes() {
+        let output = board(&issues(), &Options::default());
+
+        assert!(output.starts_with("<!DOCTYPE html>"));
+        assert!(output.ends_with("</html>\n"));
+        assert!(output.contains("<h2>open (1)</h2>"));
+        assert!(output.contains("<h2>blocked (1)</h2>"));
+        // Empty lanes are kept
+        assert!(output.contains("<h2>under review (0)</h2>"));
+        assert!(output.contains("<h2>closed (1)</h2>"));
+        assert!(
+            output.contains("<a class=\"card\" href=\"https://github.com/owner/repo/issues/1\"")
+        );
+        assert!(
+            output.contains("<div class=\"id\">repo#1</div>\n<div>Crash in &lt;video&gt;</div>\n")
+        );
+        assert!(output.contains("<div class=\"meta\">P1 · alice · 1.0</div>"));
+    }
+
+    #[test]
+    fn group_lanes() {
+        let options = Options {
+            group_by: Some(GroupBy::Milestone),
+            ..Options::default()
+        };
+        let output = board(&issues(), &options);
+
+        assert!(output.contains("<h2>1.0 (1)</h2>"));
+        assert!(output.contains("<h2>no milestone (2)</h2>"));
+        assert!(output.find("1.0 (1)") < output.find("no milestone (2)"));
+        assert!(!output.contains("<h2>open"));
+    }
+}
@@ -0,0 +1,133 @@
+use serde_json;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dirs::long_path;
+use issue::IssueCSV;
+use output::html::escape;
+use output::{write, Format, Options};
+
+// Formats included in the bundle
+const FORMATS: &[Format] = &[Format::Csv, Format::Html, Format::Json, Format::Xlsx];
+
+#[derive(Debug, Serialize)]
+pub struct Metadata<'a> {
+    pub version: &'static str,
+    pub owner: &'a str,
+    pub components: &'a [String],
+    pub issues: usize,
+    // Seconds since the Unix epoch
+    pub generated_at: u64,
+}
+
+impl<'a> Metadata<'a> {
+    pub fn new(owner: &'a str, components: &'a [String], issues: usize) -> Metadata<'a> {
+        let generated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System clock is before the Unix epoch")
+            .as_secs();
+
+        Metadata {
+            version: env!("CARGO_PKG_VERSION"),
+            owner,
+            components,
+            issues,
+            generated_at,
+        }
+    }
+}
+
+fn write_index<W: Write>(mut wtr: W, metadata: &Metadata, files: &[String]) -> io::Result<()> {
+    writeln!(wtr, "<!DOCTYPE html>")?;
+    writeln!(wtr, "<html>")?;
+    writeln!(
+        wtr,
+        "<head><meta charset=\"utf-8\"><title>Issues report</title></head>"
+    )?;
+    writeln!(wtr, "<body>")?;
+    writeln!(wtr, "<h1>Issues report for {}</h1>", escape(metadata.owner))?;
+    writeln!(
+        wtr,
+        "<p>{} issues from: {}</p>",
+        metadata.issues,
+        escape(&metadata.components.join(", "))
+    )?;
+    writeln!(wtr, "<ul>")?;
+    for file in files.iter() {
+        writeln!(wtr, "<li><a href=\"{0}\">{0}</a></li>", escape(file))?;
+    }
+    writeln!(wtr, "</ul>")?;
+    writeln!(wtr, "</body>")?;
+    writeln!(wtr, "</html>")
+}
+
+pub fn write_bundle(
+    rows: &[IssueCSV],
+    options: &Options,
+    metadata: &Metadata,
+    output: &Path,
+) -> io::Result<()> {
+    let mut zip = ZipWriter::new(File::create(long_path(output))?);
+    let file_options = FileOptions::default();
+    let mut files = Vec::new();
+
+    for format in FORMATS.iter() {
+        let name = format!("issues.{}", format.extension());
+        zip.start_file(name.as_str(), file_options)?;
+        write(*format, &mut zip, rows, options)?;
+        files.push(name);
+    }
+
+    let name = "metadata.json".to_string();
+    zip.start_file(name.as_str(), file_options)?;
+    serde_json::to_writer_pretty(&mut zip, metadata)?;
+    files.push(name);
+
+    zip.start_file("index.html", file_options)?;
+    write_index(&mut zip, metadata, &files)?;
+
+    zip.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index() {
+        let components = vec!["repo".to_string(), "<other>".to_string()];
+        let metadata = Metadata {
+            version: "1.0.0",
+            owner: "owner",
+            components: &components,
+            issues: 3,
+            generated_at: 0,
+        };
+        let files = vec!["issues.csv".to_string(), "metadata.json".to_string()];
+
+        let mut output = Vec::new();
+        write_index(&mut output, &metadata, &files).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "<!DOCTYPE html>
+<html>
+<head><meta charset=\"utf-8\"><title>Issues report</title></head>
+<body>
+<h1>Issues report for owner</h1>
+<p>3 issues from: repo, &lt;other&gt;</p>
+<ul>
+<li><a href=\"issues.csv\">issues.csv</a></li>
+<li><a href=\"metadata.json\">metadata.json</a></li>
+</ul>
+</body>
+</html>
+"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use std::io::{self, Write};
+
+use issue::IssueCSV;
+
+// Dependency between two issues, as references such as 'owner/repo#42'
+struct Edge<'a> {
+    from: &'a str,
+    to: &'a str,
+    closes: bool,
+}
+
+// Open issues and their dependencies: 'a blocks b' and 'b blocked by a' are
+// both drawn as an edge from a to b
+fn graph<'r, 'a>(rows: &'r [IssueCSV<'a>]) -> (Vec<&'r IssueCSV<'a>>, Vec<Edge<'r>>) {
+    let nodes: Vec<&IssueCSV> = rows.iter().filter(|r| r.state != "closed").collect();
+
+    let mut edges = Vec::new();
+    for &row in nodes.iter() {
+        let this = row.reference.as_str();
+        edges.extend(row.blocks.iter().map(|to| Edge {
+            from: this,
+            to,
+            closes: false,
+        }));
+        edges.extend(row.blocked_by.iter().map(|from| Edge {
+            from,
+            to: this,
+            closes: false,
+        }));
+        edges.extend(row.closes.iter().map(|to| Edge {
+            from: this,
+            to,
+            closes: true,
+        }));
+    }
+    // The same dependency can be written on both issues
+    edges.sort_by(|a, b| (a.from, a.to, a.closes).cmp(&(b.from, b.to, b.closes)));
+    edges.dedup_by(|a, b| (a.from, a.to, a.closes) == (b.from, b.to, b.closes));
+
+    (nodes, edges)
+}
+
+// Urgent issues are filled in red, orange and yellow
+pub(crate) fn fill_color(row: &IssueCSV) -> &'static str {
+    match row.priority {
+        Some(0) => "#f4a6a6",
+        Some(1) => "#f8c99b",
+        Some(2) => "#fbe8a0",
+        _ => "#ffffff",
+    }
+}
+
+fn label(row: &IssueCSV) -> String {
+    format!("{}{}: {}", row.component, row.id, row.title)
+}
+
+// Graphviz graph, see https://graphviz.org/doc/info/lang.html
+pub fn write_dot<W: Write>(mut wtr: W, rows: &[IssueCSV]) -> io::Result<()> {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let (nodes, edges) = graph(rows);
+
+    writeln!(wtr, "digraph issues {{")?;
+    writeln!(wtr, "  node [shape=box, style=filled];")?;
+    for row in nodes.iter() {
+        // Blocked issues have a dashed border, the ones under review a bold one
+        let style = match row.state.as_str() {
+            "blocked" => "filled,dashed",
+            "under review" => "filled,bold",
+            _ => "filled",
+        };
+        writeln!(
+            wtr,
+            "  \"{}\" [label=\"{}\", fillcolor=\"{}\", style=\"{}\", URL=\"{}\"];",
+            escape(&row.reference),
+            escape(&label(row)),
+            fill_color(row),
+            style,
+            escape(&row.url)
+        )?;
+    }
+    for edge in edges.iter() {
+        writeln!(
+            wtr,
+            "  \"{}\" -> \"{}\"{};",
+            escape(edge.from),
+            escape(edge.to),
+            if edge.closes {
+                " [style=dotted, label=\"closes\"]"
+            } else {
+                ""
+            }
+        )?;
+    }
+    writeln!(wtr, "}}")
+}
+
+// Mermaid flowchart, see https://mermaid.js.org/syntax/flowchart.html
+pub fn write_mermaid<W: Write>(mut wtr: W, rows: &[IssueCSV]) -> io::Result<()> {
+    // Quotes cannot be escaped in labels, only replaced by their entity
+    let escape = |s: &str| s.replace('"', "#quot;");
+    let (nodes, edges) = graph(rows);
+
+    // Mermaid ids cannot contain '/' or '#', issues outside of the graph are
+    // numbered after the exported ones
+    let mut ids: Vec<&str> = nodes.iter().map(|r| r.reference.as_str()).collect();
+    for edge in edges.iter() {
+        for &reference in [edge.from, edge.to].iter() {
+            if !ids.contains(&reference) {
+                ids.push(reference);
+            }
+        }
+    }
+    let id = |reference: &str| {
+        let idx = ids.iter().position(|r| *r == reference).unwrap();
+        format!("i{}", idx)
+    };
+
+    writeln!(wtr, "flowchart LR")?;
+    for (idx, reference) in ids.iter().enumerate() {
+        match nodes.get(idx) {
+            Some(row) => writeln!(wtr, "  i{}[\"{}\"]", idx, escape(&label(row)))?,
+            None => writeln!(wtr, "  i{}[\"{}\"]", idx, escape(reference))?,
+        }
+    }
+    for edge in edges.iter() {
+        let arrow = if edge.closes { "-. closes .->" } else { "-->" };
+        writeln!(wtr, "  {} {} {}", id(edge.from), arrow, id(edge.to))?;
+    }
+
+    for (idx, row) in nodes.iter().enumerate() {
+        let border = match row.state.as_str() {
+            "blocked" => ",stroke-dasharray:5 5",
+            "under review" => ",stroke-width:3px",
+            _ => "",
+        };
+        writeln!(wtr, "  style i{} fill:{}{}", idx, fill_color(row), border)?;
+        writeln!(wtr, "  click i{} \"{}\"", idx, escape(&row.url))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use client::Github;
+    use fixtures::issue;
+    use issue::Issue;
+    use output::Options;
+
+    fn issues() -> Vec<Issue> {
+        let mut issues = vec![
+            issue(
+                1,
+                serde_json::json!({ "body": "Blocks #2\nFixes other/lib#9" }),
+            ),
+            // The same dependency written on both issues
+            issue(
+                2,
+                serde_json::json!({
+                    "title": "Say \"hi\"",
+                    "body": "Blocked by #1",
+                    "labels": [{ "name": "blocked" }],
+                }),
+            ),
+            issue(
+                3,
+                serde_json::json!({ "state": "closed", "body": "Blocks #1" }),
+            ),
+        ];
+        issues[0].priority = Some(0);
+        issues
+    }
+
+    fn write(write: fn(&mut Vec<u8>, &[IssueCSV]) -> io::Result<()>) -> String {
+        let client = Github::new("token").unwrap();
+        let options = Options::default();
+        let issues = issues();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, &options)).collect();
+
+        let mut output = Vec::new();
+        write(&mut output, &rows).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn dot() {
+        assert_eq!(
+            write(|wtr, rows| write_dot(wtr, rows)),
+            r##"digraph issues {
+  node [shape=box, style=filled];
+  "owner/repo#1" [label="repo#1: Issue 1", fillcolor="#f4a6a6", style="filled", URL="https://github.com/owner/repo/issues/1"];
+  "owner/repo#2" [label="repo#2: Say \"hi\"", fillcolor="#ffffff", style="filled,dashed", URL="https://github.com/owner/repo/issues/2"];
+  "owner/repo#1" -> "other/lib#9" [style=dotted, label="closes"];
+  "owner/repo#1" -> "owner/repo#2";
+}
+"##
+        );
+    }
+
+    #[test]
+    fn mermaid() {
+        assert_eq!(
+            write(|wtr, rows| write_mermaid(wtr, rows)),
+            r##"flowchart LR
+  i0["repo#1: Issue 1"]
+  i1["repo#2: Say #quot;hi#quot;"]
+  i2["other/lib#9"]
+  i0 -. closes .-> i2
+  i0 --> i1
+  style i0 fill:#f4a6a6
+  click i0 "https://github.com/owner/repo/issues/1"
+  style i1 fill:#ffffff,stroke-dasharray:5 5
+  click i1 "https://github.com/owner/repo/issues/2"
+"##
+        );
+    }
+}
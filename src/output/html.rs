@@ -0,0 +1,278 @@
+use std::io::{self, Write};
+
+use column::Column;
+use issue::IssueCSV;
+use output::{groups, nested, Options};
+
+const HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Issues</title>
+<style>
+body { font-family: sans-serif; margin: 1em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }
+th { background: #eee; cursor: pointer; user-select: none; }
+th.asc::after { content: " \25B2"; }
+th.desc::after { content: " \25BC"; }
+tr.state-open td.state { background: #d4edda; }
+tr.state-blocked td.state { background: #f8d7da; }
+tr.state-under-review td.state { background: #fff3cd; }
+tr.state-closed { color: #888; }
+tr.stale td { font-style: italic; background: #f3f3f3; }
+tr.group th { background: #ddd; cursor: default; font-size: 1.1em; }
+#filter { margin-bottom: 1em; padding: 4px; width: 30em; }
+</style>
+</head>
+<body>
+<input id="filter" type="search" placeholder="Filter issues...">
+<select id="state-filter">
+<option value="">all states</option>
+<option value="open">open</option>
+<option value="blocked">blocked</option>
+<option value="under review">under review</option>
+<option value="closed">closed</option>
+</select>
+<table id="issues">
+<thead>
+<tr>
+"#;
+
+const HEADER_END: &str = r#"</tr>
+</thead>
+<tbody>
+"#;
+
+const FOOTER: &str = r#"</tbody>
+</table>
+<script>
+(function() {
+  var table = document.getElementById("issues");
+  var tbodies = Array.prototype.slice.call(table.tBodies);
+  var filter = document.getElementById("filter");
+  var stateFilter = document.getElementById("state-filter");
+
+  // Each group has its own body, starting with its heading
+  function issueRows(tbody) {
+    return Array.prototype.filter.call(tbody.rows, function(row) {
+      return !row.classList.contains("group");
+    });
+  }
+
+  function applyFilter() {
+    var text = filter.value.toLowerCase();
+    var state = stateFilter.value;
+    tbodies.forEach(function(tbody) {
+      issueRows(tbody).forEach(function(row) {
+        var matchText = row.textContent.toLowerCase().indexOf(text) !== -1;
+        var matchState = !state || row.dataset.state === state;
+        row.style.display = matchText && matchState ? "" : "none";
+      });
+    });
+  }
+
+  filter.addEventListener("input", applyFilter);
+  stateFilter.addEventListener("change", applyFilter);
+
+  Array.prototype.forEach.call(table.tHead.rows[0].cells, function(th, column) {
+    th.addEventListener("click", function() {
+      var asc = !th.classList.contains("asc");
+      Array.prototype.forEach.call(table.tHead.rows[0].cells, function(other) {
+        other.classList.remove("asc", "desc");
+      });
+      th.classList.add(asc ? "asc" : "desc");
+
+      tbodies.forEach(function(tbody) {
+        var rows = issueRows(tbody);
+        rows.sort(function(a, b) {
+          var va = a.cells[column].textContent;
+          var vb = b.cells[column].textContent;
+          var cmp = va.localeCompare(vb, undefined, { numeric: true });
+          return asc ? cmp : -cmp;
+        });
+        rows.forEach(function(row) { tbody.appendChild(row); });
+      });
+    });
+  });
+})();
+</script>
+</body>
+</html>
+"#;
+
+pub(crate) fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// 'depth' is the level of sub-issues, indenting their title
+fn write_row<W: Write>(
+    wtr: &mut W,
+    issue: &IssueCSV,
+    columns: &[Column],
+    depth: usize,
+) -> io::Result<()> {
+    writeln!(
+        wtr,
+        "<tr class=\"state-{}{}\" data-state=\"{}\">",
+        issue.state.replace(' ', "-"),
+        if issue.stale == Some(true) {
+            " stale"
+        } else {
+            ""
+        },
+        escape(&issue.state)
+    )?;
+
+    for column in columns.iter() {
+        let value = escape(&issue.field(*column));
+
+        match *column {
+            Column::Title if depth > 0 => writeln!(
+                wtr,
+                "<td style=\"padding-left: {}em\"><a href=\"{}\">{}</a></td>",
+                depth * 2,
+                escape(&issue.url),
+                value
+            )?,
+            Column::Id | Column::Title | Column::Url => writeln!(
+                wtr,
+                "<td><a href=\"{}\">{}</a></td>",
+                escape(&issue.url),
+                value
+            )?,
+            Column::State => writeln!(wtr, "<td class=\"state\">{}</td>", value)?,
+            _ => writeln!(wtr, "<td>{}</td>", value)?,
+        }
+    }
+
+    writeln!(wtr, "</tr>")
+}
+
+pub fn write_html<W: Write>(mut wtr: W, rows: &[IssueCSV], options: &Options) -> io::Result<()> {
+    wtr.write_all(HEADER.as_bytes())?;
+    for column in options.columns.iter() {
+        writeln!(wtr, "<th>{}</th>", escape(options.header(*column)))?;
+    }
+    wtr.write_all(HEADER_END.as_bytes())?;
+
+    for (i, (name, rows)) in groups(rows, options.group_by).into_iter().enumerate() {
+        if i > 0 {
+            writeln!(wtr, "</tbody>\n<tbody>")?;
+        }
+        if options.group_by.is_some() {
+            writeln!(
+                wtr,
+                "<tr class=\"group\"><th colspan=\"{}\">{} ({})</th></tr>",
+                options.columns.len(),
+                escape(&name),
+                rows.len()
+            )?;
+        }
+
+        if options.nest_sub_issues {
+            for (depth, row) in nested(&rows) {
+                write_row(&mut wtr, row, &options.columns, depth)?;
+            }
+        } else {
+            for row in rows {
+                write_row(&mut wtr, row, &options.columns, 0)?;
+            }
+        }
+    }
+
+    wtr.write_all(FOOTER.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{TimeZone, Utc};
+    use serde_json;
+
+    use client::Github;
+    use fixtures::issue;
+    use output::GroupBy;
+
+    fn html(options: &Options) -> String {
+        let issues = vec![
+            issue(1, serde_json::json!({ "title": "<b>Crash</b> & co" })),
+            issue(
+                2,
+                serde_json::json!({
+                    "labels": [{ "name": "under review" }],
+                    "milestone": { "title": "1.0" },
+                    "updated_at": "2024-07-01T00:00:00Z",
+                }),
+            ),
+        ];
+        let client = Github::new("token").unwrap();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, options)).collect();
+
+        let mut output = Vec::new();
+        write_html(&mut output, &rows, options).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn escaped() {
+        assert_eq!(
+            escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        assert_eq!(escape("Crash"), "Crash");
+    }
+
+    #[test]
+    fn rows() {
+        let options = Options {
+            columns: vec![Column::Id, Column::Title, Column::State],
+            stale_since: Some(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap()),
+            ..Options::default()
+        };
+        let output = html(&options);
+
+        assert!(output.starts_with("<!DOCTYPE html>"));
+        assert!(output.ends_with("</html>\n"));
+        assert!(output.contains("<th>id</th>\n<th>title</th>\n<th>state</th>\n"));
+        assert!(output.contains(
+            "<tr class=\"state-open stale\" data-state=\"open\">
+<td><a href=\"https://github.com/owner/repo/issues/1\">#1</a></td>
+<td><a href=\"https://github.com/owner/repo/issues/1\">&lt;b&gt;Crash&lt;/b&gt; &amp; co</a></td>
+<td class=\"state\">open</td>
+</tr>
+"
+        ));
+        assert!(output.contains("<tr class=\"state-under-review\" data-state=\"under review\">"));
+        assert!(!output.contains("class=\"group\""));
+    }
+
+    #[test]
+    fn group_sections() {
+        let options = Options {
+            columns: vec![Column::Id, Column::Title],
+            group_by: Some(GroupBy::Milestone),
+            ..Options::default()
+        };
+        let output = html(&options);
+
+        // Each group has its own body
+        assert!(output.contains("<tr class=\"group\"><th colspan=\"2\">1.0 (1)</th></tr>"));
+        assert!(output.contains(
+            "</tbody>\n<tbody>\n<tr class=\"group\"><th colspan=\"2\">no milestone (1)</th></tr>"
+        ));
+    }
+}
@@ -0,0 +1,179 @@
+use chrono::{Duration, NaiveDate, Utc};
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use issue::IssueCSV;
+
+// Lines longer than this many bytes have to be folded
+const MAX_LINE: usize = 75;
+
+// Escape the special characters of TEXT values
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Lines end with CRLF and are folded, continuation lines starting with a space
+fn write_line<W: Write>(wtr: &mut W, line: &str) -> io::Result<()> {
+    let mut start = 0;
+    let mut limit = MAX_LINE;
+    for (idx, c) in line.char_indices() {
+        // Never split a multi-byte character
+        if idx + c.len_utf8() - start > limit {
+            write!(wtr, "{}\r\n ", &line[start..idx])?;
+            start = idx;
+            // Leave room for the leading space
+            limit = MAX_LINE - 1;
+        }
+    }
+    write!(wtr, "{}\r\n", &line[start..])
+}
+
+// 'Release 1.0' -> 'release-1-0'
+fn slug(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+// iCalendar file with an all-day event on the due date of each milestone, see
+// https://www.rfc-editor.org/rfc/rfc5545. Milestones of different repositories
+// sharing their title and due date are merged.
+pub fn write_ics<W: Write>(mut wtr: W, rows: &[IssueCSV]) -> io::Result<()> {
+    // (open, closed) issues by milestone
+    let mut milestones: BTreeMap<(NaiveDate, &str), (usize, usize)> = BTreeMap::new();
+    for row in rows.iter() {
+        if let (Some(title), Some(due)) = (row.milestone, row.dates.milestone_due) {
+            let counts = milestones.entry((due.date_naive(), title)).or_default();
+            if row.state == "closed" {
+                counts.1 += 1;
+            } else {
+                counts.0 += 1;
+            }
+        }
+    }
+
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    write_line(&mut wtr, "BEGIN:VCALENDAR")?;
+    write_line(&mut wtr, "VERSION:2.0")?;
+    write_line(&mut wtr, "PRODID:-//github-issues//milestones//EN")?;
+    write_line(&mut wtr, "CALSCALE:GREGORIAN")?;
+
+    for (&(due, title), &(open, closed)) in milestones.iter() {
+        let date = |d: NaiveDate| d.format("%Y%m%d").to_string();
+
+        write_line(&mut wtr, "BEGIN:VEVENT")?;
+        write_line(
+            &mut wtr,
+            &format!("UID:{}-{}@github-issues", slug(title), date(due)),
+        )?;
+        write_line(&mut wtr, &format!("DTSTAMP:{}", stamp))?;
+        write_line(&mut wtr, &format!("DTSTART;VALUE=DATE:{}", date(due)))?;
+        write_line(
+            &mut wtr,
+            &format!("DTEND;VALUE=DATE:{}", date(due + Duration::days(1))),
+        )?;
+        write_line(
+            &mut wtr,
+            &format!(
+                "SUMMARY:{}",
+                escape(&format!("{} due ({} open issues)", title, open))
+            ),
+        )?;
+        write_line(
+            &mut wtr,
+            &format!(
+                "DESCRIPTION:{}",
+                escape(&format!("{} open and {} closed issues", open, closed))
+            ),
+        )?;
+        write_line(&mut wtr, "TRANSP:TRANSPARENT")?;
+        write_line(&mut wtr, "END:VEVENT")?;
+    }
+
+    write_line(&mut wtr, "END:VCALENDAR")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use client::Github;
+    use fixtures::issue;
+    use output::Options;
+
+    fn line(s: &str) -> String {
+        let mut output = Vec::new();
+        write_line(&mut output, s).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn text_values() {
+        assert_eq!(escape("a;b,c\\d\ne"), r"a\;b\,c\\d\ne");
+        assert_eq!(slug("Release 1.0"), "release-1-0");
+    }
+
+    #[test]
+    fn folded_lines() {
+        assert_eq!(line("VERSION:2.0"), "VERSION:2.0\r\n");
+
+        let long = "a".repeat(160);
+        assert_eq!(
+            line(&long),
+            format!(
+                "{}\r\n {}\r\n {}\r\n",
+                "a".repeat(75),
+                "a".repeat(74),
+                "a".repeat(11)
+            )
+        );
+
+        // 'é' is two bytes long, and would end at the 76th byte
+        let accents = format!("{}é", "a".repeat(74));
+        assert_eq!(line(&accents), format!("{}\r\n é\r\n", "a".repeat(74)));
+    }
+
+    #[test]
+    fn milestone_events() {
+        let milestone =
+            serde_json::json!({ "title": "Release 1.0", "due_on": "2024-03-01T08:00:00Z" });
+        let issues = vec![
+            issue(1, serde_json::json!({ "milestone": milestone })),
+            issue(
+                2,
+                serde_json::json!({ "state": "closed", "milestone": milestone }),
+            ),
+            issue(
+                3,
+                serde_json::json!({ "milestone": { "title": "Backlog" } }),
+            ),
+        ];
+        let client = Github::new("token").unwrap();
+        let options = Options::default();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, &options)).collect();
+
+        let mut output = Vec::new();
+        write_ics(&mut output, &rows).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(output.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert_eq!(output.matches("BEGIN:VEVENT").count(), 1);
+        assert!(output.contains("UID:release-1-0-20240301@github-issues\r\n"));
+        assert!(output.contains("DTSTART;VALUE=DATE:20240301\r\nDTEND;VALUE=DATE:20240302\r\n"));
+        assert!(output.contains("SUMMARY:Release 1.0 due (1 open issues)\r\n"));
+        assert!(output.contains("DESCRIPTION:1 open and 1 closed issues\r\n"));
+    }
+}
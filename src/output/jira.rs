@@ -0,0 +1,105 @@
+use csv;
+
+use std::io::{self, Write};
+
+use issue::IssueCSV;
+
+// Date format to set in the importer
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+// P0 being the most urgent
+fn priority(priority: Option<u32>) -> &'static str {
+    match priority {
+        Some(0) => "Highest",
+        Some(1) => "High",
+        Some(2) | None => "Medium",
+        Some(3) => "Low",
+        Some(_) => "Lowest",
+    }
+}
+
+// CSV following the conventions of the Jira importer: labels cannot contain
+// spaces and each of them has its own 'Labels' column
+pub fn write_jira_csv<W: Write>(wtr: W, rows: &[IssueCSV]) -> io::Result<()> {
+    let mut wtr = csv::Writer::from_writer(wtr);
+    let labels = rows.iter().map(|r| r.other_labels.len()).max().unwrap_or(0);
+
+    let mut header = vec!["Summary", "Issue Type", "Priority", "Reporter", "Created"];
+    header.extend((0..labels).map(|_| "Labels"));
+    wtr.write_record(&header)?;
+
+    for row in rows.iter() {
+        let mut record = vec![
+            row.title.to_string(),
+            row.issue_type.unwrap_or("Task").to_string(),
+            priority(row.priority).to_string(),
+            row.reporter.unwrap_or("").to_string(),
+            row.dates.created.format(DATE_FORMAT).to_string(),
+        ];
+        record.extend((0..labels).map(|i| {
+            row.other_labels
+                .get(i)
+                .map(|l| l.replace(' ', "_"))
+                .unwrap_or_default()
+        }));
+        wtr.write_record(&record)?;
+    }
+
+    wtr.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use client::Github;
+    use fixtures::issue;
+    use issue::ExtraFields;
+    use output::Options;
+
+    #[test]
+    fn priorities() {
+        assert_eq!(priority(Some(0)), "Highest");
+        assert_eq!(priority(None), "Medium");
+        assert_eq!(priority(Some(5)), "Lowest");
+    }
+
+    #[test]
+    fn jira_csv() {
+        let mut issues = vec![
+            issue(
+                1,
+                serde_json::json!({
+                    "title": "Crash, on start",
+                    "user": { "login": "alice" },
+                    "labels": [{ "name": "good first issue" }, { "name": "P1" }, { "name": "ui" }],
+                    "created_at": "2024-01-01T10:30:00Z",
+                }),
+            ),
+            issue(2, serde_json::Value::Null),
+        ];
+        issues[0].priority = Some(1);
+        issues[0].consumed_labels = vec!["P1".to_string()];
+        issues[0].extra_fields = Some(ExtraFields {
+            issue_type: Some("Bug".to_string()),
+            ..ExtraFields::default()
+        });
+        let client = Github::new("token").unwrap();
+        let options = Options::default();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, &options)).collect();
+
+        let mut output = Vec::new();
+        write_jira_csv(&mut output, &rows).unwrap();
+
+        // Each label has its own column
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Summary,Issue Type,Priority,Reporter,Created,Labels,Labels
+\"Crash, on start\",Bug,High,alice,2024-01-01 10:30,good_first_issue,ui
+Issue 2,Task,Medium,,2024-01-01 00:00,,
+"
+        );
+    }
+}
@@ -0,0 +1,698 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, Utc};
+use csv;
+use serde_json::{self, Map, Value};
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use calendar::Calendar;
+use client::Github;
+use column::{Column, DEFAULT_COLUMNS};
+use dirs::long_path;
+use issue::{Issue, IssueCSV};
+use progress;
+
+pub mod ado;
+pub mod board;
+pub mod bundle;
+pub mod graph;
+pub mod html;
+pub mod ics;
+pub mod jira;
+pub mod prometheus;
+pub mod sqlite;
+pub mod table;
+pub mod xlsx;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Csv,
+    Tsv,
+    Html,
+    Json,
+    Xlsx,
+    Sqlite,
+    Prometheus,
+    Table,
+    Dot,
+    Mermaid,
+    Ics,
+    Board,
+    JiraCsv,
+    AdoCsv,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match *self {
+            Format::Csv => "csv",
+            Format::Tsv => "tsv",
+            Format::Html => "html",
+            Format::Json => "json",
+            Format::Xlsx => "xlsx",
+            Format::Sqlite => "db",
+            Format::Prometheus => "prom",
+            Format::Table => "txt",
+            Format::Dot => "dot",
+            Format::Mermaid => "mmd",
+            Format::Ics => "ics",
+            Format::Board => "html",
+            Format::JiraCsv => "csv",
+            Format::AdoCsv => "csv",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "csv" => Ok(Format::Csv),
+            "tsv" => Ok(Format::Tsv),
+            "html" => Ok(Format::Html),
+            "json" => Ok(Format::Json),
+            "xlsx" => Ok(Format::Xlsx),
+            "sqlite" => Ok(Format::Sqlite),
+            "prometheus" => Ok(Format::Prometheus),
+            "table" => Ok(Format::Table),
+            "dot" => Ok(Format::Dot),
+            "mermaid" => Ok(Format::Mermaid),
+            "ics" => Ok(Format::Ics),
+            "board" => Ok(Format::Board),
+            "jira-csv" => Ok(Format::JiraCsv),
+            "ado-csv" => Ok(Format::AdoCsv),
+            _ => Err(format!("unknown output format '{}'", s)),
+        }
+    }
+}
+
+// Field splitting the output into sections
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroupBy {
+    Component,
+    Milestone,
+    Assignee,
+    Priority,
+    BoardColumn,
+    Status,
+}
+
+impl GroupBy {
+    pub fn column(&self) -> Column {
+        match *self {
+            GroupBy::Component => Column::Component,
+            GroupBy::Milestone => Column::Milestone,
+            GroupBy::Assignee => Column::Assignee,
+            GroupBy::Priority => Column::Priority,
+            GroupBy::BoardColumn => Column::BoardColumn,
+            GroupBy::Status => Column::Status,
+        }
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "component" => Ok(GroupBy::Component),
+            "milestone" => Ok(GroupBy::Milestone),
+            "assignee" => Ok(GroupBy::Assignee),
+            "priority" => Ok(GroupBy::Priority),
+            "board_column" => Ok(GroupBy::BoardColumn),
+            "status" => Ok(GroupBy::Status),
+            _ => Err(format!("unknown group '{}'", s)),
+        }
+    }
+}
+
+// Timezone the dates are rendered in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Timezone {
+    Utc,
+    Local,
+    Offset(FixedOffset),
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    // 'UTC', 'local' or an offset such as '+02:00'
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "utc" | "UTC" => Ok(Timezone::Utc),
+            "local" => Ok(Timezone::Local),
+            _ => s
+                .parse::<FixedOffset>()
+                .map(Timezone::Offset)
+                .map_err(|_| format!("unknown timezone, expected utc, local or +hh:mm: '{}'", s)),
+        }
+    }
+}
+
+// Check the strftime format once instead of failing when rendering the dates
+pub fn parse_date_format(s: &str) -> Result<String, String> {
+    if StrftimeItems::new(s).any(|item| item == Item::Error) {
+        Err(format!("invalid date format: '{}'", s))
+    } else {
+        Ok(s.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quoting {
+    Necessary,
+    Always,
+    NonNumeric,
+    Never,
+}
+
+impl Quoting {
+    fn quote_style(&self) -> csv::QuoteStyle {
+        match *self {
+            Quoting::Necessary => csv::QuoteStyle::Necessary,
+            Quoting::Always => csv::QuoteStyle::Always,
+            Quoting::NonNumeric => csv::QuoteStyle::NonNumeric,
+            Quoting::Never => csv::QuoteStyle::Never,
+        }
+    }
+}
+
+impl FromStr for Quoting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "necessary" => Ok(Quoting::Necessary),
+            "always" => Ok(Quoting::Always),
+            "non-numeric" => Ok(Quoting::NonNumeric),
+            "never" => Ok(Quoting::Never),
+            _ => Err(format!("unknown quoting style '{}'", s)),
+        }
+    }
+}
+
+// How to neutralize CSV fields which spreadsheets would interpret as formulas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sanitization {
+    // Prefix the field with a single quote
+    Prefix,
+    // Remove the leading formula characters
+    Strip,
+    Off,
+}
+
+impl Sanitization {
+    fn is_formula_char(c: char) -> bool {
+        matches!(c, '=' | '+' | '-' | '@' | '\t' | '\r')
+    }
+
+    pub fn apply(&self, value: String) -> String {
+        let first = value.chars().next();
+
+        match first {
+            Some(c) if Sanitization::is_formula_char(c) => match *self {
+                Sanitization::Prefix => format!("'{}", value),
+                Sanitization::Strip => value
+                    .trim_start_matches(Sanitization::is_formula_char)
+                    .to_string(),
+                Sanitization::Off => value,
+            },
+            _ => value,
+        }
+    }
+}
+
+impl FromStr for Sanitization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prefix" => Ok(Sanitization::Prefix),
+            "strip" => Ok(Sanitization::Strip),
+            "off" => Ok(Sanitization::Off),
+            _ => Err(format!("unknown sanitization policy '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    // Columns to export, in order
+    pub columns: Vec<Column>,
+    // Custom header names, for columns not using their default name
+    pub headers: HashMap<Column, String>,
+    // Field delimiter of the CSV output, TSV always uses tabs
+    pub delimiter: u8,
+    pub quoting: Quoting,
+    // Write CSV files Excel opens correctly: UTF-8 BOM and CRLF line endings.
+    // Dates are written as 'yyyy-mm-dd' by default, which Excel recognizes.
+    pub excel: bool,
+    pub sanitization: Sanitization,
+    // Separator between the assignees of an issue
+    pub assignee_separator: String,
+    // Separator between the labels of an issue
+    pub label_separator: String,
+    // Only export the primary assignee
+    pub primary_assignee_only: bool,
+    // Split the output into numbered files of at most this number of issues
+    pub max_rows_per_file: Option<usize>,
+    // Rewrite the links to the issues, see Issue::get_url()
+    pub url_template: Option<String>,
+    pub group_by: Option<GroupBy>,
+    // List the sub-issues right after their parent in HTML outputs
+    pub nest_sub_issues: bool,
+    // Number of characters of the bodies exported in the 'body' column
+    pub body_length: usize,
+    // Open issues not updated since this time are stale
+    pub stale_since: Option<DateTime<Utc>>,
+    pub timezone: Timezone,
+    // strftime format of the dates
+    pub date_format: String,
+    // Count the age of the issues in business days
+    pub calendar: Option<Calendar>,
+    // Color the rows of the table format by state and priority, and the
+    // changes of the diff format
+    pub color: bool,
+    // Parent of the areas of the Azure DevOps work items
+    pub area_path: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            columns: DEFAULT_COLUMNS.to_vec(),
+            headers: HashMap::new(),
+            delimiter: b',',
+            quoting: Quoting::Necessary,
+            excel: false,
+            sanitization: Sanitization::Prefix,
+            assignee_separator: ", ".to_string(),
+            label_separator: ", ".to_string(),
+            primary_assignee_only: false,
+            max_rows_per_file: None,
+            url_template: None,
+            group_by: None,
+            stale_since: None,
+            body_length: 200,
+            nest_sub_issues: false,
+            timezone: Timezone::Utc,
+            date_format: "%Y-%m-%d".to_string(),
+            calendar: None,
+            color: false,
+            area_path: None,
+        }
+    }
+}
+
+impl Options {
+    pub fn header(&self, column: Column) -> &str {
+        match self.headers.get(&column) {
+            Some(header) => header.as_str(),
+            None => column.name(),
+        }
+    }
+
+    fn in_timezone(&self, date: &DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self.timezone {
+            Timezone::Utc => date.fixed_offset(),
+            Timezone::Local => date.with_timezone(&Local).fixed_offset(),
+            Timezone::Offset(offset) => date.with_timezone(&offset),
+        }
+    }
+
+    pub fn format_date(&self, date: &DateTime<Utc>) -> String {
+        self.in_timezone(date).format(&self.date_format).to_string()
+    }
+
+    // Day of the date in the timezone of the output
+    pub fn local_day(&self, date: &DateTime<Utc>) -> NaiveDate {
+        self.in_timezone(date).date_naive()
+    }
+}
+
+// Compute the exported fields of all issues
+pub fn rows<'a>(
+    client: &Github,
+    issues: &'a [Issue],
+    options: &Options,
+    quiet: bool,
+) -> Vec<IssueCSV<'a>> {
+    let progress = progress::bar(issues.len(), "issues", quiet);
+    progress.set_message("Computing columns");
+
+    let rows = issues
+        .iter()
+        .map(|issue| {
+            trace!("{:?} {}", issue, issue.get_component());
+            progress.inc(1);
+            issue.csv(client, options)
+        })
+        .collect();
+    progress.finish_and_clear();
+    rows
+}
+
+// Rows of each group, sorted by name with the rows without value last. Rows keep
+// their order within their group. Without grouping, all the rows are in a single
+// unnamed group.
+pub fn groups<'r, 'a>(
+    rows: &'r [IssueCSV<'a>],
+    group_by: Option<GroupBy>,
+) -> Vec<(String, Vec<&'r IssueCSV<'a>>)> {
+    let column = match group_by {
+        Some(group_by) => group_by.column(),
+        None => return vec![(String::new(), rows.iter().collect())],
+    };
+
+    let mut groups: Vec<(String, Vec<&IssueCSV>)> = Vec::new();
+    for row in rows.iter() {
+        let name = row.field(column);
+        match groups.iter_mut().find(|g| g.0 == name) {
+            Some(group) => group.1.push(row),
+            None => groups.push((name, vec![row])),
+        }
+    }
+    groups.sort_by(|a, b| (a.0.is_empty(), &a.0).cmp(&(b.0.is_empty(), &b.0)));
+
+    groups
+        .into_iter()
+        .map(|(name, rows)| {
+            if name.is_empty() {
+                (format!("no {}", column.name()), rows)
+            } else {
+                (name, rows)
+            }
+        })
+        .collect()
+}
+
+// Move the sub-issues right after their parent, along with their depth in the
+// hierarchy. Issues whose parent is not listed stay at the top level.
+pub fn nested<'r, 'a>(rows: &[&'r IssueCSV<'a>]) -> Vec<(usize, &'r IssueCSV<'a>)> {
+    let position = |reference: &str| rows.iter().position(|r| r.reference == reference);
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); rows.len()];
+    let mut roots = Vec::new();
+    for (idx, row) in rows.iter().enumerate() {
+        match row.parent.and_then(position) {
+            Some(parent) if parent != idx => children[parent].push(idx),
+            _ => roots.push(idx),
+        }
+    }
+
+    let mut nested = Vec::with_capacity(rows.len());
+    let mut stack: Vec<(usize, usize)> = roots.into_iter().rev().map(|idx| (0, idx)).collect();
+    while let Some((depth, idx)) = stack.pop() {
+        nested.push((depth, rows[idx]));
+        stack.extend(children[idx].iter().rev().map(|&child| (depth + 1, child)));
+    }
+
+    nested
+}
+
+pub fn write_csv<W: Write>(
+    mut wtr: W,
+    rows: &[IssueCSV],
+    options: &Options,
+    delimiter: u8,
+) -> io::Result<()> {
+    let terminator = if options.excel {
+        // Excel assumes files without BOM are in the local ANSI encoding
+        wtr.write_all(b"\xEF\xBB\xBF")?;
+        csv::Terminator::CRLF
+    } else {
+        csv::Terminator::Any(b'\n')
+    };
+
+    let mut wtr = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .quote_style(options.quoting.quote_style())
+        .terminator(terminator)
+        .from_writer(wtr);
+
+    // Groups are written as leading columns
+    let grouped = options.group_by.is_some();
+
+    let mut header = Vec::new();
+    if grouped {
+        header.push("group");
+        header.push("group_count");
+    }
+    header.extend(options.columns.iter().map(|c| options.header(*c)));
+    wtr.write_record(&header)?;
+
+    for (name, rows) in groups(rows, options.group_by) {
+        let count = rows.len().to_string();
+
+        for row in rows {
+            let mut record = Vec::new();
+            if grouped {
+                record.push(options.sanitization.apply(name.clone()));
+                record.push(count.clone());
+            }
+            record.extend(options.columns.iter().map(|c| {
+                let field = row.field(*c);
+                if c.is_numeric() {
+                    field
+                } else {
+                    options.sanitization.apply(field)
+                }
+            }));
+            wtr.write_record(&record)?;
+        }
+    }
+
+    wtr.flush()
+}
+
+pub fn write_json<W: Write>(wtr: W, rows: &[IssueCSV], options: &Options) -> io::Result<()> {
+    let mut records = Vec::new();
+
+    for row in rows.iter() {
+        let mut all = match serde_json::to_value(row)? {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        };
+        let mut record = Map::new();
+
+        for column in options.columns.iter() {
+            let value = all.remove(column.name()).unwrap_or(Value::Null);
+            record.insert(options.header(*column).to_string(), value);
+        }
+        records.push(Value::Object(record));
+    }
+
+    serde_json::to_writer_pretty(wtr, &records)?;
+    Ok(())
+}
+
+// Formats without sections only list the rows of each group together
+fn ordered<'a>(rows: &[IssueCSV<'a>], options: &Options) -> Vec<IssueCSV<'a>> {
+    groups(rows, options.group_by)
+        .into_iter()
+        .flat_map(|(_, rows)| rows)
+        .cloned()
+        .collect()
+}
+
+// Render the rows in a format which can be written to any stream
+pub fn write<W: Write>(
+    format: Format,
+    wtr: W,
+    rows: &[IssueCSV],
+    options: &Options,
+) -> io::Result<()> {
+    match format {
+        Format::Csv => write_csv(wtr, rows, options, options.delimiter),
+        Format::Tsv => write_csv(wtr, rows, options, b'\t'),
+        Format::Html => html::write_html(wtr, rows, options),
+        Format::Json => write_json(wtr, &ordered(rows, options), options),
+        Format::Xlsx => xlsx::write_xlsx(wtr, &ordered(rows, options), options),
+        Format::Prometheus => prometheus::write_prometheus(wtr, rows),
+        Format::Table => table::write_table(wtr, &ordered(rows, options), options),
+        Format::Dot => graph::write_dot(wtr, rows),
+        Format::Mermaid => graph::write_mermaid(wtr, rows),
+        Format::Ics => ics::write_ics(wtr, rows),
+        Format::Board => board::write_board(wtr, rows, options),
+        Format::JiraCsv => jira::write_jira_csv(wtr, &ordered(rows, options)),
+        Format::AdoCsv => {
+            ado::write_ado_csv(wtr, &ordered(rows, options), options.area_path.as_deref())
+        }
+        Format::Sqlite => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "sqlite output can only be written to a file",
+        )),
+    }
+}
+
+pub fn is_stdout(output: &Path) -> bool {
+    output.to_str() == Some("-")
+}
+
+// Open the output file, '-' meaning the standard output
+pub fn open(output: &Path) -> Box<dyn Write> {
+    if is_stdout(output) {
+        Box::new(io::stdout())
+    } else {
+        let file = File::create(long_path(output)).expect("Failed to create output file");
+        Box::new(BufWriter::new(file))
+    }
+}
+
+// 'issues.csv' -> 'issues-1.csv'
+fn part_path(output: &Path, part: usize) -> PathBuf {
+    let stem = output
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("issues");
+    let name = match output.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}-{}.{}", stem, part, ext),
+        None => format!("{}-{}", stem, part),
+    };
+
+    output.with_file_name(name)
+}
+
+fn write_file(format: Format, rows: &[IssueCSV], options: &Options, output: &Path) {
+    let mut wtr = open(output);
+
+    write(format, &mut wtr, rows, options).expect("Failed to write output");
+    wtr.flush().expect("Failed to flush output");
+}
+
+pub fn generate(format: Format, rows: &[IssueCSV], options: &Options, output: &PathBuf) {
+    if format == Format::Sqlite && !is_stdout(output) {
+        sqlite::write_sqlite(rows, output).expect("Failed to update database");
+        return;
+    }
+
+    match options.max_rows_per_file {
+        Some(max) if rows.len() > max && !is_stdout(output) => {
+            for (i, part) in rows.chunks(max).enumerate() {
+                write_file(format, part, options, &part_path(output, i + 1));
+            }
+        }
+        _ => write_file(format, rows, options, output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use fixtures::issue;
+    use issue::ExtraFields;
+
+    #[test]
+    fn sanitization_apply() {
+        let apply = |s: Sanitization, value: &str| s.apply(value.to_string());
+
+        assert_eq!(
+            apply(Sanitization::Prefix, "=HYPERLINK(\"x\")"),
+            "'=HYPERLINK(\"x\")"
+        );
+        assert_eq!(apply(Sanitization::Prefix, "@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(apply(Sanitization::Strip, "+-=cmd"), "cmd");
+        assert_eq!(apply(Sanitization::Strip, "\t\rvalue"), "value");
+        assert_eq!(apply(Sanitization::Off, "=1+1"), "=1+1");
+
+        for s in [Sanitization::Prefix, Sanitization::Strip, Sanitization::Off] {
+            assert_eq!(apply(s, "Crash on start"), "Crash on start");
+            assert_eq!(apply(s, "a=b"), "a=b");
+            assert_eq!(apply(s, ""), "");
+        }
+    }
+
+    #[test]
+    fn sanitization_from_str() {
+        assert_eq!("prefix".parse(), Ok(Sanitization::Prefix));
+        assert_eq!("strip".parse(), Ok(Sanitization::Strip));
+        assert_eq!("off".parse(), Ok(Sanitization::Off));
+        assert_eq!(
+            "Prefix".parse::<Sanitization>(),
+            Err("unknown sanitization policy 'Prefix'".to_string())
+        );
+        assert!("".parse::<Sanitization>().is_err());
+    }
+
+    #[test]
+    fn json_columns() {
+        let mut issue: Issue = serde_json::from_value(serde_json::json!({
+            "title": "Crash",
+            "html_url": "https://github.com/owner/repo/issues/1",
+            "number": 1,
+            "repository_url": "https://api.github.com/repos/owner/repo",
+            "labels": [],
+            "state": "open",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+        }))
+        .unwrap();
+        issue.extra_fields = Some(ExtraFields {
+            issue_type: Some("Bug".to_string()),
+            ..ExtraFields::default()
+        });
+
+        let mut options = Options {
+            columns: vec![Column::Id, Column::Type, Column::Comments],
+            ..Options::default()
+        };
+        options
+            .headers
+            .insert(Column::Comments, "Comment count".to_string());
+        let client = Github::new("token").unwrap();
+        let rows = vec![issue.csv(&client, &options)];
+
+        let mut output = Vec::new();
+        write_json(&mut output, &rows, &options).unwrap();
+        let json: Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!([{"id": "#1", "type": "Bug", "Comment count": 0}])
+        );
+    }
+
+    #[test]
+    fn nested_sub_issues() {
+        let parents = [
+            None,
+            Some("owner/repo#3"),
+            Some("owner/repo#1"),
+            Some("owner/lib#9"),
+            Some("owner/repo#2"),
+        ];
+        let issues: Vec<Issue> = parents
+            .iter()
+            .enumerate()
+            .map(|(i, parent)| {
+                let mut issue = issue(i as u32 + 1, Value::Null);
+                issue.extra_fields = Some(ExtraFields {
+                    parent: parent.map(String::from),
+                    ..ExtraFields::default()
+                });
+                issue
+            })
+            .collect();
+        let client = Github::new("token").unwrap();
+        let options = Options::default();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, &options)).collect();
+        let rows: Vec<&IssueCSV> = rows.iter().collect();
+
+        // Sub-issues listed before their parent are moved after it, and the ones
+        // whose parent is not exported stay at the top level
+        assert_eq!(
+            nested(&rows)
+                .into_iter()
+                .map(|(depth, row)| (depth, row.number))
+                .collect::<Vec<(usize, u32)>>(),
+            vec![(0, 1), (1, 3), (2, 2), (3, 5), (0, 4)]
+        );
+    }
+}
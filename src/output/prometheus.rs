@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use issue::IssueCSV;
+
+// Label values are quoted, backslashes, quotes and new lines being escaped
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+// Expose the number of open issues as a Prometheus gauge, see
+// https://prometheus.io/docs/instrumenting/exposition_formats/
+pub fn write_prometheus<W: Write>(mut wtr: W, rows: &[IssueCSV]) -> io::Result<()> {
+    let mut counts: BTreeMap<(&str, String, &str), usize> = BTreeMap::new();
+
+    for row in rows.iter().filter(|r| r.state != "closed") {
+        let priority = row.priority.map(|p| p.to_string()).unwrap_or_default();
+        *counts
+            .entry((row.component.as_str(), priority, row.state.as_str()))
+            .or_insert(0) += 1;
+    }
+
+    writeln!(wtr, "# HELP github_issues_open Number of open issues.")?;
+    writeln!(wtr, "# TYPE github_issues_open gauge")?;
+    for (&(component, ref priority, state), count) in counts.iter() {
+        writeln!(
+            wtr,
+            "github_issues_open{{component=\"{}\",priority=\"{}\",state=\"{}\"}} {}",
+            escape(component),
+            escape(priority),
+            escape(state),
+            count
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use client::Github;
+    use fixtures::issue;
+    use output::Options;
+
+    #[test]
+    fn open_issues_gauge() {
+        let mut issues = vec![
+            issue(1, serde_json::Value::Null),
+            issue(2, serde_json::Value::Null),
+            issue(3, serde_json::json!({ "labels": [{ "name": "blocked" }] })),
+            issue(4, serde_json::json!({ "state": "closed" })),
+            issue(5, serde_json::Value::Null),
+        ];
+        issues[0].priority = Some(1);
+        issues[1].priority = Some(1);
+        issues[4].component = Some("media \"av\"".to_string());
+        let client = Github::new("token").unwrap();
+        let options = Options::default();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, &options)).collect();
+
+        let mut output = Vec::new();
+        write_prometheus(&mut output, &rows).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            r#"# HELP github_issues_open Number of open issues.
+# TYPE github_issues_open gauge
+github_issues_open{component="media \"av\"",priority="",state="open"} 1
+github_issues_open{component="repo",priority="",state="blocked"} 1
+github_issues_open{component="repo",priority="1",state="open"} 2
+"#
+        );
+        assert_eq!(escape("a\\b\nc"), "a\\\\b\\nc");
+    }
+}
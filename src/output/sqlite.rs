@@ -0,0 +1,384 @@
+use rusqlite::{Connection, Result};
+use url::Url;
+
+use std::path::Path;
+
+use dirs::long_path;
+use issue::IssueCSV;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    taken_at TEXT NOT NULL,
+    issue_count INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS issues (
+    -- 'owner/repo#42', as repositories may be merged into a component
+    reference TEXT NOT NULL PRIMARY KEY,
+    component TEXT NOT NULL,
+    number INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    state TEXT NOT NULL,
+    assignee TEXT,
+    milestone TEXT,
+    priority INTEGER,
+    created_at TEXT NOT NULL,
+    closed_at TEXT,
+    url TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    last_comment TEXT NOT NULL,
+    score REAL,
+    snapshot_id INTEGER NOT NULL REFERENCES snapshots(id),
+    url_status TEXT,
+    moved_to TEXT,
+    verified_at TEXT,
+    first_seen TEXT,
+    last_seen TEXT
+);
+";
+
+const UPSERT: &str = "
+INSERT INTO issues (reference, component, number, title, state, assignee, milestone, priority,
+                    created_at, closed_at, url, updated_at, last_comment, score, snapshot_id,
+                    first_seen, last_seen)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15,
+        (SELECT taken_at FROM snapshots WHERE id = ?15),
+        (SELECT taken_at FROM snapshots WHERE id = ?15))
+ON CONFLICT (reference) DO UPDATE SET
+    component = excluded.component,
+    number = excluded.number,
+    title = excluded.title,
+    state = excluded.state,
+    assignee = excluded.assignee,
+    milestone = excluded.milestone,
+    priority = excluded.priority,
+    created_at = excluded.created_at,
+    closed_at = excluded.closed_at,
+    url = excluded.url,
+    updated_at = excluded.updated_at,
+    last_comment = excluded.last_comment,
+    score = excluded.score,
+    snapshot_id = excluded.snapshot_id,
+    -- Issues stored before these columns were added are first seen now
+    first_seen = COALESCE(issues.first_seen, excluded.first_seen),
+    last_seen = excluded.last_seen,
+    url_status = NULL,
+    moved_to = NULL,
+    verified_at = NULL
+";
+
+// Columns added after the first version of the schema
+const ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("url_status", "TEXT"),
+    ("moved_to", "TEXT"),
+    ("verified_at", "TEXT"),
+    ("first_seen", "TEXT"),
+    ("last_seen", "TEXT"),
+];
+
+// Columns of both versions of the issues table
+const COPIED_COLUMNS: &str = "component, number, title, state, assignee, milestone, priority,
+    created_at, closed_at, url, updated_at, last_comment, score, snapshot_id, url_status,
+    moved_to, verified_at, first_seen, last_seen";
+
+// 'https://github.com/owner/repo/issues/42' -> 'owner/repo#42', other URLs
+// being kept as they are
+fn reference(url: &str) -> String {
+    let segments: Vec<String> = Url::parse(url)
+        .ok()
+        .and_then(|u| {
+            u.path_segments()
+                .map(|s| s.filter(|s| *s != "-").map(String::from).collect())
+        })
+        .unwrap_or_default();
+
+    match segments.as_slice() {
+        [.., owner, repo, kind, number] if kind == "issues" || kind == "pull" => {
+            format!("{}/{}#{}", owner, repo, number)
+        }
+        _ => url.to_string(),
+    }
+}
+
+// Issues were keyed by their component and number before repositories could be
+// merged into components. Copy them to a table keyed by their reference.
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute_batch("ALTER TABLE issues RENAME TO issues_by_component")?;
+    tx.execute_batch(SCHEMA)?;
+    tx.execute_batch(&format!(
+        "INSERT INTO issues (reference, {columns}) SELECT url, {columns} FROM issues_by_component;
+         DROP TABLE issues_by_component;",
+        columns = COPIED_COLUMNS
+    ))?;
+
+    let urls = tx
+        .prepare("SELECT url FROM issues")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>>>()?;
+    {
+        let mut stmt = tx.prepare("UPDATE issues SET reference = ?2 WHERE reference = ?1")?;
+        for url in urls.iter() {
+            stmt.execute(params![url, reference(url)])?;
+        }
+    }
+
+    tx.commit()
+}
+
+fn open(output: &Path) -> Result<Connection> {
+    let mut conn = Connection::open(long_path(output))?;
+    conn.execute_batch(SCHEMA)?;
+
+    let columns = conn
+        .prepare("PRAGMA table_info(issues)")?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<String>>>()?;
+    for &(name, kind) in ADDED_COLUMNS.iter() {
+        if !columns.iter().any(|c| c == name) {
+            conn.execute_batch(&format!("ALTER TABLE issues ADD COLUMN {} {}", name, kind))?;
+        }
+    }
+    if !columns.iter().any(|c| c == "reference") {
+        migrate(&mut conn)?;
+    }
+
+    Ok(conn)
+}
+
+pub fn write_sqlite(rows: &[IssueCSV], output: &Path) -> Result<()> {
+    let mut conn = open(output)?;
+
+    let tx = conn.transaction()?;
+    tx.execute(
+        "INSERT INTO snapshots (taken_at, issue_count) VALUES (datetime('now'), ?1)",
+        params![rows.len() as i64],
+    )?;
+    let snapshot_id = tx.last_insert_rowid();
+
+    {
+        let mut stmt = tx.prepare(UPSERT)?;
+
+        for row in rows.iter() {
+            stmt.execute(params![
+                row.reference,
+                row.component,
+                row.number,
+                row.title,
+                row.state,
+                row.assignee,
+                row.milestone,
+                row.priority,
+                // Stored as 'yyyy-mm-dd' whatever the date format
+                row.dates.created.format("%Y-%m-%d").to_string(),
+                row.dates.closed.map(|d| d.format("%Y-%m-%d").to_string()),
+                // Keep the github URL, so issues can be verified
+                row.html_url,
+                row.dates.updated.format("%Y-%m-%d").to_string(),
+                row.last_comment,
+                row.score,
+                snapshot_id
+            ])?;
+        }
+    }
+
+    tx.commit()
+}
+
+// URLs of the stored issues, the ones which have not been verified for the longest
+// time first
+pub fn urls_to_verify(db: &Path, sample: Option<usize>) -> Result<Vec<String>> {
+    let conn = open(db)?;
+    let limit = sample.map(|s| s as i64).unwrap_or(-1);
+
+    let mut stmt = conn
+        .prepare("SELECT url FROM issues ORDER BY verified_at IS NOT NULL, verified_at LIMIT ?1")?;
+    let urls = stmt
+        .query_map(params![limit], |row| row.get(0))?
+        .collect::<Result<Vec<String>>>()?;
+    Ok(urls)
+}
+
+// Record the status of the URLs: 'ok', 'deleted' or 'moved' along with the new URL
+pub fn set_url_status(db: &Path, statuses: &[(String, &str, Option<String>)]) -> Result<()> {
+    let mut conn = open(db)?;
+    let tx = conn.transaction()?;
+
+    {
+        let mut stmt = tx.prepare(
+            "UPDATE issues SET url_status = ?2, moved_to = ?3, verified_at = datetime('now')
+             WHERE url = ?1",
+        )?;
+
+        for &(ref url, status, ref moved_to) in statuses.iter() {
+            stmt.execute(params![url, status, moved_to])?;
+        }
+    }
+
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    use client::Github;
+    use fixtures::issue;
+    use issue::Issue;
+    use output::Options;
+
+    #[test]
+    fn references() {
+        assert_eq!(
+            reference("https://github.com/owner/repo/issues/42"),
+            "owner/repo#42"
+        );
+        assert_eq!(
+            reference("https://github.com/owner/repo/pull/7"),
+            "owner/repo#7"
+        );
+        assert_eq!(
+            reference("https://gitlab.com/group/project/-/issues/3"),
+            "group/project#3"
+        );
+        assert_eq!(
+            reference("https://example.com/tickets/3"),
+            "https://example.com/tickets/3"
+        );
+        assert_eq!(reference("not a url"), "not a url");
+    }
+
+    #[test]
+    fn migrate_component_keys() {
+        let path = env::temp_dir().join(format!("github-issues-migrate-{}.db", process::id()));
+        let _ = fs::remove_file(&path);
+
+        // First version of the schema, keyed by component and number
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE snapshots (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    taken_at TEXT NOT NULL,
+                    issue_count INTEGER NOT NULL
+                );
+                CREATE TABLE issues (
+                    component TEXT NOT NULL,
+                    number INTEGER NOT NULL,
+                    title TEXT NOT NULL,
+                    state TEXT NOT NULL,
+                    assignee TEXT,
+                    milestone TEXT,
+                    priority INTEGER,
+                    created_at TEXT NOT NULL,
+                    closed_at TEXT,
+                    url TEXT NOT NULL,
+                    updated_at TEXT NOT NULL,
+                    last_comment TEXT NOT NULL,
+                    score REAL,
+                    snapshot_id INTEGER NOT NULL REFERENCES snapshots(id),
+                    PRIMARY KEY (component, number)
+                );
+                INSERT INTO snapshots (taken_at, issue_count) VALUES ('2024-01-01 00:00:00', 1);
+                INSERT INTO issues VALUES ('repo', 1, 'Crash', 'open', NULL, NULL, NULL,
+                    '2024-01-01', NULL, 'https://github.com/owner/repo/issues/1', '2024-01-01',
+                    '', NULL, 1);",
+            )
+            .unwrap();
+        }
+
+        let issue: Issue = serde_json::from_value(serde_json::json!({
+            "title": "Crash on start",
+            "html_url": "https://github.com/owner/repo/issues/1",
+            "number": 1,
+            "repository_url": "https://api.github.com/repos/owner/repo",
+            "labels": [],
+            "state": "open",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+        }))
+        .unwrap();
+        let client = Github::new("token").unwrap();
+        let rows = vec![issue.csv(&client, &Options::default())];
+        write_sqlite(&rows, &path).unwrap();
+
+        let conn = Connection::open(&path).unwrap();
+        let stored = conn
+            .prepare("SELECT reference, title, first_seen, last_seen, snapshot_id FROM issues")
+            .unwrap()
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, i64>(4)?,
+                ))
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // The migrated issue is updated rather than stored again
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].0, "owner/repo#1");
+        assert_eq!(stored[0].1, "Crash on start");
+        assert_eq!(stored[0].4, 2);
+        // Issues stored before first_seen existed are first seen by this run
+        assert!(stored[0].2.is_some());
+        assert_eq!(stored[0].2, stored[0].3);
+    }
+
+    #[test]
+    fn urls_not_verified_first() {
+        let path = env::temp_dir().join(format!("github-issues-verify-{}.db", process::id()));
+        let _ = fs::remove_file(&path);
+
+        let issues: Vec<Issue> = (1..4).map(|n| issue(n, serde_json::Value::Null)).collect();
+        let client = Github::new("token").unwrap();
+        let options = Options::default();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, &options)).collect();
+        write_sqlite(&rows, &path).unwrap();
+
+        let url = |n: u32| format!("https://github.com/owner/repo/issues/{}", n);
+        set_url_status(
+            &path,
+            &[
+                (url(1), "ok", None),
+                (
+                    url(3),
+                    "moved",
+                    Some("https://github.com/owner/lib/issues/1".to_string()),
+                ),
+            ],
+        )
+        .unwrap();
+
+        let all = urls_to_verify(&path, None).unwrap();
+        let sample = urls_to_verify(&path, Some(1)).unwrap();
+        let moved: Option<String> = Connection::open(&path)
+            .unwrap()
+            .query_row(
+                "SELECT moved_to FROM issues WHERE url = ?1 AND url_status = 'moved'",
+                params![url(3)],
+                |row| row.get(0),
+            )
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(all.len(), 3);
+        assert_eq!(sample, vec![url(2)]);
+        assert_eq!(
+            moved.as_deref(),
+            Some("https://github.com/owner/lib/issues/1")
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::io::{self, Write};
+
+use issue::IssueCSV;
+use output::Options;
+
+const RESET: &str = "\x1b[0m";
+
+// Closed issues are dimmed, urgent open ones highlighted
+fn color(row: &IssueCSV) -> Option<&'static str> {
+    if row.state == "closed" {
+        return Some("\x1b[2m");
+    }
+
+    match row.priority {
+        Some(0) => Some("\x1b[1;31m"),
+        Some(1) => Some("\x1b[31m"),
+        Some(2) => Some("\x1b[33m"),
+        _ => None,
+    }
+}
+
+fn write_line<W: Write>(
+    wtr: &mut W,
+    cells: &[String],
+    widths: &[usize],
+    color: Option<&str>,
+) -> io::Result<()> {
+    let line = cells
+        .iter()
+        .zip(widths.iter())
+        .map(|(cell, width)| {
+            let padding = width - cell.chars().count();
+            format!("{}{}", cell, " ".repeat(padding))
+        })
+        .collect::<Vec<String>>()
+        .join("  ");
+    let line = line.trim_end();
+
+    match color {
+        Some(color) => writeln!(wtr, "{}{}{}", color, line, RESET),
+        None => writeln!(wtr, "{}", line),
+    }
+}
+
+// Columns aligned with spaces, for reading in a terminal
+pub fn write_table<W: Write>(mut wtr: W, rows: &[IssueCSV], options: &Options) -> io::Result<()> {
+    let header: Vec<String> = options
+        .columns
+        .iter()
+        .map(|c| options.header(*c).to_string())
+        .collect();
+    let records: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            options
+                .columns
+                .iter()
+                // New lines would break the alignment
+                .map(|c| row.field(*c).replace(['\n', '\r', '\t'], " "))
+                .collect()
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for record in records.iter() {
+        for (width, cell) in widths.iter_mut().zip(record.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    write_line(&mut wtr, &header, &widths, None)?;
+    for (row, record) in rows.iter().zip(records.iter()) {
+        let color = if options.color { color(row) } else { None };
+        write_line(&mut wtr, record, &widths, color)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use client::Github;
+    use column::Column;
+    use fixtures::issue;
+
+    fn table(color: bool) -> String {
+        let mut issues = vec![
+            issue(1, serde_json::json!({ "title": "Crash\non start" })),
+            issue(2, serde_json::json!({ "title": "Typo", "state": "closed" })),
+            issue(3, serde_json::json!({ "title": "Leak" })),
+        ];
+        issues[0].priority = Some(0);
+        let options = Options {
+            columns: vec![Column::Id, Column::Title, Column::State, Column::Priority],
+            color,
+            ..Options::default()
+        };
+        let client = Github::new("token").unwrap();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, &options)).collect();
+
+        let mut output = Vec::new();
+        write_table(&mut output, &rows, &options).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn aligned_columns() {
+        assert_eq!(
+            table(false),
+            "id  title           state   priority
+#1  Crash on start  open    0
+#2  Typo            closed
+#3  Leak            open
+"
+        );
+    }
+
+    #[test]
+    fn colored_rows() {
+        assert_eq!(
+            table(true),
+            "id  title           state   priority
+\x1b[1;31m#1  Crash on start  open    0\x1b[0m
+\x1b[2m#2  Typo            closed\x1b[0m
+#3  Leak            open
+"
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use chrono::{Datelike, NaiveDate};
+use rust_xlsxwriter::{ExcelDateTime, Format as CellFormat, Workbook, Worksheet, XlsxError};
+
+use std::collections::{BTreeSet, HashSet};
+use std::io::{self, Write};
+
+use column::Column;
+use issue::IssueCSV;
+use output::Options;
+
+const MAX_SHEET_NAME: usize = 31;
+
+// Excel limits sheet names to 31 characters, forbids some of them and compares
+// them ignoring case. Names already 'used' get a numeric suffix.
+fn sheet_name(component: &str, used: &mut HashSet<String>) -> String {
+    let name: String = component
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | ':' | '*' | '?' | '/' | '\\' => '_',
+            _ => c,
+        })
+        .collect();
+    let name = if name.trim().is_empty() {
+        "_".to_string()
+    } else {
+        name
+    };
+
+    let mut candidate: String = name.chars().take(MAX_SHEET_NAME).collect();
+    let mut count = 1;
+    while !used.insert(candidate.to_lowercase()) {
+        count += 1;
+        let suffix = format!(" ({})", count);
+        candidate = name
+            .chars()
+            .take(MAX_SHEET_NAME - suffix.len())
+            .chain(suffix.chars())
+            .collect();
+    }
+    candidate
+}
+
+fn write_date(
+    sheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    date: Option<NaiveDate>,
+    format: &CellFormat,
+) -> Result<(), XlsxError> {
+    if let Some(date) = date {
+        let date =
+            ExcelDateTime::from_ymd(date.year() as u16, date.month() as u8, date.day() as u8)?;
+        sheet.write_datetime_with_format(row, col, &date, format)?;
+    }
+    Ok(())
+}
+
+fn write_cell(
+    sheet: &mut Worksheet,
+    row: u32,
+    col: u16,
+    issue: &IssueCSV,
+    column: Column,
+    options: &Options,
+    date_format: &CellFormat,
+) -> Result<(), XlsxError> {
+    match column {
+        Column::Priority => {
+            if let Some(priority) = issue.priority {
+                sheet.write_number(row, col, f64::from(priority))?;
+            }
+        }
+        Column::Score => {
+            if let Some(score) = issue.score {
+                sheet.write_number(row, col, score)?;
+            }
+        }
+        Column::MilestoneChanges => {
+            if let Some(changes) = issue.milestone_changes {
+                sheet.write_number(row, col, f64::from(changes))?;
+            }
+        }
+        Column::Estimate => {
+            if let Some(estimate) = issue.estimate {
+                sheet.write_number(row, col, estimate)?;
+            }
+        }
+        Column::AgeDays => {
+            if let Some(age) = issue.age_days {
+                sheet.write_number(row, col, age as f64)?;
+            }
+        }
+        Column::Reactions => {
+            if let Some(reactions) = issue.reactions {
+                sheet.write_number(row, col, f64::from(reactions))?;
+            }
+        }
+        Column::Comments => {
+            sheet.write_number(row, col, f64::from(issue.comments))?;
+        }
+        Column::Votes => {
+            sheet.write_number(row, col, f64::from(issue.votes))?;
+        }
+        Column::FirstResponseHours => {
+            if let Some(hours) = issue.first_response_hours {
+                sheet.write_number(row, col, hours)?;
+            }
+        }
+        // Excel renders the dates itself, only their day in the timezone is needed
+        Column::CreatedAt => {
+            let day = options.local_day(&issue.dates.created);
+            write_date(sheet, row, col, Some(day), date_format)?
+        }
+        Column::ClosedAt => {
+            let day = issue.dates.closed.map(|d| options.local_day(&d));
+            write_date(sheet, row, col, day, date_format)?
+        }
+        Column::UpdatedAt => {
+            let day = options.local_day(&issue.dates.updated);
+            write_date(sheet, row, col, Some(day), date_format)?
+        }
+        Column::FirstSeen => {
+            let day = issue.dates.first_seen.map(|d| options.local_day(&d));
+            write_date(sheet, row, col, day, date_format)?
+        }
+        Column::LastSeen => {
+            let day = issue.dates.last_seen.map(|d| options.local_day(&d));
+            write_date(sheet, row, col, day, date_format)?
+        }
+        Column::Url => {
+            sheet.write_url(row, col, issue.url.as_str())?;
+        }
+        _ => {
+            sheet.write_string(row, col, issue.field(column))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_sheet(
+    sheet: &mut Worksheet,
+    issues: &[&IssueCSV],
+    options: &Options,
+) -> Result<(), XlsxError> {
+    let header_format = CellFormat::new().set_bold();
+    let date_format = CellFormat::new().set_num_format("yyyy-mm-dd");
+
+    for (col, column) in options.columns.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, options.header(*column), &header_format)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+
+    for (i, issue) in issues.iter().enumerate() {
+        let row = i as u32 + 1;
+
+        for (col, column) in options.columns.iter().enumerate() {
+            write_cell(
+                sheet,
+                row,
+                col as u16,
+                issue,
+                *column,
+                options,
+                &date_format,
+            )?;
+        }
+    }
+
+    sheet.autofit();
+    Ok(())
+}
+
+fn build_workbook(issues: &[IssueCSV], options: &Options) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+
+    let mut used = HashSet::new();
+    let all: Vec<&IssueCSV> = issues.iter().collect();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(sheet_name("All issues", &mut used))?;
+    write_sheet(sheet, &all, options)?;
+
+    let components: BTreeSet<&str> = issues.iter().map(|i| i.component.as_str()).collect();
+    for component in components {
+        let component_issues: Vec<&IssueCSV> =
+            issues.iter().filter(|i| i.component == component).collect();
+
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(sheet_name(component, &mut used))?;
+        write_sheet(sheet, &component_issues, options)?;
+    }
+
+    workbook.save_to_buffer()
+}
+
+pub fn write_xlsx<W: Write>(mut wtr: W, rows: &[IssueCSV], options: &Options) -> io::Result<()> {
+    let buffer = build_workbook(rows, options)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+
+    wtr.write_all(&buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use client::Github;
+    use issue::Issue;
+
+    #[test]
+    fn sheet_names() {
+        let mut used = HashSet::new();
+        let long = "a".repeat(40);
+
+        assert_eq!(sheet_name("All issues", &mut used), "All issues");
+        assert_eq!(sheet_name("all issues", &mut used), "all issues (2)");
+        assert_eq!(sheet_name("ALL ISSUES", &mut used), "ALL ISSUES (3)");
+        assert_eq!(sheet_name("owner/repo:[x]", &mut used), "owner_repo__x_");
+        assert_eq!(sheet_name("  ", &mut used), "_");
+        assert_eq!(sheet_name("?", &mut used), "_ (2)");
+        assert_eq!(sheet_name(&long, &mut used), "a".repeat(31));
+        assert_eq!(
+            sheet_name(&long, &mut used),
+            format!("{} (2)", "a".repeat(27))
+        );
+    }
+
+    #[test]
+    fn workbook_with_clashing_components() {
+        let client = Github::new("token").unwrap();
+        let options = Options {
+            columns: vec![Column::Component, Column::Id, Column::CreatedAt],
+            ..Options::default()
+        };
+        let rows: Vec<IssueCSV> = ["Audio", "audio", "All issues"]
+            .iter()
+            .enumerate()
+            .map(|(i, component)| {
+                let mut issue: Issue = serde_json::from_value(serde_json::json!({
+                    "title": "Crash",
+                    "html_url": format!("https://github.com/owner/repo/issues/{}", i + 1),
+                    "number": i + 1,
+                    "repository_url": "https://api.github.com/repos/owner/repo",
+                    "labels": [],
+                    "state": "open",
+                    "created_at": "2024-01-01T00:00:00Z",
+                    "updated_at": "2024-01-02T00:00:00Z",
+                }))
+                .unwrap();
+                issue.component = Some(component.to_string());
+                issue.csv(&client, &options)
+            })
+            .collect();
+
+        let mut output = Vec::new();
+        write_xlsx(&mut output, &rows, &options).unwrap();
+        // xlsx files are zip archives
+        assert!(output.starts_with(b"PK"));
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use client::{Github, HeaderMap, StatusCode};
+
+// Maximum difference, in seconds, tolerated between the local clock and github's
+const MAX_CLOCK_SKEW: i64 = 5 * 60;
+
+// Parse a HTTP date such as 'Tue, 15 Nov 1994 08:12:31 GMT'
+fn parse_http_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(date)
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn check_clock(headers: &HeaderMap, problems: &mut Vec<String>) {
+    let server = match header(headers, "date").and_then(parse_http_date) {
+        Some(server) => server,
+        None => {
+            problems.push("Cannot check clock skew: no valid date returned by the API".to_string());
+            return;
+        }
+    };
+    let local = Utc::now();
+
+    let skew = (local - server).num_seconds().abs();
+    if skew > MAX_CLOCK_SKEW {
+        problems.push(format!(
+            "Local clock is {} seconds {} github's, more than the {} tolerated",
+            skew,
+            if local > server { "ahead of" } else { "behind" },
+            MAX_CLOCK_SKEW
+        ));
+    }
+}
+
+fn check_rate_limit(headers: &HeaderMap, problems: &mut Vec<String>) {
+    if header(headers, "x-ratelimit-remaining") == Some("0") {
+        let reset = header(headers, "x-ratelimit-reset").unwrap_or("unknown");
+        problems.push(format!(
+            "API rate limit exhausted, it will be reset at {} (Unix time)",
+            reset
+        ));
+    }
+}
+
+// Check the API can be used before starting a long run, returning all the problems found
+pub fn check(client: &Github) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    match client.get::<Value>("user") {
+        Ok((headers, status, _json)) => {
+            if status == StatusCode::UNAUTHORIZED {
+                problems.push("Invalid github token".to_string());
+            } else if !status.is_success() {
+                problems.push(format!("Unexpected API status: {}", status));
+            }
+            check_rate_limit(&headers, &mut problems);
+            check_clock(&headers, &mut problems);
+        }
+        Err(e) => problems.push(format!("Cannot reach the github API: {}", e)),
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Duration;
+    use reqwest::header::HeaderValue;
+
+    fn headers(fields: &[(&'static str, String)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for &(name, ref value) in fields.iter() {
+            headers.insert(name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    fn clock_problems(server: Option<DateTime<Utc>>) -> Vec<String> {
+        let fields: Vec<(&'static str, String)> = server
+            .map(|s| ("date", s.to_rfc2822()))
+            .into_iter()
+            .collect();
+        let mut problems = Vec::new();
+        check_clock(&headers(&fields), &mut problems);
+        problems
+    }
+
+    #[test]
+    fn http_dates() {
+        assert_eq!(
+            parse_http_date("Tue, 15 Nov 1994 08:12:31 GMT").map(|d| d.to_rfc3339()),
+            Some("1994-11-15T08:12:31+00:00".to_string())
+        );
+        assert_eq!(parse_http_date("1994-11-15"), None);
+    }
+
+    #[test]
+    fn clock_skew() {
+        assert!(clock_problems(Some(Utc::now() - Duration::seconds(30))).is_empty());
+
+        let problems = clock_problems(Some(Utc::now() - Duration::minutes(10)));
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("Local clock is "));
+        assert!(problems[0].ends_with(" seconds ahead of github's, more than the 300 tolerated"));
+
+        let problems = clock_problems(Some(Utc::now() + Duration::minutes(10)));
+        assert!(problems[0].contains(" seconds behind github's"));
+
+        assert_eq!(
+            clock_problems(None),
+            vec!["Cannot check clock skew: no valid date returned by the API"]
+        );
+    }
+
+    #[test]
+    fn rate_limit() {
+        let mut problems = Vec::new();
+        check_rate_limit(
+            &headers(&[("x-ratelimit-remaining", "12".to_string())]),
+            &mut problems,
+        );
+        assert!(problems.is_empty());
+
+        check_rate_limit(
+            &headers(&[
+                ("x-ratelimit-remaining", "0".to_string()),
+                ("x-ratelimit-reset", "1700000000".to_string()),
+            ]),
+            &mut problems,
+        );
+        assert_eq!(
+            problems,
+            vec!["API rate limit exhausted, it will be reset at 1700000000 (Unix time)"]
+        );
+    }
+}
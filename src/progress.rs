@@ -0,0 +1,27 @@
+use indicatif::{ProgressBar, ProgressStyle};
+
+// Progress over 'len' items, hidden in quiet mode. Nothing is drawn either if the
+// standard error is not a terminal.
+pub fn bar(len: usize, unit: &str, quiet: bool) -> ProgressBar {
+    if quiet {
+        return ProgressBar::hidden();
+    }
+
+    let template = format!("{{msg:40}} [{{bar:30}}] {{pos}}/{{len}} {}", unit);
+    let bar = ProgressBar::new(len as u64);
+    bar.set_style(
+        ProgressStyle::with_template(&template)
+            .expect("Invalid progress template")
+            .progress_chars("=> "),
+    );
+    bar
+}
+
+// Progress over an unknown number of items
+pub fn spinner(quiet: bool) -> ProgressBar {
+    if quiet {
+        return ProgressBar::hidden();
+    }
+
+    ProgressBar::new_spinner()
+}
@@ -0,0 +1,141 @@
+use chrono::{NaiveDate, Utc};
+use csv;
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use issue::Issue;
+use output::Format;
+use report::chart::{write_chart, Band};
+
+// Number of open issues at the end of each day, from the creation of the first issue
+// until today
+fn series(issues: &[Issue]) -> Vec<(NaiveDate, usize)> {
+    // Change of the number of open issues on each day
+    let mut changes: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+
+    for issue in issues.iter() {
+        *changes.entry(issue.created_at.date_naive()).or_insert(0) += 1;
+        if let Some(closed) = issue.closed_at {
+            *changes.entry(closed.date_naive()).or_insert(0) -= 1;
+        }
+    }
+
+    let (first, last) = match (changes.keys().next(), changes.keys().next_back()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Vec::new(),
+    };
+    let last = last.max(Utc::now().date_naive());
+
+    let mut open = 0;
+    first
+        .iter_days()
+        .take_while(|day| *day <= last)
+        .map(|day| {
+            open += changes.get(&day).cloned().unwrap_or(0);
+            (day, open.max(0) as usize)
+        })
+        .collect()
+}
+
+fn write_markdown<W: Write>(mut wtr: W, series: &[(NaiveDate, usize)]) -> io::Result<()> {
+    writeln!(wtr, "# Burndown of open issues")?;
+    writeln!(wtr)?;
+    writeln!(wtr, "| date | open |")?;
+    writeln!(wtr, "|---|---|")?;
+
+    for &(day, open) in series.iter() {
+        writeln!(wtr, "| {} | {} |", day, open)?;
+    }
+    Ok(())
+}
+
+fn write_csv<W: Write>(wtr: W, series: &[(NaiveDate, usize)]) -> io::Result<()> {
+    let mut wtr = csv::Writer::from_writer(wtr);
+
+    wtr.write_record(&["date", "open"])?;
+    for &(day, open) in series.iter() {
+        wtr.write_record(&[day.to_string(), open.to_string()])?;
+    }
+    wtr.flush()
+}
+
+pub fn write_burndown<W: Write>(
+    wtr: W,
+    issues: &[Issue],
+    format: Option<Format>,
+) -> io::Result<()> {
+    let series = series(issues);
+
+    match format {
+        Some(Format::Csv) => write_csv(wtr, &series),
+        Some(Format::Html) => write_chart(
+            wtr,
+            "Burndown",
+            &series
+                .iter()
+                .map(|&(day, _)| day)
+                .collect::<Vec<NaiveDate>>(),
+            &[Band {
+                name: "open",
+                color: "#d9534f",
+                values: series.iter().map(|&(_, open)| open).collect(),
+            }],
+        ),
+        _ => write_markdown(wtr, &series),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn open_issues_per_day() {
+        let issues = vec![
+            issue(1, serde_json::Value::Null),
+            issue(
+                2,
+                serde_json::json!({
+                    "state": "closed",
+                    "created_at": "2024-01-02T10:00:00Z",
+                    "closed_at": "2024-01-03T10:00:00Z",
+                }),
+            ),
+        ];
+
+        let series = series(&issues);
+        assert_eq!(
+            series[..4],
+            [
+                (day("2024-01-01"), 1),
+                (day("2024-01-02"), 2),
+                (day("2024-01-03"), 1),
+                (day("2024-01-04"), 1),
+            ]
+        );
+        // Until today
+        assert_eq!(series.last(), Some(&(Utc::now().date_naive(), 1)));
+
+        assert!(series(&[]).is_empty());
+    }
+
+    #[test]
+    fn burndown_csv() {
+        let issues = vec![issue(1, serde_json::Value::Null)];
+
+        let mut output = Vec::new();
+        write_burndown(&mut output, &issues, Some(Format::Csv)).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("date,open\n2024-01-01,1\n2024-01-02,1\n"));
+    }
+}
@@ -0,0 +1,168 @@
+use std::io::{self, Write};
+
+use config::ChangelogSection;
+use issue::{Issue, Resolution};
+
+const OTHER: &str = "Other changes";
+
+fn default_sections() -> Vec<ChangelogSection> {
+    vec![
+        ChangelogSection {
+            title: "Features".to_string(),
+            labels: vec!["enhancement".to_string(), "feature".to_string()],
+        },
+        ChangelogSection {
+            title: "Bug fixes".to_string(),
+            labels: vec!["bug".to_string()],
+        },
+    ]
+}
+
+// Release notes of the closed issues, in the first section having one of their labels.
+// Duplicate and won't fix issues are left out.
+pub fn write_changelog<W: Write>(
+    mut wtr: W,
+    issues: &[Issue],
+    sections: &[ChangelogSection],
+    url_template: Option<&str>,
+) -> io::Result<()> {
+    let defaults;
+    let sections = if sections.is_empty() {
+        defaults = default_sections();
+        &defaults
+    } else {
+        sections
+    };
+
+    // One more group for the issues not in any section
+    let mut groups: Vec<Vec<&Issue>> = vec![Vec::new(); sections.len() + 1];
+    let resolved = issues.iter().filter(|i| {
+        matches!(
+            i.get_resolution(),
+            Some(Resolution::Fixed) | Some(Resolution::Other)
+        )
+    });
+
+    for issue in resolved {
+        let idx = sections
+            .iter()
+            .position(|s| s.labels.iter().any(|l| issue.has_label(l)))
+            .unwrap_or(sections.len());
+        groups[idx].push(issue);
+    }
+
+    writeln!(wtr, "# Changelog")?;
+
+    let titles = sections.iter().map(|s| s.title.as_str()).chain(Some(OTHER));
+    for (title, issues) in titles.zip(groups.iter()) {
+        if issues.is_empty() {
+            continue;
+        }
+
+        writeln!(wtr)?;
+        writeln!(wtr, "## {}", title)?;
+        writeln!(wtr)?;
+        for issue in issues.iter() {
+            writeln!(
+                wtr,
+                "- {} ([{}#{}]({}))",
+                issue.title,
+                issue.get_component(),
+                issue.number,
+                issue.get_url(url_template)
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    fn closed(number: u32, labels: &[&str], state_reason: &str) -> Issue {
+        let labels: Vec<serde_json::Value> = labels
+            .iter()
+            .map(|l| serde_json::json!({ "name": l }))
+            .collect();
+
+        issue(
+            number,
+            serde_json::json!({
+                "state": "closed",
+                "state_reason": state_reason,
+                "labels": labels,
+            }),
+        )
+    }
+
+    fn issues() -> Vec<Issue> {
+        vec![
+            closed(1, &["bug"], "completed"),
+            closed(2, &["feature"], "completed"),
+            closed(3, &["bug"], "duplicate"),
+            closed(4, &[], "completed"),
+            issue(5, serde_json::json!({ "labels": [{ "name": "bug" }] })),
+            closed(6, &["bug", "enhancement"], "completed"),
+            closed(7, &["wontfix"], "completed"),
+        ]
+    }
+
+    #[test]
+    fn default_changelog() {
+        let mut output = Vec::new();
+        write_changelog(
+            &mut output,
+            &issues(),
+            &[],
+            Some("https://proxy.example/{owner}/{repo}/{number}"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Changelog
+
+## Features
+
+- Issue 2 ([repo#2](https://proxy.example/owner/repo/2))
+- Issue 6 ([repo#6](https://proxy.example/owner/repo/6))
+
+## Bug fixes
+
+- Issue 1 ([repo#1](https://proxy.example/owner/repo/1))
+
+## Other changes
+
+- Issue 4 ([repo#4](https://proxy.example/owner/repo/4))
+"
+        );
+    }
+
+    #[test]
+    fn configured_sections() {
+        let sections = vec![ChangelogSection {
+            title: "Features".to_string(),
+            labels: vec!["feature".to_string()],
+        }];
+
+        let mut output = Vec::new();
+        write_changelog(&mut output, &issues(), &sections, None).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(!output.contains("## Bug fixes"));
+        assert!(output.ends_with(
+            "## Other changes
+
+- Issue 1 ([repo#1](https://github.com/owner/repo/issues/1))
+- Issue 4 ([repo#4](https://github.com/owner/repo/issues/4))
+- Issue 6 ([repo#6](https://github.com/owner/repo/issues/6))
+"
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+use chrono::NaiveDate;
+
+use std::io::{self, Write};
+
+use output::html::escape;
+
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 400.0;
+const MARGIN: f64 = 40.0;
+
+pub struct Band<'a> {
+    pub name: &'a str,
+    pub color: &'a str,
+    // One value per day
+    pub values: Vec<usize>,
+}
+
+// HTML page with a chart of daily values: a line for a single band, stacked areas,
+// the first band at the bottom, otherwise
+pub fn write_chart<W: Write>(
+    mut wtr: W,
+    title: &str,
+    days: &[NaiveDate],
+    bands: &[Band],
+) -> io::Result<()> {
+    // Top of each band
+    let mut tops: Vec<Vec<usize>> = Vec::new();
+    for band in bands.iter() {
+        let top = match tops.last() {
+            Some(below) => band
+                .values
+                .iter()
+                .zip(below.iter())
+                .map(|(v, b)| v + b)
+                .collect(),
+            None => band.values.clone(),
+        };
+        tops.push(top);
+    }
+
+    let max = tops
+        .last()
+        .and_then(|t| t.iter().max().cloned())
+        .unwrap_or(0)
+        .max(1);
+    let step = (WIDTH - 2.0 * MARGIN) / (days.len().max(2) - 1) as f64;
+    let bottom = HEIGHT - MARGIN;
+    let points = |values: &[usize]| -> Vec<String> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let x = MARGIN + i as f64 * step;
+                let y = bottom - *v as f64 * (HEIGHT - 2.0 * MARGIN) / max as f64;
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect()
+    };
+
+    writeln!(wtr, "<!DOCTYPE html>")?;
+    writeln!(wtr, "<html>")?;
+    writeln!(wtr, "<head>")?;
+    writeln!(wtr, "<meta charset=\"utf-8\">")?;
+    writeln!(wtr, "<title>{}</title>", escape(title))?;
+    writeln!(wtr, "</head>")?;
+    writeln!(wtr, "<body>")?;
+    writeln!(
+        wtr,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"sans-serif\" font-size=\"12\">",
+        WIDTH, HEIGHT
+    )?;
+
+    if bands.len() == 1 {
+        writeln!(
+            wtr,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+            points(&tops[0]).join(" "),
+            bands[0].color
+        )?;
+    } else {
+        for (i, band) in bands.iter().enumerate() {
+            let below = if i == 0 {
+                vec![0; days.len()]
+            } else {
+                tops[i - 1].clone()
+            };
+            let mut outline = points(&tops[i]);
+            outline.extend(points(&below).into_iter().rev());
+
+            writeln!(
+                wtr,
+                "<polygon points=\"{}\" fill=\"{}\" stroke=\"none\"/>",
+                outline.join(" "),
+                band.color
+            )?;
+            // Legend, in the top right corner
+            writeln!(
+                wtr,
+                "<rect x=\"{}\" y=\"{}\" width=\"10\" height=\"10\" fill=\"{}\"/>",
+                WIDTH - MARGIN - 100.0,
+                MARGIN + 14.0 * (bands.len() - 1 - i) as f64,
+                band.color
+            )?;
+            writeln!(
+                wtr,
+                "<text x=\"{}\" y=\"{}\">{}</text>",
+                WIDTH - MARGIN - 86.0,
+                MARGIN + 14.0 * (bands.len() - 1 - i) as f64 + 9.0,
+                escape(band.name)
+            )?;
+        }
+    }
+
+    writeln!(
+        wtr,
+        "<path d=\"M{m},{m} V{b} H{r}\" fill=\"none\" stroke=\"#888\"/>",
+        m = MARGIN,
+        b = bottom,
+        r = WIDTH - MARGIN
+    )?;
+    writeln!(
+        wtr,
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+        MARGIN - 4.0,
+        MARGIN + 4.0,
+        max
+    )?;
+    writeln!(
+        wtr,
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">0</text>",
+        MARGIN - 4.0,
+        bottom + 4.0
+    )?;
+    if let (Some(first), Some(last)) = (days.first(), days.last()) {
+        writeln!(
+            wtr,
+            "<text x=\"{}\" y=\"{}\">{}</text>",
+            MARGIN,
+            bottom + 16.0,
+            first
+        )?;
+        writeln!(
+            wtr,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+            WIDTH - MARGIN,
+            bottom + 16.0,
+            last
+        )?;
+    }
+    writeln!(wtr, "</svg>")?;
+    writeln!(wtr, "</body>")?;
+    writeln!(wtr, "</html>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(bands: &[Band]) -> String {
+        let days: Vec<NaiveDate> = (1..=bands[0].values.len() as u32)
+            .map(|d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap())
+            .collect();
+        let mut output = Vec::new();
+        write_chart(&mut output, "Open <issues>", &days, bands).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn line() {
+        let chart = chart(&[Band {
+            name: "open",
+            color: "red",
+            values: vec![0, 2, 4],
+        }]);
+
+        assert!(chart.contains("<title>Open &lt;issues&gt;</title>"));
+        assert!(chart.contains(
+            "<polyline points=\"40.0,360.0 400.0,200.0 760.0,40.0\" fill=\"none\" stroke=\"red\""
+        ));
+        assert!(chart.contains(">2024-01-01</text>"));
+        assert!(chart.contains(">2024-01-03</text>"));
+        // No legend for a single band
+        assert!(!chart.contains("<rect"));
+    }
+
+    #[test]
+    fn stacked_areas() {
+        let chart = chart(&[
+            Band {
+                name: "closed",
+                color: "green",
+                values: vec![1, 1],
+            },
+            Band {
+                name: "open",
+                color: "red",
+                values: vec![1, 3],
+            },
+        ]);
+
+        assert!(chart.contains(
+            "<polygon points=\"40.0,280.0 760.0,280.0 760.0,360.0 40.0,360.0\" fill=\"green\""
+        ));
+        // Stacked on top of the first band
+        assert!(chart.contains(
+            "<polygon points=\"40.0,200.0 760.0,40.0 760.0,280.0 40.0,280.0\" fill=\"red\""
+        ));
+        assert!(chart.contains(">4</text>"));
+        assert!(chart.contains(">closed</text>"));
+    }
+}
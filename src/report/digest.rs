@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use client::Github;
+use issue::{Issue, IssueState};
+
+#[derive(Debug, Default)]
+struct Changes<'a> {
+    opened: Vec<&'a Issue>,
+    closed: Vec<&'a Issue>,
+    blocked: Vec<&'a Issue>,
+}
+
+// Whether the issue is blocked since 'since', a 'yyyy-mm-dd' date
+fn newly_blocked(client: &Github, issue: &Issue, since: &str) -> bool {
+    if issue.get_state() != IssueState::Blocked {
+        return false;
+    }
+
+    let history = issue.get_state_history(client);
+    // Blocked since the last change to another state
+    history
+        .iter()
+        .rposition(|&(_, state)| state != IssueState::Blocked)
+        .and_then(|i| history.get(i + 1))
+        .is_some_and(|&(ref date, _)| date.as_str() >= since)
+}
+
+fn write_list<W: Write>(
+    wtr: &mut W,
+    title: &str,
+    issues: &[&Issue],
+    url_template: Option<&str>,
+) -> io::Result<()> {
+    if issues.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(wtr, "### {} ({})", title, issues.len())?;
+    writeln!(wtr)?;
+    for issue in issues.iter() {
+        writeln!(
+            wtr,
+            "- [#{}]({}) {}",
+            issue.number,
+            issue.get_url(url_template),
+            issue.title
+        )?;
+    }
+    writeln!(wtr)
+}
+
+// Issues opened, closed and blocked since 'since', a timestamp formatted as the API ones
+pub fn write_digest<W: Write>(
+    mut wtr: W,
+    client: &Github,
+    issues: &[Issue],
+    since: &str,
+    url_template: Option<&str>,
+) -> io::Result<()> {
+    let since_day = &since[..10];
+    let mut components: BTreeMap<String, Changes> = BTreeMap::new();
+
+    for issue in issues.iter() {
+        let opened = issue.get_created_at().as_str() >= since_day;
+        let closed = issue
+            .get_closed_at()
+            .is_some_and(|c| c.as_str() >= since_day);
+        let blocked = newly_blocked(client, issue, since_day);
+        if !opened && !closed && !blocked {
+            continue;
+        }
+
+        let changes = components.entry(issue.get_component()).or_default();
+        if opened {
+            changes.opened.push(issue);
+        }
+        if closed {
+            changes.closed.push(issue);
+        }
+        if blocked {
+            changes.blocked.push(issue);
+        }
+    }
+
+    let total = |f: fn(&Changes) -> usize| components.values().map(f).sum::<usize>();
+    writeln!(wtr, "# Digest since {}", since_day)?;
+    writeln!(wtr)?;
+    writeln!(
+        wtr,
+        "{} opened, {} closed and {} newly blocked issues.",
+        total(|c| c.opened.len()),
+        total(|c| c.closed.len()),
+        total(|c| c.blocked.len())
+    )?;
+    writeln!(wtr)?;
+
+    for (component, changes) in components.iter() {
+        writeln!(wtr, "## {}", component)?;
+        writeln!(wtr)?;
+        write_list(&mut wtr, "Opened", &changes.opened, url_template)?;
+        write_list(&mut wtr, "Closed", &changes.closed, url_template)?;
+        write_list(&mut wtr, "Newly blocked", &changes.blocked, url_template)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    #[test]
+    fn changes_since() {
+        let mut issues = vec![
+            issue(
+                1,
+                serde_json::json!({ "created_at": "2024-02-03T00:00:00Z" }),
+            ),
+            issue(
+                2,
+                serde_json::json!({ "state": "closed", "closed_at": "2024-02-02T00:00:00Z" }),
+            ),
+            issue(
+                3,
+                serde_json::json!({
+                    "state": "closed",
+                    "created_at": "2024-02-05T00:00:00Z",
+                    "closed_at": "2024-02-06T00:00:00Z",
+                }),
+            ),
+            issue(4, serde_json::Value::Null),
+            // Blocked since its creation, as other trackers have no events
+            issue(
+                5,
+                serde_json::json!({ "source": "gitlab", "labels": [{ "name": "blocked" }] }),
+            ),
+        ];
+        for issue in issues.iter_mut() {
+            issue.component = Some("core".to_string());
+        }
+        issues[2].component = Some("ui".to_string());
+        let client = Github::new("token").unwrap();
+
+        let mut output = Vec::new();
+        write_digest(&mut output, &client, &issues, "2024-02-01T00:00:00Z", None).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Digest since 2024-02-01
+
+2 opened, 2 closed and 0 newly blocked issues.
+
+## core
+
+### Opened (1)
+
+- [#1](https://github.com/owner/repo/issues/1) Issue 1
+
+### Closed (1)
+
+- [#2](https://github.com/owner/repo/issues/2) Issue 2
+
+## ui
+
+### Opened (1)
+
+- [#3](https://github.com/owner/repo/issues/3) Issue 3
+
+### Closed (1)
+
+- [#3](https://github.com/owner/repo/issues/3) Issue 3
+
+"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+use issue::Issue;
+
+// Words too common to tell two titles apart
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "at", "be", "by", "for", "from", "in", "is", "it", "of", "on", "or", "the",
+    "to", "when", "with",
+];
+
+// Lowercase words of the title, without punctuation and stop words
+fn tokens(title: &str) -> BTreeSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .map(|t| t.to_lowercase())
+        .filter(|t| !t.is_empty() && !STOP_WORDS.contains(&t.as_str()))
+        .collect()
+}
+
+// Jaccard index of the two sets of tokens, from 0 to 1
+fn similarity(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+
+    a.intersection(b).count() as f64 / union as f64
+}
+
+// List the pairs of issues of different components whose titles have at least
+// 'threshold' similarity, the most similar first
+pub fn write_dupes<W: Write>(
+    mut wtr: W,
+    issues: &[Issue],
+    threshold: f64,
+    url_template: Option<&str>,
+) -> io::Result<()> {
+    let tokens: Vec<(String, BTreeSet<String>)> = issues
+        .iter()
+        .map(|i| (i.get_component(), tokens(&i.title)))
+        .collect();
+
+    let mut pairs = Vec::new();
+    for (a, &(ref component_a, ref tokens_a)) in tokens.iter().enumerate() {
+        for (b, &(ref component_b, ref tokens_b)) in tokens.iter().enumerate().skip(a + 1) {
+            if component_a == component_b {
+                continue;
+            }
+            let similarity = similarity(tokens_a, tokens_b);
+            if similarity >= threshold {
+                pairs.push((a, b, similarity));
+            }
+        }
+    }
+    pairs.sort_by(|x, y| y.2.partial_cmp(&x.2).expect("Invalid similarity"));
+
+    let link = |issue: &Issue| {
+        format!(
+            "[{}#{}]({}) {}",
+            issue.get_component(),
+            issue.number,
+            issue.get_url(url_template),
+            issue.title.replace('|', "\\|")
+        )
+    };
+
+    writeln!(wtr, "# Possible duplicates across components")?;
+    writeln!(wtr)?;
+    writeln!(wtr, "| issue | duplicate | similarity |")?;
+    writeln!(wtr, "|---|---|---|")?;
+
+    for &(a, b, similarity) in pairs.iter() {
+        writeln!(
+            wtr,
+            "| {} | {} | {:.0}% |",
+            link(&issues[a]),
+            link(&issues[b]),
+            similarity * 100.0
+        )?;
+    }
+    writeln!(wtr)?;
+    writeln!(wtr, "{} possible duplicates", pairs.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    fn titled(number: u32, component: &str, title: &str) -> Issue {
+        let mut issue = issue(number, serde_json::json!({ "title": title }));
+        issue.component = Some(component.to_string());
+        issue
+    }
+
+    #[test]
+    fn title_similarity() {
+        let crash = tokens("Crash when opening a file");
+        assert_eq!(
+            crash.iter().map(String::as_str).collect::<Vec<&str>>(),
+            vec!["crash", "file", "opening"]
+        );
+
+        assert_eq!(
+            similarity(&crash, &tokens("CRASH on opening the file!")),
+            1.0
+        );
+        assert_eq!(similarity(&crash, &tokens("Crash when saving a file")), 0.5);
+        assert_eq!(similarity(&crash, &tokens("Typo in the docs")), 0.0);
+        assert_eq!(similarity(&tokens("The"), &tokens("a")), 0.0);
+    }
+
+    #[test]
+    fn duplicates_across_components() {
+        let issues = vec![
+            titled(1, "core", "Crash when opening a file"),
+            titled(2, "ui", "Crash on opening the file"),
+            // Same component as the first one
+            titled(3, "core", "Crash opening file"),
+            titled(4, "ui", "Typo in the docs"),
+        ];
+
+        let mut output = Vec::new();
+        write_dupes(&mut output, &issues, 0.5, None).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Possible duplicates across components
+
+| issue | duplicate | similarity |
+|---|---|---|
+| [core#1](https://github.com/owner/repo/issues/1) Crash when opening a file | [ui#2](https://github.com/owner/repo/issues/2) Crash on opening the file | 100% |
+| [ui#2](https://github.com/owner/repo/issues/2) Crash on opening the file | [core#3](https://github.com/owner/repo/issues/3) Crash opening file | 100% |
+
+2 possible duplicates
+"
+        );
+    }
+}
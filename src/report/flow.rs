@@ -0,0 +1,188 @@
+use chrono::{NaiveDate, Utc};
+use csv;
+
+use std::io::{self, Write};
+
+use client::Github;
+use issue::{Issue, IssueState};
+use output::Format;
+use report::chart::{write_chart, Band};
+
+// Stacked from the bottom of the diagram
+const STATES: &[(IssueState, &str, &str)] = &[
+    (IssueState::Closed, "closed", "#5cb85c"),
+    (IssueState::UnderReview, "under review", "#f0ad4e"),
+    (IssueState::Blocked, "blocked", "#d9534f"),
+    (IssueState::Open, "open", "#5bc0de"),
+];
+
+// Number of issues in each state, indexed as STATES
+type Counts = [usize; 4];
+
+// Days and number of issues in each state at the end of each day, from the creation of
+// the first issue until today
+fn flow(client: &Github, issues: &[Issue]) -> (Vec<NaiveDate>, Vec<Counts>) {
+    let histories: Vec<Vec<(NaiveDate, IssueState)>> = issues
+        .iter()
+        .map(|issue| {
+            issue
+                .get_state_history(client)
+                .into_iter()
+                .filter_map(|(day, state)| {
+                    NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+                        .ok()
+                        .map(|d| (d, state))
+                })
+                .collect()
+        })
+        .collect();
+
+    let first = match histories
+        .iter()
+        .filter_map(|h| h.first())
+        .map(|c| c.0)
+        .min()
+    {
+        Some(first) => first,
+        None => return (Vec::new(), Vec::new()),
+    };
+    let last = histories
+        .iter()
+        .filter_map(|h| h.last())
+        .map(|c| c.0)
+        .fold(Utc::now().date_naive(), NaiveDate::max);
+
+    let days: Vec<NaiveDate> = first.iter_days().take_while(|d| *d <= last).collect();
+    let mut counts = vec![[0; 4]; days.len()];
+
+    for history in histories.iter() {
+        let mut changes = history.iter().peekable();
+        let mut state = None;
+
+        for (i, day) in days.iter().enumerate() {
+            // State at the end of the day
+            while let Some(&&(d, s)) = changes.peek() {
+                if d > *day {
+                    break;
+                }
+                state = Some(s);
+                changes.next();
+            }
+
+            if let Some(s) = state {
+                let idx = STATES
+                    .iter()
+                    .position(|&(st, _, _)| st == s)
+                    .expect("unknown state");
+                counts[i][idx] += 1;
+            }
+        }
+    }
+
+    (days, counts)
+}
+
+fn write_markdown<W: Write>(mut wtr: W, days: &[NaiveDate], counts: &[Counts]) -> io::Result<()> {
+    writeln!(wtr, "# Cumulative flow of issues")?;
+    writeln!(wtr)?;
+    writeln!(wtr, "| date | closed | under review | blocked | open |")?;
+    writeln!(wtr, "|---|---|---|---|---|")?;
+
+    for (day, c) in days.iter().zip(counts.iter()) {
+        writeln!(
+            wtr,
+            "| {} | {} | {} | {} | {} |",
+            day, c[0], c[1], c[2], c[3]
+        )?;
+    }
+    Ok(())
+}
+
+fn write_csv<W: Write>(wtr: W, days: &[NaiveDate], counts: &[Counts]) -> io::Result<()> {
+    let mut wtr = csv::Writer::from_writer(wtr);
+
+    wtr.write_record(&["date", "closed", "under review", "blocked", "open"])?;
+    for (day, c) in days.iter().zip(counts.iter()) {
+        let mut record = vec![day.to_string()];
+        record.extend(c.iter().map(|n| n.to_string()));
+        wtr.write_record(&record)?;
+    }
+    wtr.flush()
+}
+
+pub fn write_flow<W: Write>(
+    wtr: W,
+    client: &Github,
+    issues: &[Issue],
+    format: Option<Format>,
+) -> io::Result<()> {
+    let (days, counts) = flow(client, issues);
+
+    match format {
+        Some(Format::Csv) => write_csv(wtr, &days, &counts),
+        Some(Format::Html) => {
+            let bands: Vec<Band> = STATES
+                .iter()
+                .enumerate()
+                .map(|(i, &(_, name, color))| Band {
+                    name,
+                    color,
+                    values: counts.iter().map(|c| c[i]).collect(),
+                })
+                .collect();
+            write_chart(wtr, "Cumulative flow", &days, &bands)
+        }
+        _ => write_markdown(wtr, &days, &counts),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    #[test]
+    fn flow_csv() {
+        // Issues of other trackers have no events, their history only comes from
+        // their creation and closing dates
+        let issues = vec![
+            issue(1, serde_json::json!({ "source": "gitlab" })),
+            issue(
+                2,
+                serde_json::json!({
+                    "source": "gitlab",
+                    "state": "closed",
+                    "created_at": "2024-01-02T10:00:00Z",
+                    "closed_at": "2024-01-03T10:00:00Z",
+                }),
+            ),
+        ];
+        let client = Github::new("token").unwrap();
+
+        let mut output = Vec::new();
+        write_flow(&mut output, &client, &issues, Some(Format::Csv)).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with(
+            "date,closed,under review,blocked,open
+2024-01-01,0,0,0,1
+2024-01-02,0,0,0,2
+2024-01-03,1,0,0,1
+2024-01-04,1,0,0,1
+"
+        ));
+        assert!(output.ends_with(&format!("{},1,0,0,1\n", Utc::now().date_naive())));
+    }
+
+    #[test]
+    fn no_issues() {
+        let client = Github::new("token").unwrap();
+        let (days, counts) = flow(&client, &[]);
+
+        assert!(days.is_empty());
+        assert!(counts.is_empty());
+    }
+}
@@ -0,0 +1,147 @@
+use chrono::{NaiveDate, Utc};
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use issue::{Issue, IssueStateJson};
+
+#[derive(Debug)]
+struct Milestone {
+    // Creation of its oldest issue
+    start: NaiveDate,
+    open: usize,
+    closed: usize,
+}
+
+// Milestones with a due date, by due date. Milestones of different repositories
+// sharing their title and due date are merged.
+fn milestones(issues: &[Issue]) -> BTreeMap<(NaiveDate, &str), Milestone> {
+    let mut milestones: BTreeMap<(NaiveDate, &str), Milestone> = BTreeMap::new();
+
+    for issue in issues.iter() {
+        let (title, due) = match issue.milestone {
+            Some(ref m) => match m.due_on {
+                Some(due) => (m.title.as_str(), due.date_naive()),
+                None => continue,
+            },
+            None => continue,
+        };
+        let created = issue.created_at.date_naive();
+
+        let milestone = milestones.entry((due, title)).or_insert(Milestone {
+            start: created,
+            open: 0,
+            closed: 0,
+        });
+        milestone.start = milestone.start.min(created);
+        if issue.state == IssueStateJson::Closed {
+            milestone.closed += 1;
+        } else {
+            milestone.open += 1;
+        }
+    }
+
+    milestones
+}
+
+// Mermaid Gantt chart of the milestones, see https://mermaid.js.org/syntax/gantt.html
+pub fn write_gantt<W: Write>(mut wtr: W, issues: &[Issue]) -> io::Result<()> {
+    let today = Utc::now().date_naive();
+
+    writeln!(wtr, "```mermaid")?;
+    writeln!(wtr, "gantt")?;
+    writeln!(wtr, "  title Milestones")?;
+    writeln!(wtr, "  dateFormat YYYY-MM-DD")?;
+
+    for (idx, (&(due, title), milestone)) in milestones(issues).iter().enumerate() {
+        let total = milestone.open + milestone.closed;
+        let done = milestone.closed * 100 / total;
+        // Completed milestones are greyed out, the late ones shown in red
+        let tag = if milestone.open == 0 {
+            "done, "
+        } else if due < today {
+            "crit, "
+        } else {
+            ""
+        };
+
+        writeln!(
+            wtr,
+            "  {} ({}%) :{}m{}, {}, {}",
+            // ':' and ';' separate the fields of the tasks
+            title.replace(|c| c == ':' || c == ';', " "),
+            done,
+            tag,
+            idx,
+            // Issues added after the due date do not move the start after the end
+            milestone.start.min(due).format("%Y-%m-%d"),
+            due.format("%Y-%m-%d")
+        )?;
+    }
+
+    writeln!(wtr, "```")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    #[test]
+    fn milestones_chart() {
+        let v1 = serde_json::json!({ "title": "1.0", "due_on": "2024-03-01T08:00:00Z" });
+        let issues = vec![
+            issue(1, serde_json::json!({ "milestone": v1 })),
+            // Same milestone in another repository
+            issue(
+                2,
+                serde_json::json!({
+                    "repository_url": "https://api.github.com/repos/owner/lib",
+                    "state": "closed",
+                    "created_at": "2024-01-10T00:00:00Z",
+                    "milestone": v1,
+                }),
+            ),
+            issue(
+                3,
+                serde_json::json!({
+                    "created_at": "2024-02-01T00:00:00Z",
+                    "milestone": { "title": "2.0: final", "due_on": "2999-01-01T00:00:00Z" },
+                }),
+            ),
+            issue(
+                4,
+                serde_json::json!({
+                    "state": "closed",
+                    "created_at": "2024-01-05T00:00:00Z",
+                    "milestone": { "title": "0.9", "due_on": "2023-12-01T00:00:00Z" },
+                }),
+            ),
+            // Milestone without due date
+            issue(
+                5,
+                serde_json::json!({ "milestone": { "title": "Backlog" } }),
+            ),
+            issue(6, serde_json::Value::Null),
+        ];
+
+        let mut output = Vec::new();
+        write_gantt(&mut output, &issues).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "```mermaid
+gantt
+  title Milestones
+  dateFormat YYYY-MM-DD
+  0.9 (100%) :done, m0, 2023-12-01, 2023-12-01
+  1.0 (50%) :crit, m1, 2024-01-01, 2024-03-01
+  2.0  final (0%) :m2, 2024-02-01, 2999-01-01
+```
+"
+        );
+    }
+}
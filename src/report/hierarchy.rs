@@ -0,0 +1,140 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use issue::{Issue, IssueStateJson};
+
+// Labels such as 'area/media/video' are grouped at each level of the hierarchy
+const SEPARATOR: char = '/';
+
+#[derive(Debug, Default)]
+struct Node {
+    open: usize,
+    closed: usize,
+    // Sum of the estimates of the open issues
+    estimate: f64,
+}
+
+// 'area/media/video' -> ['area', 'area/media', 'area/media/video']
+fn levels(label: &str) -> Vec<&str> {
+    label
+        .match_indices(SEPARATOR)
+        .map(|(idx, _)| &label[..idx])
+        .chain(Some(label))
+        .collect()
+}
+
+// Nodes are indexed by the segments of their path, so each level is sorted right
+// before its sub-levels
+fn roll_up(issues: &[Issue]) -> BTreeMap<Vec<&str>, Node> {
+    let mut nodes: BTreeMap<Vec<&str>, Node> = BTreeMap::new();
+
+    for issue in issues.iter() {
+        // Only count issues once per level, even if they have several labels below it
+        let paths: BTreeSet<&str> = match issue.labels {
+            Some(ref labels) => labels
+                .iter()
+                .filter(|l| l.name.contains(SEPARATOR))
+                .flat_map(|l| levels(&l.name))
+                .collect(),
+            None => BTreeSet::new(),
+        };
+
+        for path in paths {
+            let node = nodes.entry(path.split(SEPARATOR).collect()).or_default();
+
+            if issue.state == IssueStateJson::Closed {
+                node.closed += 1;
+            } else {
+                node.open += 1;
+                node.estimate += issue.estimate.unwrap_or(0.0);
+            }
+        }
+    }
+
+    nodes
+}
+
+pub fn write_hierarchy<W: Write>(mut wtr: W, issues: &[Issue]) -> io::Result<()> {
+    let nodes = roll_up(issues);
+
+    writeln!(wtr, "# Backlog by label hierarchy")?;
+    writeln!(wtr)?;
+    writeln!(wtr, "| label | open | closed | total | open estimate |")?;
+    writeln!(wtr, "|---|---|---|---|---|")?;
+
+    for (path, node) in nodes.iter() {
+        writeln!(
+            wtr,
+            "| {}{} | {} | {} | {} | {} |",
+            "&nbsp;&nbsp;".repeat(path.len() - 1),
+            path[path.len() - 1],
+            node.open,
+            node.closed,
+            node.open + node.closed,
+            node.estimate
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    fn labelled(number: u32, state: &str, labels: &[&str]) -> Issue {
+        let labels: Vec<serde_json::Value> = labels
+            .iter()
+            .map(|l| serde_json::json!({ "name": l }))
+            .collect();
+
+        issue(
+            number,
+            serde_json::json!({ "state": state, "labels": labels }),
+        )
+    }
+
+    #[test]
+    fn label_levels() {
+        assert_eq!(
+            levels("area/media/video"),
+            vec!["area", "area/media", "area/media/video"]
+        );
+        assert_eq!(levels("bug"), vec!["bug"]);
+    }
+
+    #[test]
+    fn rolled_up_hierarchy() {
+        let mut issues = vec![
+            labelled(1, "open", &["area/media/video", "area/media/audio"]),
+            labelled(2, "closed", &["area/media/video"]),
+            labelled(3, "open", &["area/docs", "bug"]),
+            labelled(4, "open", &["bug"]),
+        ];
+        issues[0].estimate = Some(3.0);
+        issues[1].estimate = Some(2.0);
+        issues[2].estimate = Some(1.5);
+
+        let mut output = Vec::new();
+        write_hierarchy(&mut output, &issues).unwrap();
+
+        // Issues are counted once in 'area/media', despite having two labels below it
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Backlog by label hierarchy
+
+| label | open | closed | total | open estimate |
+|---|---|---|---|---|
+| area | 2 | 1 | 3 | 4.5 |
+| &nbsp;&nbsp;docs | 1 | 0 | 1 | 1.5 |
+| &nbsp;&nbsp;media | 1 | 1 | 2 | 3 |
+| &nbsp;&nbsp;&nbsp;&nbsp;audio | 1 | 0 | 1 | 3 |
+| &nbsp;&nbsp;&nbsp;&nbsp;video | 1 | 1 | 2 | 3 |
+"
+        );
+    }
+}
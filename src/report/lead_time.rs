@@ -0,0 +1,162 @@
+use csv;
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use issue::Issue;
+use output::Format;
+
+// Days between the creation and the closing of each closed issue, per group
+fn breakdown<F>(issues: &[Issue], key: F) -> BTreeMap<String, Vec<i64>>
+where
+    F: Fn(&Issue) -> String,
+{
+    let mut result: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+
+    for issue in issues.iter() {
+        if let Some(closed) = issue.closed_at {
+            let days = closed.date_naive() - issue.created_at.date_naive();
+            result.entry(key(issue)).or_default().push(days.num_days());
+        }
+    }
+
+    for days in result.values_mut() {
+        days.sort();
+    }
+    result
+}
+
+// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[i64], p: usize) -> i64 {
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn write_table<W: Write>(
+    wtr: &mut W,
+    title: &str,
+    key: &str,
+    breakdown: &BTreeMap<String, Vec<i64>>,
+) -> io::Result<()> {
+    writeln!(wtr, "## {}", title)?;
+    writeln!(wtr)?;
+    writeln!(wtr, "| {} | closed | median days | p90 days |", key)?;
+    writeln!(wtr, "|---|---|---|---|")?;
+
+    for (name, days) in breakdown.iter() {
+        writeln!(
+            wtr,
+            "| {} | {} | {} | {} |",
+            name,
+            days.len(),
+            percentile(days, 50),
+            percentile(days, 90)
+        )?;
+    }
+    writeln!(wtr)
+}
+
+pub fn write_lead_time<W: Write>(
+    mut wtr: W,
+    issues: &[Issue],
+    format: Option<Format>,
+) -> io::Result<()> {
+    let breakdowns = [
+        (
+            "Per component",
+            "component",
+            breakdown(issues, |i| i.get_component()),
+        ),
+        (
+            "Per priority",
+            "priority",
+            breakdown(issues, |i| match i.get_priority() {
+                Some(p) => format!("P{}", p),
+                None => "none".to_string(),
+            }),
+        ),
+        (
+            "Per milestone",
+            "milestone",
+            breakdown(issues, |i| match i.milestone {
+                Some(ref m) => m.title.clone(),
+                None => "none".to_string(),
+            }),
+        ),
+    ];
+
+    if format == Some(Format::Csv) {
+        let mut wtr = csv::Writer::from_writer(wtr);
+
+        wtr.write_record(&["group", "name", "closed", "median_days", "p90_days"])?;
+        for &(_, key, ref breakdown) in breakdowns.iter() {
+            for (name, days) in breakdown.iter() {
+                wtr.write_record(&[
+                    key.to_string(),
+                    name.clone(),
+                    days.len().to_string(),
+                    percentile(days, 50).to_string(),
+                    percentile(days, 90).to_string(),
+                ])?;
+            }
+        }
+        return wtr.flush();
+    }
+
+    writeln!(wtr, "# Lead time of closed issues")?;
+    writeln!(wtr)?;
+    for &(title, key, ref breakdown) in breakdowns.iter() {
+        write_table(&mut wtr, title, key, breakdown)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    fn closed(number: u32, closed_at: &str) -> Issue {
+        issue(
+            number,
+            serde_json::json!({ "state": "closed", "closed_at": closed_at }),
+        )
+    }
+
+    #[test]
+    fn percentiles() {
+        let days: Vec<i64> = (1..=10).collect();
+
+        assert_eq!(percentile(&days, 50), 5);
+        assert_eq!(percentile(&days, 90), 9);
+        assert_eq!(percentile(&days, 100), 10);
+        assert_eq!(percentile(&[3], 90), 3);
+    }
+
+    #[test]
+    fn lead_time_csv() {
+        let mut issues = vec![
+            closed(1, "2024-01-02T12:00:00Z"),
+            closed(2, "2024-01-11T00:00:00Z"),
+            closed(3, "2024-01-03T00:00:00Z"),
+            issue(4, serde_json::Value::Null),
+        ];
+        issues[0].priority = Some(1);
+
+        let mut output = Vec::new();
+        write_lead_time(&mut output, &issues, Some(Format::Csv)).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "group,name,closed,median_days,p90_days
+component,repo,3,2,10
+priority,P1,1,1,1
+priority,none,2,2,10
+milestone,none,3,2,10
+"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use calendar::Calendar;
+use client::Github;
+use config::Config;
+use issue::Issue;
+use output::{self, Format};
+use report::triage::Missing;
+
+pub mod burndown;
+pub mod changelog;
+mod chart;
+pub mod digest;
+pub mod dupes;
+pub mod flow;
+pub mod gantt;
+pub mod hierarchy;
+pub mod lead_time;
+pub mod notifications;
+pub mod resolution;
+pub mod sla;
+pub mod stats;
+pub mod triage;
+pub mod workload;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Report {
+    Burndown,
+    Changelog,
+    Digest,
+    Dupes,
+    Flow,
+    Gantt,
+    Hierarchy,
+    LeadTime,
+    Notifications,
+    Resolution,
+    Sla,
+    Stats,
+    Triage,
+    Workload,
+}
+
+impl FromStr for Report {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "burndown" => Ok(Report::Burndown),
+            "changelog" => Ok(Report::Changelog),
+            "digest" => Ok(Report::Digest),
+            "dupes" => Ok(Report::Dupes),
+            "flow" => Ok(Report::Flow),
+            "gantt" => Ok(Report::Gantt),
+            "hierarchy" => Ok(Report::Hierarchy),
+            "lead-time" => Ok(Report::LeadTime),
+            "notifications" => Ok(Report::Notifications),
+            "resolution" => Ok(Report::Resolution),
+            "sla" => Ok(Report::Sla),
+            "stats" => Ok(Report::Stats),
+            "triage" => Ok(Report::Triage),
+            "workload" => Ok(Report::Workload),
+            _ => Err(format!("unknown report '{}'", s)),
+        }
+    }
+}
+
+pub struct Options<'a> {
+    pub config: &'a Config,
+    // Reports are written as Markdown, unless they support the requested format
+    pub format: Option<Format>,
+    // Fields checked by the triage report, all of them if empty
+    pub triage: Vec<Missing>,
+    // Start of the period covered by the digest, formatted as the API timestamps
+    pub since: String,
+    // Count the SLAs in business days
+    pub calendar: Option<Calendar>,
+    // Minimum similarity, from 0 to 1, of the titles of the duplicates
+    pub dupes_threshold: f64,
+    // Whether the first responses to the issues have been fetched
+    pub first_response: bool,
+}
+
+pub fn write<W: Write>(
+    report: Report,
+    wtr: W,
+    client: &Github,
+    issues: &[Issue],
+    options: &Options,
+) -> io::Result<()> {
+    let config = options.config;
+    let format = options.format;
+
+    match report {
+        Report::Burndown => burndown::write_burndown(wtr, issues, format),
+        Report::Changelog => changelog::write_changelog(
+            wtr,
+            issues,
+            &config.changelog,
+            config.url_template.as_deref(),
+        ),
+        Report::Digest => digest::write_digest(
+            wtr,
+            client,
+            issues,
+            &options.since,
+            config.url_template.as_deref(),
+        ),
+        Report::Dupes => dupes::write_dupes(
+            wtr,
+            issues,
+            options.dupes_threshold,
+            config.url_template.as_deref(),
+        ),
+        Report::Flow => flow::write_flow(wtr, client, issues, format),
+        Report::Gantt => gantt::write_gantt(wtr, issues),
+        Report::Hierarchy => hierarchy::write_hierarchy(wtr, issues),
+        Report::LeadTime => lead_time::write_lead_time(wtr, issues, format),
+        Report::Notifications => {
+            notifications::write_notifications(wtr, client, issues, config.url_template.as_deref())
+        }
+        Report::Resolution => resolution::write_resolution(wtr, issues),
+        Report::Sla => sla::write_sla(
+            wtr,
+            issues,
+            &config.sla,
+            options.calendar.as_ref(),
+            config.url_template.as_deref(),
+        ),
+        Report::Stats => stats::write_stats(
+            wtr,
+            issues,
+            options.first_response,
+            format == Some(Format::Json),
+        ),
+        Report::Triage => {
+            triage::write_triage(wtr, issues, &options.triage, config.url_template.as_deref())
+        }
+        Report::Workload => workload::write_workload(wtr, issues, format == Some(Format::Csv)),
+    }
+}
+
+pub fn generate(
+    report: Report,
+    client: &Github,
+    issues: &[Issue],
+    options: &Options,
+    output: &PathBuf,
+) {
+    let mut wtr = output::open(output);
+
+    write(report, &mut wtr, client, issues, options).expect("Failed to write report");
+    wtr.flush().expect("Failed to flush output");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_from_str() {
+        assert_eq!("lead-time".parse(), Ok(Report::LeadTime));
+        assert_eq!("stats".parse(), Ok(Report::Stats));
+        assert_eq!(
+            "lead_time".parse::<Report>(),
+            Err("unknown report 'lead_time'".to_string())
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use std::io::{self, Write};
+
+use client::Github;
+use github::{get_notifications, Notification};
+use issue::Issue;
+
+// Most actionable reasons first
+fn reason_rank(reason: &str) -> u32 {
+    match reason {
+        "assign" => 0,
+        "mention" => 1,
+        "team_mention" => 2,
+        "author" => 3,
+        "comment" => 4,
+        "manual" => 5,
+        "state_change" => 6,
+        "subscribed" => 7,
+        _ => 8,
+    }
+}
+
+// Unread notifications about any of the issues, the other ones being only counted
+fn write_unread<W: Write>(
+    mut wtr: W,
+    notifications: &[Notification],
+    issues: &[Issue],
+    url_template: Option<&str>,
+) -> io::Result<()> {
+    let unread = notifications.iter().filter(|n| n.unread);
+
+    let mut matched: Vec<(&Notification, &Issue)> = Vec::new();
+    let mut others = 0;
+
+    for notification in unread {
+        let issue = notification.issue_number().and_then(|number| {
+            issues
+                .iter()
+                .find(|i| i.number == number && i.get_repo() == notification.repository.name)
+        });
+
+        match issue {
+            Some(issue) => matched.push((notification, issue)),
+            None => others += 1,
+        }
+    }
+
+    matched.sort_by_key(|&(n, i)| {
+        (
+            i.get_priority().unwrap_or(u32::MAX),
+            reason_rank(&n.reason),
+            i.get_state(),
+        )
+    });
+
+    writeln!(wtr, "# Needs my attention")?;
+    writeln!(wtr)?;
+    writeln!(
+        wtr,
+        "| priority | component | id | title | state | reason | updated |"
+    )?;
+    writeln!(wtr, "|---|---|---|---|---|---|---|")?;
+
+    for (notification, issue) in matched.iter() {
+        writeln!(
+            wtr,
+            "| {} | {} | [#{}]({}) | {} | {} | {} | {} |",
+            issue
+                .get_priority()
+                .map(|p| format!("P{}", p))
+                .unwrap_or_default(),
+            issue.get_component(),
+            issue.number,
+            issue.get_url(url_template),
+            issue.title.replace('|', "\\|"),
+            issue.get_state_str(),
+            notification.reason,
+            notification.updated_at.format("%Y-%m-%d")
+        )?;
+    }
+
+    if others > 0 {
+        writeln!(wtr)?;
+        writeln!(
+            wtr,
+            "{} other unread notifications are not about aggregated issues.",
+            others
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn write_notifications<W: Write>(
+    wtr: W,
+    client: &Github,
+    issues: &[Issue],
+    url_template: Option<&str>,
+) -> io::Result<()> {
+    let notifications = get_notifications(client).expect("failed to get notifications");
+
+    write_unread(wtr, &notifications, issues, url_template)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    fn notification(
+        reason: &str,
+        unread: bool,
+        kind: &str,
+        repo: &str,
+        number: u32,
+    ) -> Notification {
+        serde_json::from_value(serde_json::json!({
+            "reason": reason,
+            "unread": unread,
+            "updated_at": "2024-03-01T10:00:00Z",
+            "subject": {
+                "title": "Crash",
+                "url": format!("https://api.github.com/repos/owner/{}/issues/{}", repo, number),
+                "type": kind,
+            },
+            "repository": { "name": repo, "full_name": format!("owner/{}", repo) },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn issue_numbers() {
+        assert_eq!(
+            notification("mention", true, "Issue", "repo", 42).issue_number(),
+            Some(42)
+        );
+        assert_eq!(
+            notification("mention", true, "PullRequest", "repo", 42).issue_number(),
+            None
+        );
+    }
+
+    #[test]
+    fn unread_notifications() {
+        let mut issues = vec![
+            issue(1, serde_json::Value::Null),
+            issue(2, serde_json::json!({ "labels": [{ "name": "blocked" }] })),
+            issue(3, serde_json::Value::Null),
+        ];
+        issues[0].priority = Some(2);
+        issues[1].priority = Some(1);
+        let notifications = vec![
+            notification("subscribed", true, "Issue", "repo", 1),
+            notification("assign", true, "Issue", "repo", 3),
+            notification("comment", true, "Issue", "repo", 2),
+            notification("mention", false, "Issue", "repo", 1),
+            // Same number in a repository which is not aggregated
+            notification("mention", true, "Issue", "other", 1),
+            notification("review_requested", true, "PullRequest", "repo", 4),
+        ];
+
+        let mut output = Vec::new();
+        write_unread(&mut output, &notifications, &issues, None).unwrap();
+
+        // Issues with a priority first, then the most actionable reasons
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Needs my attention
+
+| priority | component | id | title | state | reason | updated |
+|---|---|---|---|---|---|---|
+| P1 | repo | [#2](https://github.com/owner/repo/issues/2) | Issue 2 | blocked | comment | 2024-03-01 |
+| P2 | repo | [#1](https://github.com/owner/repo/issues/1) | Issue 1 | open | subscribed | 2024-03-01 |
+|  | repo | [#3](https://github.com/owner/repo/issues/3) | Issue 3 | open | assign | 2024-03-01 |
+
+2 other unread notifications are not about aggregated issues.
+"
+        );
+    }
+
+    #[test]
+    fn reason_ranks() {
+        assert!(reason_rank("assign") < reason_rank("mention"));
+        assert!(reason_rank("subscribed") < reason_rank("ci_activity"));
+    }
+}
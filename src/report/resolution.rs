@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use issue::{Issue, Resolution};
+
+const RESOLUTIONS: &[Resolution] = &[
+    Resolution::Fixed,
+    Resolution::Duplicate,
+    Resolution::WontFix,
+    Resolution::Other,
+];
+
+// Number of issues for each resolution, indexed as RESOLUTIONS
+type Counts = [usize; 4];
+
+fn quarter(date: &str) -> String {
+    // 'yyyy-mm-dd' -> 'yyyy-Qn'
+    let month: u32 = date[5..7].parse().expect("Failed to parse month");
+    format!("{}-Q{}", &date[..4], (month - 1) / 3 + 1)
+}
+
+fn breakdown<F>(issues: &[Issue], key: F) -> BTreeMap<String, Counts>
+where
+    F: Fn(&Issue) -> String,
+{
+    let mut result = BTreeMap::new();
+
+    for issue in issues.iter() {
+        if let Some(resolution) = issue.get_resolution() {
+            let counts = result.entry(key(issue)).or_insert([0; 4]);
+            let idx = RESOLUTIONS
+                .iter()
+                .position(|r| *r == resolution)
+                .expect("unknown resolution");
+            counts[idx] += 1;
+        }
+    }
+
+    result
+}
+
+fn write_table<W: Write>(
+    wtr: &mut W,
+    title: &str,
+    key: &str,
+    breakdown: &BTreeMap<String, Counts>,
+) -> io::Result<()> {
+    writeln!(wtr, "## {}", title)?;
+    writeln!(wtr)?;
+    writeln!(
+        wtr,
+        "| {} | fixed | duplicate | wontfix | other | total | fixed % |",
+        key
+    )?;
+    writeln!(wtr, "|---|---|---|---|---|---|---|")?;
+
+    for (name, counts) in breakdown.iter() {
+        let total: usize = counts.iter().sum();
+
+        writeln!(
+            wtr,
+            "| {} | {} | {} | {} | {} | {} | {:.0}% |",
+            name,
+            counts[0],
+            counts[1],
+            counts[2],
+            counts[3],
+            total,
+            counts[0] as f64 * 100.0 / total as f64
+        )?;
+    }
+    writeln!(wtr)
+}
+
+pub fn write_resolution<W: Write>(mut wtr: W, issues: &[Issue]) -> io::Result<()> {
+    let per_component = breakdown(issues, |i| i.get_component());
+    let per_quarter = breakdown(issues, |i| {
+        quarter(&i.get_closed_at().expect("closed issue without closed date"))
+    });
+
+    writeln!(wtr, "# Resolution of closed issues")?;
+    writeln!(wtr)?;
+    write_table(&mut wtr, "Per component", "component", &per_component)?;
+    write_table(&mut wtr, "Per quarter", "quarter", &per_quarter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    fn closed(number: u32, closed_at: &str, state_reason: Option<&str>) -> Issue {
+        issue(
+            number,
+            serde_json::json!({
+                "state": "closed",
+                "state_reason": state_reason,
+                "closed_at": closed_at,
+            }),
+        )
+    }
+
+    #[test]
+    fn quarters() {
+        assert_eq!(quarter("2024-01-01"), "2024-Q1");
+        assert_eq!(quarter("2024-03-31"), "2024-Q1");
+        assert_eq!(quarter("2024-04-01"), "2024-Q2");
+        assert_eq!(quarter("2024-12-31"), "2024-Q4");
+    }
+
+    #[test]
+    fn resolutions() {
+        let issues = vec![
+            closed(1, "2024-02-10T00:00:00Z", Some("completed")),
+            closed(2, "2024-03-31T00:00:00Z", Some("duplicate")),
+            closed(3, "2024-04-01T00:00:00Z", Some("not_planned")),
+            closed(4, "2024-12-01T00:00:00Z", None),
+            closed(5, "2024-05-01T00:00:00Z", Some("completed")),
+            issue(6, serde_json::Value::Null),
+        ];
+
+        let mut output = Vec::new();
+        write_resolution(&mut output, &issues).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Resolution of closed issues
+
+## Per component
+
+| component | fixed | duplicate | wontfix | other | total | fixed % |
+|---|---|---|---|---|---|---|
+| repo | 2 | 1 | 1 | 1 | 5 | 40% |
+
+## Per quarter
+
+| quarter | fixed | duplicate | wontfix | other | total | fixed % |
+|---|---|---|---|---|---|---|
+| 2024-Q1 | 1 | 1 | 0 | 0 | 2 | 50% |
+| 2024-Q2 | 1 | 0 | 1 | 0 | 2 | 50% |
+| 2024-Q4 | 0 | 0 | 0 | 1 | 1 | 0% |
+
+"
+        );
+    }
+}
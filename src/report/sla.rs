@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use calendar::Calendar;
+use issue::Issue;
+
+// 'P0' or '0' -> 0
+fn parse_priority(s: &str) -> Option<u32> {
+    s.trim_start_matches(|c: char| c == 'P' || c == 'p')
+        .parse()
+        .ok()
+}
+
+struct Breach<'a> {
+    issue: &'a Issue,
+    priority: u32,
+    age: i64,
+    sla: u32,
+}
+
+impl<'a> Breach<'a> {
+    fn overdue(&self) -> i64 {
+        self.age - i64::from(self.sla)
+    }
+}
+
+// 'sla': priority -> number of days allowed to close the issues, business days
+// if a calendar is given
+pub fn write_sla<W: Write>(
+    mut wtr: W,
+    issues: &[Issue],
+    sla: &HashMap<String, u32>,
+    calendar: Option<&Calendar>,
+    url_template: Option<&str>,
+) -> io::Result<()> {
+    let sla: HashMap<u32, u32> = sla
+        .iter()
+        .map(|(priority, days)| {
+            let priority = parse_priority(priority).expect("Invalid priority in sla");
+            (priority, *days)
+        })
+        .collect();
+
+    let mut breaches: Vec<Breach> = issues
+        .iter()
+        .filter_map(|issue| {
+            let priority = issue.get_priority()?;
+            let days = *sla.get(&priority)?;
+            let age = issue.get_age_days(calendar)?;
+
+            if age > i64::from(days) {
+                Some(Breach {
+                    issue,
+                    priority,
+                    age,
+                    sla: days,
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+    // Most overdue first
+    breaches.sort_by(|a, b| b.overdue().cmp(&a.overdue()));
+
+    writeln!(wtr, "# Open issues breaching their SLA")?;
+    writeln!(wtr)?;
+
+    if sla.is_empty() {
+        writeln!(wtr, "No SLA configured.")?;
+        return Ok(());
+    }
+
+    let unit = if calendar.is_some() {
+        "business days"
+    } else {
+        "days"
+    };
+    writeln!(
+        wtr,
+        "| issue | title | priority | age ({0}) | SLA ({0}) | overdue ({0}) |",
+        unit
+    )?;
+    writeln!(wtr, "|---|---|---|---|---|---|")?;
+
+    for breach in breaches.iter() {
+        writeln!(
+            wtr,
+            "| [{}#{}]({}) | {} | P{} | {} | {} | {} |",
+            breach.issue.get_component(),
+            breach.issue.number,
+            breach.issue.get_url(url_template),
+            breach.issue.title.replace('|', "\\|"),
+            breach.priority,
+            breach.age,
+            breach.sla,
+            breach.overdue()
+        )?;
+    }
+    writeln!(wtr)?;
+    writeln!(wtr, "{} issues overdue", breaches.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{Duration, Utc};
+    use serde_json;
+
+    use fixtures::issue;
+    use issue::IssueStateJson;
+
+    fn open(number: u32, priority: u32, age: i64) -> Issue {
+        let mut issue = issue(number, serde_json::Value::Null);
+        issue.created_at = Utc::now() - Duration::days(age);
+        issue.priority = Some(priority);
+        issue
+    }
+
+    #[test]
+    fn parse_priorities() {
+        assert_eq!(parse_priority("P0"), Some(0));
+        assert_eq!(parse_priority("p2"), Some(2));
+        assert_eq!(parse_priority("3"), Some(3));
+        assert_eq!(parse_priority("high"), None);
+    }
+
+    #[test]
+    fn breaches() {
+        let mut issues = vec![
+            open(1, 1, 20),
+            open(2, 0, 10),
+            open(3, 1, 5),
+            open(4, 2, 100),
+            open(5, 0, 10),
+        ];
+        issues[1].title = "Crash | data loss".to_string();
+        issues[4].state = IssueStateJson::Closed;
+        let sla: HashMap<String, u32> = vec![("P0".to_string(), 2), ("1".to_string(), 14)]
+            .into_iter()
+            .collect();
+
+        let mut output = Vec::new();
+        write_sla(&mut output, &issues, &sla, None, None).unwrap();
+
+        // Most overdue first
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Open issues breaching their SLA
+
+| issue | title | priority | age (days) | SLA (days) | overdue (days) |
+|---|---|---|---|---|---|
+| [repo#2](https://github.com/owner/repo/issues/2) | Crash \\| data loss | P0 | 10 | 2 | 8 |
+| [repo#1](https://github.com/owner/repo/issues/1) | Issue 1 | P1 | 20 | 14 | 6 |
+
+2 issues overdue
+"
+        );
+    }
+
+    #[test]
+    fn no_sla() {
+        let mut output = Vec::new();
+        write_sla(&mut output, &[open(1, 0, 10)], &HashMap::new(), None, None).unwrap();
+
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .ends_with("No SLA configured.\n"));
+    }
+}
@@ -0,0 +1,285 @@
+use serde_json;
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+use issue::{Issue, IssueState};
+
+// Number of issues in each state
+#[derive(Debug, Default, Serialize)]
+struct Counts {
+    open: usize,
+    blocked: usize,
+    under_review: usize,
+    closed: usize,
+    total: usize,
+}
+
+impl Counts {
+    fn add(&mut self, state: IssueState) {
+        match state {
+            IssueState::Open => self.open += 1,
+            IssueState::Blocked => self.blocked += 1,
+            IssueState::UnderReview => self.under_review += 1,
+            IssueState::Closed => self.closed += 1,
+        }
+        self.total += 1;
+    }
+}
+
+// Time until the first answer of a maintainer
+#[derive(Debug, Serialize)]
+struct FirstResponse {
+    answered: usize,
+    unanswered: usize,
+    median_hours: Option<f64>,
+    p90_hours: Option<f64>,
+}
+
+fn percentile(sorted: &[i64], p: usize) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1] as f64 / 3600.0)
+}
+
+impl FirstResponse {
+    fn new<'a, I: Iterator<Item = &'a Issue>>(issues: I) -> FirstResponse {
+        let mut unanswered = 0;
+        let mut secs = Vec::new();
+        for issue in issues {
+            match issue.first_response {
+                Some(s) => secs.push(s),
+                None => unanswered += 1,
+            }
+        }
+        secs.sort_unstable();
+
+        FirstResponse {
+            answered: secs.len(),
+            unanswered,
+            median_hours: percentile(&secs, 50),
+            p90_hours: percentile(&secs, 90),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Stats {
+    all: Counts,
+    per_component: BTreeMap<String, Counts>,
+    per_priority: BTreeMap<String, Counts>,
+    per_milestone: BTreeMap<String, Counts>,
+    // Only if the first responses have been fetched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_response: Option<BTreeMap<String, FirstResponse>>,
+}
+
+// Overall as 'all', and per component
+fn first_responses(issues: &[Issue]) -> BTreeMap<String, FirstResponse> {
+    let mut components: BTreeMap<String, Vec<&Issue>> = BTreeMap::new();
+    for issue in issues.iter() {
+        components
+            .entry(issue.get_component())
+            .or_default()
+            .push(issue);
+    }
+
+    let mut result: BTreeMap<String, FirstResponse> = components
+        .into_iter()
+        .map(|(component, issues)| (component, FirstResponse::new(issues.into_iter())))
+        .collect();
+    result.insert("all".to_string(), FirstResponse::new(issues.iter()));
+    result
+}
+
+fn breakdown<F>(issues: &[Issue], key: F) -> BTreeMap<String, Counts>
+where
+    F: Fn(&Issue) -> String,
+{
+    let mut result: BTreeMap<String, Counts> = BTreeMap::new();
+
+    for issue in issues.iter() {
+        result.entry(key(issue)).or_default().add(issue.get_state());
+    }
+
+    result
+}
+
+impl Stats {
+    fn new(issues: &[Issue], first_response: bool) -> Stats {
+        let mut all = Counts::default();
+        for issue in issues.iter() {
+            all.add(issue.get_state());
+        }
+
+        Stats {
+            all,
+            per_component: breakdown(issues, |i| i.get_component()),
+            per_priority: breakdown(issues, |i| match i.get_priority() {
+                Some(p) => format!("P{}", p),
+                None => "none".to_string(),
+            }),
+            per_milestone: breakdown(issues, |i| match i.milestone {
+                Some(ref m) => m.title.clone(),
+                None => "none".to_string(),
+            }),
+            first_response: if first_response {
+                Some(first_responses(issues))
+            } else {
+                None
+            },
+        }
+    }
+}
+
+fn write_row<W: Write>(wtr: &mut W, name: &str, counts: &Counts) -> io::Result<()> {
+    writeln!(
+        wtr,
+        "| {} | {} | {} | {} | {} | {} |",
+        name, counts.open, counts.blocked, counts.under_review, counts.closed, counts.total
+    )
+}
+
+fn write_table<W: Write>(
+    wtr: &mut W,
+    title: &str,
+    key: &str,
+    breakdown: &BTreeMap<String, Counts>,
+) -> io::Result<()> {
+    writeln!(wtr, "## {}", title)?;
+    writeln!(wtr)?;
+    writeln!(
+        wtr,
+        "| {} | open | blocked | under review | closed | total |",
+        key
+    )?;
+    writeln!(wtr, "|---|---|---|---|---|---|")?;
+
+    for (name, counts) in breakdown.iter() {
+        write_row(wtr, name, counts)?;
+    }
+    writeln!(wtr)
+}
+
+fn hours(hours: Option<f64>) -> String {
+    hours.map(|h| format!("{:.1}", h)).unwrap_or_default()
+}
+
+pub fn write_stats<W: Write>(
+    mut wtr: W,
+    issues: &[Issue],
+    first_response: bool,
+    json: bool,
+) -> io::Result<()> {
+    let stats = Stats::new(issues, first_response);
+
+    if json {
+        serde_json::to_writer_pretty(&mut wtr, &stats)?;
+        return writeln!(wtr);
+    }
+
+    writeln!(wtr, "# Backlog statistics")?;
+    writeln!(wtr)?;
+    writeln!(wtr, "| | open | blocked | under review | closed | total |")?;
+    writeln!(wtr, "|---|---|---|---|---|---|")?;
+    write_row(&mut wtr, "all", &stats.all)?;
+    writeln!(wtr)?;
+    write_table(&mut wtr, "Per component", "component", &stats.per_component)?;
+    write_table(&mut wtr, "Per priority", "priority", &stats.per_priority)?;
+    write_table(&mut wtr, "Per milestone", "milestone", &stats.per_milestone)?;
+
+    if let Some(ref responses) = stats.first_response {
+        writeln!(wtr, "## First response")?;
+        writeln!(wtr)?;
+        writeln!(
+            wtr,
+            "| | answered | unanswered | median hours | p90 hours |"
+        )?;
+        writeln!(wtr, "|---|---|---|---|---|")?;
+        for (name, response) in responses.iter() {
+            writeln!(
+                wtr,
+                "| {} | {} | {} | {} | {} |",
+                name,
+                response.answered,
+                response.unanswered,
+                hours(response.median_hours),
+                hours(response.p90_hours)
+            )?;
+        }
+        writeln!(wtr)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::{self, Value};
+
+    use fixtures::issue;
+
+    #[test]
+    fn stats_json() {
+        let mut issues = vec![
+            issue(1, serde_json::json!({ "milestone": { "title": "1.0" } })),
+            issue(2, serde_json::json!({ "labels": [{ "name": "blocked" }] })),
+            issue(
+                3,
+                serde_json::json!({ "state": "closed", "milestone": { "title": "1.0" } }),
+            ),
+        ];
+        issues[0].priority = Some(1);
+        issues[0].first_response = Some(3600);
+        issues[1].first_response = Some(7200);
+        issues[2].component = Some("ui".to_string());
+
+        let mut output = Vec::new();
+        write_stats(&mut output, &issues, true, true).unwrap();
+        let stats: Value = serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(
+            stats["all"],
+            serde_json::json!({
+                "open": 1, "blocked": 1, "under_review": 0, "closed": 1, "total": 3
+            })
+        );
+        assert_eq!(stats["per_component"]["repo"]["total"], 2);
+        assert_eq!(stats["per_component"]["ui"]["closed"], 1);
+        assert_eq!(stats["per_priority"]["P1"]["open"], 1);
+        assert_eq!(stats["per_priority"]["none"]["total"], 2);
+        assert_eq!(stats["per_milestone"]["1.0"]["total"], 2);
+        assert_eq!(stats["per_milestone"]["none"]["blocked"], 1);
+        assert_eq!(
+            stats["first_response"]["all"],
+            serde_json::json!({
+                "answered": 2, "unanswered": 1, "median_hours": 1.0, "p90_hours": 2.0
+            })
+        );
+        assert_eq!(stats["first_response"]["ui"]["median_hours"], Value::Null);
+
+        let mut output = Vec::new();
+        write_stats(&mut output, &issues, false, true).unwrap();
+        let stats: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(stats.get("first_response"), None);
+    }
+
+    #[test]
+    fn stats_markdown() {
+        let issues = vec![issue(1, Value::Null), issue(2, Value::Null)];
+
+        let mut output = Vec::new();
+        write_stats(&mut output, &issues, false, false).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("# Backlog statistics\n"));
+        assert!(output.contains("| all | 2 | 0 | 0 | 0 | 2 |\n"));
+        assert!(output.contains("## Per component\n"));
+        assert!(output.contains("| repo | 2 | 0 | 0 | 0 | 2 |\n"));
+        assert!(!output.contains("## First response"));
+    }
+}
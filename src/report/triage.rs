@@ -0,0 +1,150 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use issue::{Issue, IssueStateJson};
+
+// Field which open issues are expected to have once triaged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Missing {
+    Priority,
+    Milestone,
+    Assignee,
+}
+
+const ALL: &[Missing] = &[Missing::Priority, Missing::Milestone, Missing::Assignee];
+
+impl Missing {
+    fn name(&self) -> &'static str {
+        match *self {
+            Missing::Priority => "priority",
+            Missing::Milestone => "milestone",
+            Missing::Assignee => "assignee",
+        }
+    }
+
+    fn is_missing(&self, issue: &Issue) -> bool {
+        match *self {
+            Missing::Priority => issue.get_priority().is_none(),
+            Missing::Milestone => issue.milestone.is_none(),
+            Missing::Assignee => issue.get_assignees().is_empty(),
+        }
+    }
+}
+
+impl FromStr for Missing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ALL.iter()
+            .find(|m| m.name() == s)
+            .cloned()
+            .ok_or_else(|| format!("unknown triage field '{}'", s))
+    }
+}
+
+// Open issues missing any of the 'checks' fields, all of them if empty
+pub fn write_triage<W: Write>(
+    mut wtr: W,
+    issues: &[Issue],
+    checks: &[Missing],
+    url_template: Option<&str>,
+) -> io::Result<()> {
+    let checks = if checks.is_empty() { ALL } else { checks };
+
+    writeln!(wtr, "# Open issues to triage")?;
+    writeln!(wtr)?;
+    writeln!(wtr, "| issue | title | missing |")?;
+    writeln!(wtr, "|---|---|---|")?;
+
+    let mut count = 0;
+    for issue in issues.iter().filter(|i| i.state == IssueStateJson::Open) {
+        let missing: Vec<&str> = checks
+            .iter()
+            .filter(|m| m.is_missing(issue))
+            .map(|m| m.name())
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+
+        writeln!(
+            wtr,
+            "| [{}#{}]({}) | {} | {} |",
+            issue.get_component(),
+            issue.number,
+            issue.get_url(url_template),
+            issue.title.replace('|', "\\|"),
+            missing.join(", ")
+        )?;
+        count += 1;
+    }
+
+    writeln!(wtr)?;
+    writeln!(wtr, "{} issues to triage", count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    fn issues() -> Vec<Issue> {
+        let mut issues = vec![
+            issue(1, serde_json::Value::Null),
+            issue(
+                2,
+                serde_json::json!({
+                    "milestone": { "title": "1.0" },
+                    "assignee": { "login": "alice" },
+                }),
+            ),
+            issue(3, serde_json::json!({ "milestone": { "title": "1.0" } })),
+            issue(4, serde_json::json!({ "state": "closed" })),
+        ];
+        issues[1].priority = Some(1);
+        issues[2].priority = Some(2);
+        issues
+    }
+
+    #[test]
+    fn missing_from_str() {
+        assert_eq!("milestone".parse(), Ok(Missing::Milestone));
+        assert_eq!(
+            "labels".parse::<Missing>(),
+            Err("unknown triage field 'labels'".to_string())
+        );
+    }
+
+    #[test]
+    fn all_fields() {
+        let mut output = Vec::new();
+        write_triage(&mut output, &issues(), &[], None).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "# Open issues to triage
+
+| issue | title | missing |
+|---|---|---|
+| [repo#1](https://github.com/owner/repo/issues/1) | Issue 1 | priority, milestone, assignee |
+| [repo#3](https://github.com/owner/repo/issues/3) | Issue 3 | assignee |
+
+2 issues to triage
+"
+        );
+    }
+
+    #[test]
+    fn some_fields() {
+        let mut output = Vec::new();
+        write_triage(&mut output, &issues(), &[Missing::Milestone], None).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("| Issue 1 | milestone |\n"));
+        assert!(!output.contains("Issue 3"));
+        assert!(output.ends_with("1 issues to triage\n"));
+    }
+}
@@ -0,0 +1,234 @@
+use csv;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+
+use issue::{Issue, IssueStateJson};
+
+const UNASSIGNED: &str = "unassigned";
+
+#[derive(Debug, Default)]
+struct Workload<'a> {
+    total: usize,
+    per_priority: BTreeMap<Option<u32>, usize>,
+    oldest: Option<&'a Issue>,
+}
+
+impl<'a> Workload<'a> {
+    fn add(&mut self, issue: &'a Issue) {
+        self.total += 1;
+        *self.per_priority.entry(issue.get_priority()).or_insert(0) += 1;
+
+        if self
+            .oldest
+            .is_none_or(|o| issue.get_created_at() < o.get_created_at())
+        {
+            self.oldest = Some(issue);
+        }
+    }
+}
+
+// Open issues of each assignee, an issue counting for all its assignees. The busiest
+// assignees come first.
+fn workloads(issues: &[Issue]) -> Vec<(&str, Workload)> {
+    let mut result: BTreeMap<&str, Workload> = BTreeMap::new();
+
+    for issue in issues.iter().filter(|i| i.state == IssueStateJson::Open) {
+        let assignees = issue.get_assignees();
+
+        if assignees.is_empty() {
+            result.entry(UNASSIGNED).or_default().add(issue);
+        }
+        for assignee in assignees {
+            result.entry(assignee).or_default().add(issue);
+        }
+    }
+
+    let mut result: Vec<(&str, Workload)> = result.into_iter().collect();
+    result.sort_by(|a, b| b.1.total.cmp(&a.1.total));
+    result
+}
+
+// Priorities having at least an issue, issues without priority last
+fn priorities(workloads: &[(&str, Workload)]) -> Vec<Option<u32>> {
+    let set: BTreeSet<u32> = workloads
+        .iter()
+        .flat_map(|&(_, ref w)| w.per_priority.keys().filter_map(|p| *p))
+        .collect();
+    let mut priorities: Vec<Option<u32>> = set.into_iter().map(Some).collect();
+
+    if workloads
+        .iter()
+        .any(|&(_, ref w)| w.per_priority.contains_key(&None))
+    {
+        priorities.push(None);
+    }
+    priorities
+}
+
+fn priority_name(priority: Option<u32>) -> String {
+    match priority {
+        Some(p) => format!("P{}", p),
+        None => "no priority".to_string(),
+    }
+}
+
+fn write_markdown<W: Write>(
+    mut wtr: W,
+    workloads: &[(&str, Workload)],
+    priorities: &[Option<u32>],
+) -> io::Result<()> {
+    writeln!(wtr, "# Workload of open issues")?;
+    writeln!(wtr)?;
+
+    let names: Vec<String> = priorities.iter().map(|p| priority_name(*p)).collect();
+    writeln!(wtr, "| assignee | open | {} | oldest |", names.join(" | "))?;
+    writeln!(wtr, "|---|---|{}---|", "---|".repeat(priorities.len()))?;
+
+    for &(assignee, ref workload) in workloads.iter() {
+        let counts: Vec<String> = priorities
+            .iter()
+            .map(|p| {
+                workload
+                    .per_priority
+                    .get(p)
+                    .cloned()
+                    .unwrap_or(0)
+                    .to_string()
+            })
+            .collect();
+        let oldest = match workload.oldest {
+            Some(issue) => format!(
+                "[{}#{}]({}) ({})",
+                issue.get_component(),
+                issue.number,
+                issue.html_url,
+                issue.get_created_at()
+            ),
+            None => String::new(),
+        };
+
+        writeln!(
+            wtr,
+            "| {} | {} | {} | {} |",
+            assignee,
+            workload.total,
+            counts.join(" | "),
+            oldest
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_csv<W: Write>(
+    wtr: W,
+    workloads: &[(&str, Workload)],
+    priorities: &[Option<u32>],
+) -> io::Result<()> {
+    let mut wtr = csv::Writer::from_writer(wtr);
+
+    let mut header = vec!["assignee".to_string(), "open".to_string()];
+    header.extend(priorities.iter().map(|p| priority_name(*p)));
+    header.push("oldest_url".to_string());
+    header.push("oldest_created_at".to_string());
+    wtr.write_record(&header)?;
+
+    for &(assignee, ref workload) in workloads.iter() {
+        let mut record = vec![assignee.to_string(), workload.total.to_string()];
+        record.extend(priorities.iter().map(|p| {
+            workload
+                .per_priority
+                .get(p)
+                .cloned()
+                .unwrap_or(0)
+                .to_string()
+        }));
+        record.push(
+            workload
+                .oldest
+                .map(|i| i.html_url.clone())
+                .unwrap_or_default(),
+        );
+        record.push(
+            workload
+                .oldest
+                .map(|i| i.get_created_at().to_string())
+                .unwrap_or_default(),
+        );
+        wtr.write_record(&record)?;
+    }
+
+    wtr.flush()
+}
+
+pub fn write_workload<W: Write>(wtr: W, issues: &[Issue], csv: bool) -> io::Result<()> {
+    let workloads = workloads(issues);
+    let priorities = priorities(&workloads);
+
+    if csv {
+        write_csv(wtr, &workloads, &priorities)
+    } else {
+        write_markdown(wtr, &workloads, &priorities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use fixtures::issue;
+
+    fn issues() -> Vec<Issue> {
+        let mut issues = vec![
+            issue(
+                1,
+                serde_json::json!({
+                    "assignees": [{ "login": "alice" }, { "login": "bob" }],
+                    "created_at": "2024-01-03T00:00:00Z",
+                }),
+            ),
+            issue(2, serde_json::json!({ "assignee": { "login": "alice" } })),
+            issue(3, serde_json::Value::Null),
+            issue(
+                4,
+                serde_json::json!({ "state": "closed", "assignee": { "login": "carol" } }),
+            ),
+        ];
+        issues[0].priority = Some(1);
+        issues[1].priority = Some(2);
+        issues[3].priority = Some(1);
+        issues
+    }
+
+    #[test]
+    fn workload_csv() {
+        let mut output = Vec::new();
+        write_workload(&mut output, &issues(), true).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "assignee,open,P1,P2,no priority,oldest_url,oldest_created_at
+alice,2,1,1,0,https://github.com/owner/repo/issues/2,2024-01-01
+bob,1,1,0,0,https://github.com/owner/repo/issues/1,2024-01-03
+unassigned,1,0,0,1,https://github.com/owner/repo/issues/3,2024-01-01
+"
+        );
+    }
+
+    #[test]
+    fn workload_markdown() {
+        let mut output = Vec::new();
+        write_workload(&mut output, &issues(), false).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("| assignee | open | P1 | P2 | no priority | oldest |\n"));
+        assert!(output.contains("|---|---|---|---|---|---|\n"));
+        assert!(output.contains(
+            "| alice | 2 | 1 | 1 | 0 | [repo#2](https://github.com/owner/repo/issues/2) (2024-01-01) |\n"
+        ));
+        assert!(!output.contains("carol"));
+    }
+}
@@ -0,0 +1,150 @@
+use config::Config;
+use expr::{Context, Filter};
+use issue::{Issue, IssueCSV};
+
+// Send the issues matching a filter to their own output, the first matching route
+// winning. Other issues go to the default output.
+pub struct Router {
+    routes: Vec<(String, Filter)>,
+}
+
+impl Router {
+    pub fn from_config(config: &Config) -> Result<Router, String> {
+        let routes = config
+            .routes
+            .iter()
+            .map(|r| {
+                r.filter
+                    .parse::<Filter>()
+                    .map(|f| (r.name.clone(), f))
+                    .map_err(|e| format!("Invalid filter of route '{}': {}", r.name, e))
+            })
+            .collect::<Result<Vec<(String, Filter)>, String>>()?;
+
+        Ok(Router { routes })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    // Name of the route of this issue, none for the default output
+    pub fn route(&self, issue: &Issue, context: &Context) -> Option<&str> {
+        self.routes
+            .iter()
+            .find(|&&(_, ref filter)| filter.matches(issue, context))
+            .map(|&(ref name, _)| name.as_str())
+    }
+
+    // Split the rows, computed from 'issues' in the same order, between the routes.
+    // The default output is listed last.
+    pub fn split<'a>(
+        &self,
+        issues: &[Issue],
+        rows: &[IssueCSV<'a>],
+        context: &Context,
+    ) -> Vec<(Option<&str>, Vec<IssueCSV<'a>>)> {
+        let mut outputs: Vec<(Option<&str>, Vec<IssueCSV<'a>>)> = self
+            .routes
+            .iter()
+            .map(|&(ref name, _)| (Some(name.as_str()), Vec::new()))
+            .collect();
+        let default = self.routes.len();
+        outputs.push((None, Vec::new()));
+
+        for (issue, row) in issues.iter().zip(rows.iter()) {
+            let idx = self
+                .routes
+                .iter()
+                .position(|&(_, ref filter)| filter.matches(issue, context))
+                .unwrap_or(default);
+            outputs[idx].1.push(row.clone());
+        }
+
+        outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+    use toml;
+
+    use client::Github;
+    use fixtures::issue;
+    use output::Options;
+
+    fn router(routes: &str) -> Result<Router, String> {
+        let config: Config = toml::from_str(routes).unwrap();
+        Router::from_config(&config)
+    }
+
+    #[test]
+    fn first_matching_route() {
+        let router = router(
+            r#"
+[[routes]]
+name = "urgent"
+filter = "priority <= 1"
+
+[[routes]]
+name = "regressions"
+filter = "label == regression"
+"#,
+        )
+        .unwrap();
+        let mut issues = vec![
+            issue(
+                1,
+                serde_json::json!({ "labels": [{ "name": "regression" }] }),
+            ),
+            issue(
+                2,
+                serde_json::json!({ "labels": [{ "name": "regression" }] }),
+            ),
+            issue(3, serde_json::Value::Null),
+        ];
+        issues[0].priority = Some(0);
+        let context = Context::default();
+
+        assert!(!router.is_empty());
+        assert_eq!(router.route(&issues[0], &context), Some("urgent"));
+        assert_eq!(router.route(&issues[1], &context), Some("regressions"));
+        assert_eq!(router.route(&issues[2], &context), None);
+
+        let client = Github::new("token").unwrap();
+        let options = Options::default();
+        let rows: Vec<IssueCSV> = issues.iter().map(|i| i.csv(&client, &options)).collect();
+        let outputs: Vec<(Option<&str>, Vec<u32>)> = router
+            .split(&issues, &rows, &context)
+            .into_iter()
+            .map(|(name, rows)| (name, rows.iter().map(|r| r.number).collect()))
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                (Some("urgent"), vec![1]),
+                (Some("regressions"), vec![2]),
+                (None, vec![3]),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_route() {
+        assert!(router("").unwrap().is_empty());
+
+        let err = router(
+            r#"
+[[routes]]
+name = "bad"
+filter = "priority <="
+"#,
+        )
+        .err()
+        .unwrap();
+        assert!(err.starts_with("Invalid filter of route 'bad': "));
+    }
+}
@@ -0,0 +1,517 @@
+use hmac::{Hmac, Mac};
+use serde_json;
+use sha2::Sha256;
+
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use client::Github;
+use github::{get_all_issues, IssueQuery, PullRequestFilter};
+use issue::{Issue, IssueCSV};
+use output::{self, Format, Options};
+use transform::Pipeline;
+
+// Largest payload github sends to webhooks
+const MAX_PAYLOAD: usize = 25 * 1024 * 1024;
+
+// Connections answered at the same time, others being refused
+const MAX_CONNECTIONS: usize = 16;
+
+// Size of the request line and headers together
+const MAX_HEADER_BYTES: u64 = 16 * 1024;
+
+// Time given to a client to send its whole request and read the response
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Latest rendering of the issues in each served format
+#[derive(Debug, Default)]
+pub struct Pages {
+    html: Vec<u8>,
+    json: Vec<u8>,
+    csv: Vec<u8>,
+    metrics: Vec<u8>,
+}
+
+pub type SharedPages = Arc<Mutex<Pages>>;
+
+impl Pages {
+    pub fn render(rows: &[IssueCSV], options: &Options) -> io::Result<Pages> {
+        let mut pages = Pages::default();
+
+        output::write(Format::Html, &mut pages.html, rows, options)?;
+        output::write(Format::Json, &mut pages.json, rows, options)?;
+        output::write(Format::Csv, &mut pages.csv, rows, options)?;
+        output::write(Format::Prometheus, &mut pages.metrics, rows, options)?;
+        Ok(pages)
+    }
+}
+
+// Issue reported as changed by a webhook
+#[derive(Debug)]
+pub struct Update {
+    pub owner: String,
+    pub repo: String,
+    pub html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookOwner {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookRepository {
+    name: String,
+    owner: WebhookOwner,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookIssue {
+    html_url: String,
+}
+
+// Payload of the 'issues' events, only used to know which repository to fetch again
+#[derive(Debug, Deserialize)]
+struct Webhook {
+    issue: WebhookIssue,
+    repository: WebhookRepository,
+}
+
+impl Update {
+    // Only the issues of the exported components are followed
+    pub fn concerns(&self, owner: &str, components: &[String]) -> bool {
+        self.owner == owner && components.contains(&self.repo)
+    }
+}
+
+impl From<Webhook> for Update {
+    fn from(webhook: Webhook) -> Update {
+        Update {
+            owner: webhook.repository.owner.login,
+            repo: webhook.repository.name,
+            html_url: webhook.issue.html_url,
+        }
+    }
+}
+
+// The payloads are not trusted: the issues of the repositories they are about
+// are fetched again from the API, replacing the previous ones. Deleted and
+// transferred issues are dropped as the API does not return them anymore.
+pub fn refresh_repos(
+    client: &Github,
+    pipeline: &Pipeline,
+    query: &IssueQuery,
+    pull_requests: PullRequestFilter,
+    limit: Option<usize>,
+    issues: &mut Vec<Issue>,
+    repos: &BTreeSet<(String, String)>,
+) {
+    for &(ref owner, ref repo) in repos.iter() {
+        info!("Fetching the issues of {}/{} again", owner, repo);
+        let fetched = get_all_issues(
+            client,
+            owner,
+            &[repo.clone()],
+            limit,
+            pull_requests,
+            query,
+            1,
+            true,
+        );
+
+        issues.retain(|i| i.source.is_some() || (&i.get_owner(), &i.get_repo()) != (owner, repo));
+        issues.extend(
+            pipeline
+                .apply(fetched)
+                .into_iter()
+                .filter(|i| query.matches(i)),
+        );
+    }
+}
+
+// '8080' listens on localhost, other addresses must be complete: '0.0.0.0:8080'
+pub fn parse_address(s: &str) -> Result<SocketAddr, String> {
+    match s.parse::<u16>() {
+        Ok(port) => Ok(SocketAddr::from(([127, 0, 0, 1], port))),
+        Err(_) => s
+            .parse::<SocketAddr>()
+            .map_err(|_| format!("invalid address, expected a port or ip:port: '{}'", s)),
+    }
+}
+
+// 'signature' is the 'X-Hub-Signature-256' header: 'sha256=' followed by the
+// hexadecimal HMAC of the payload, keyed by the secret of the webhook
+fn is_signed(payload: &[u8], signature: Option<&str>, secret: &str) -> bool {
+    let digest = match signature.and_then(|s| s.strip_prefix("sha256=")) {
+        Some(digest) if digest.len() % 2 == 0 => digest,
+        _ => return false,
+    };
+    let digest: Option<Vec<u8>> = (0..digest.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(digest.get(i..i + 2)?, 16).ok())
+        .collect();
+    let digest = match digest {
+        Some(digest) => digest,
+        None => return false,
+    };
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("Failed to set up HMAC");
+    mac.update(payload);
+    // Compared in constant time
+    mac.verify_slice(&digest).is_ok()
+}
+
+// Headers of a webhook delivery
+#[derive(Debug, Default)]
+struct Delivery {
+    event: Option<String>,
+    signature: Option<String>,
+    length: usize,
+}
+
+// Request line and headers of a request, only keeping what is needed
+#[derive(Debug, Default)]
+struct Head {
+    method: Option<String>,
+    path: Option<String>,
+    delivery: Delivery,
+}
+
+// Stream failing to read once the deadline of the connection is passed, so slow
+// clients cannot hold a connection by sending a byte every few seconds
+struct Deadline {
+    stream: TcpStream,
+    end: Instant,
+}
+
+impl Read for Deadline {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.end.saturating_duration_since(Instant::now());
+        if left == Duration::ZERO {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "request timed out"));
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buf)
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+    )
+}
+
+// Read the request line and the headers, None if they are larger than
+// MAX_HEADER_BYTES. The body is left in the reader.
+fn read_head<R: BufRead>(reader: &mut R) -> io::Result<Option<Head>> {
+    let mut reader = reader.by_ref().take(MAX_HEADER_BYTES);
+    let mut head = Head::default();
+
+    // 'GET /issues.json HTTP/1.1'
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    if !request.ends_with('\n') && reader.limit() == 0 {
+        return Ok(None);
+    }
+    let mut parts = request.split_whitespace();
+    head.method = parts.next().map(str::to_string);
+    head.path = parts
+        .next()
+        .and_then(|p| p.split('?').next())
+        .map(str::to_string);
+
+    // Only the headers needed by webhooks are kept
+    loop {
+        let mut line = String::new();
+        let read = reader.read_line(&mut line)?;
+        if !line.ends_with('\n') && reader.limit() == 0 {
+            return Ok(None);
+        }
+        if read == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            match name.trim().to_lowercase().as_str() {
+                "x-github-event" => head.delivery.event = Some(value.to_string()),
+                "x-hub-signature-256" => head.delivery.signature = Some(value.to_string()),
+                "content-length" => head.delivery.length = value.parse().unwrap_or(0),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(Some(head))
+}
+
+// Read the body of a webhook and notify the main loop if it is about an issue,
+// returning the status and message of the response
+fn receive<R: BufRead>(
+    reader: R,
+    delivery: &Delivery,
+    secret: Option<&str>,
+    updates: &Sender<Update>,
+) -> io::Result<(&'static str, &'static str)> {
+    let secret = match secret {
+        Some(secret) => secret,
+        None => return Ok(("403 Forbidden", "Webhooks need a webhook-secret\n")),
+    };
+    if delivery.length > MAX_PAYLOAD {
+        return Ok(("413 Payload Too Large", "Payload too large\n"));
+    }
+    // Never read more than announced, however much the client sends
+    let mut payload = Vec::new();
+    reader
+        .take(delivery.length as u64)
+        .read_to_end(&mut payload)?;
+    if payload.len() < delivery.length {
+        return Ok(("400 Bad Request", "Truncated payload\n"));
+    }
+
+    if !is_signed(&payload, delivery.signature.as_deref(), secret) {
+        warn!("Rejected webhook with an invalid signature");
+        return Ok(("401 Unauthorized", "Invalid signature\n"));
+    }
+    let event = delivery.event.as_deref();
+
+    // 'ping' and other events are acknowledged but ignored
+    if event == Some("issues") {
+        match serde_json::from_slice::<Webhook>(&payload) {
+            Ok(webhook) => {
+                updates
+                    .send(Update::from(webhook))
+                    .expect("Failed to send update");
+            }
+            Err(e) => {
+                warn!("Invalid webhook payload: {}", e);
+                return Ok(("400 Bad Request", "Invalid payload\n"));
+            }
+        }
+    }
+
+    Ok(("200 OK", "OK\n"))
+}
+
+// Status, content type and body of the response to a request
+fn answer<R: BufRead>(
+    reader: &mut R,
+    pages: &SharedPages,
+    secret: Option<&str>,
+    updates: &Sender<Update>,
+) -> io::Result<(&'static str, &'static str, Vec<u8>)> {
+    let head = match read_head(reader)? {
+        Some(head) => head,
+        None => {
+            return Ok((
+                "431 Request Header Fields Too Large",
+                "text/plain",
+                b"Request headers too large\n".to_vec(),
+            ))
+        }
+    };
+    let method = head.method.as_deref();
+    let path = head.path.as_deref();
+
+    Ok(if (method, path) == (Some("POST"), Some("/webhook")) {
+        let (status, body) = receive(reader, &head.delivery, secret, updates)?;
+        (status, "text/plain", body.as_bytes().to_vec())
+    } else {
+        let pages = pages.lock().expect("Failed to lock pages");
+
+        match (method, path) {
+            (Some("GET"), Some("/")) | (Some("GET"), Some("/issues.html")) => {
+                ("200 OK", "text/html; charset=utf-8", pages.html.clone())
+            }
+            (Some("GET"), Some("/issues.json")) => {
+                ("200 OK", "application/json", pages.json.clone())
+            }
+            (Some("GET"), Some("/issues.csv")) => {
+                ("200 OK", "text/csv; charset=utf-8", pages.csv.clone())
+            }
+            (Some("GET"), Some("/metrics")) => {
+                ("200 OK", "text/plain; version=0.0.4", pages.metrics.clone())
+            }
+            (Some("GET"), _) => ("404 Not Found", "text/plain", b"Not found\n".to_vec()),
+            _ => (
+                "405 Method Not Allowed",
+                "text/plain",
+                b"Method not allowed\n".to_vec(),
+            ),
+        }
+    })
+}
+
+fn respond(
+    mut stream: TcpStream,
+    pages: &SharedPages,
+    secret: Option<&str>,
+    updates: &Sender<Update>,
+) -> io::Result<()> {
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut reader = BufReader::new(Deadline {
+        stream: stream.try_clone()?,
+        end: Instant::now() + REQUEST_TIMEOUT,
+    });
+
+    let (status, content_type, body) = match answer(&mut reader, pages, secret, updates) {
+        Ok(response) => response,
+        Err(ref e) if is_timeout(e) => (
+            "408 Request Timeout",
+            "text/plain",
+            b"Request timeout\n".to_vec(),
+        ),
+        Err(e) => return Err(e),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    stream.flush()
+}
+
+// Serve the pages from a background thread, while they are refreshed by the caller.
+// Webhooks signed with 'secret' are forwarded to 'updates', they are refused if
+// no secret is set.
+pub fn spawn(
+    address: SocketAddr,
+    pages: SharedPages,
+    secret: Option<String>,
+    updates: Sender<Update>,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    info!("Serving issues on http://{}/", address);
+    let connections = Arc::new(AtomicUsize::new(0));
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let pages = pages.clone();
+            let secret = secret.clone();
+            let updates = updates.clone();
+            let connections = connections.clone();
+
+            match stream {
+                Ok(mut stream) => {
+                    if connections.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                        let _ = stream.write_all(
+                            b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        );
+                        continue;
+                    }
+                    thread::spawn(move || {
+                        if let Err(e) = respond(stream, &pages, secret.as_deref(), &updates) {
+                            warn!("Failed to answer request: {}", e);
+                        }
+                        connections.fetch_sub(1, Ordering::SeqCst);
+                    });
+                }
+                Err(e) => warn!("Failed to accept connection: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    const SECRET: &str = "It's a Secret to Everybody";
+    const PAYLOAD: &[u8] = b"Hello, World!";
+    // Example of the github documentation
+    const SIGNATURE: &str =
+        "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+    #[test]
+    fn good_signature() {
+        assert!(is_signed(PAYLOAD, Some(SIGNATURE), SECRET));
+        // Hexadecimal digits are not case sensitive
+        assert!(is_signed(
+            PAYLOAD,
+            Some(&SIGNATURE.to_uppercase().replace("SHA256=", "sha256=")),
+            SECRET
+        ));
+    }
+
+    #[test]
+    fn bad_signatures() {
+        assert!(!is_signed(b"Hello, World?", Some(SIGNATURE), SECRET));
+        assert!(!is_signed(PAYLOAD, Some(SIGNATURE), "another secret"));
+        for signature in [
+            "sha256=857107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17",
+            // Truncated, odd length, not hexadecimal, other algorithm
+            "sha256=757107ea0eb2509fc211221cce984b8a",
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e1",
+            "sha256=zz7107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17",
+            "sha1=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17",
+            "757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17",
+            "sha256=",
+            "",
+        ] {
+            assert!(
+                !is_signed(PAYLOAD, Some(signature), SECRET),
+                "'{}' was accepted",
+                signature
+            );
+        }
+    }
+
+    #[test]
+    fn missing_signature() {
+        assert!(!is_signed(PAYLOAD, None, SECRET));
+    }
+
+    #[test]
+    fn head_parsing() {
+        let mut reader = Cursor::new(
+            "POST /webhook?x=1 HTTP/1.1\r\nHost: localhost\r\nX-GitHub-Event: issues\r\n\
+             X-Hub-Signature-256: sha256=00\r\nContent-Length: 13\r\n\r\nHello, World!",
+        );
+        let head = read_head(&mut reader).unwrap().unwrap();
+
+        assert_eq!(head.method.as_deref(), Some("POST"));
+        assert_eq!(head.path.as_deref(), Some("/webhook"));
+        assert_eq!(head.delivery.event.as_deref(), Some("issues"));
+        assert_eq!(head.delivery.signature.as_deref(), Some("sha256=00"));
+        assert_eq!(head.delivery.length, 13);
+
+        // The body is left to be read
+        let mut body = String::new();
+        reader.read_to_string(&mut body).unwrap();
+        assert_eq!(body, "Hello, World!");
+    }
+
+    #[test]
+    fn head_too_large() {
+        let long = "a".repeat(MAX_HEADER_BYTES as usize);
+        // Without any newline, and with many small headers
+        let requests = [
+            format!("GET /{}", long),
+            format!("GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n", long),
+            format!("GET / HTTP/1.1\r\n{}\r\n", "X: y\r\n".repeat(4096)),
+        ];
+        for request in requests.iter() {
+            assert!(read_head(&mut Cursor::new(request)).unwrap().is_none());
+        }
+
+        let head = read_head(&mut Cursor::new("GET / HTTP/1.1\r\n\r\n"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(head.path.as_deref(), Some("/"));
+    }
+}
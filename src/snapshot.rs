@@ -0,0 +1,172 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde_json;
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use dirs::long_path;
+use expr::{Context, Filter};
+use issue::Issue;
+use transform::Pipeline;
+
+// Colons are not allowed in Windows file names
+const NAME_FORMAT: &str = "%Y-%m-%dT%H-%M-%SZ";
+
+// Store the issues in a new JSON file of 'dir', named after the current time
+pub fn store(dir: &Path, issues: &[Issue]) -> io::Result<PathBuf> {
+    fs::create_dir_all(long_path(dir))?;
+
+    let name = format!("{}.json", Utc::now().format(NAME_FORMAT));
+    let path = dir.join(name);
+
+    let mut wtr = BufWriter::new(File::create(long_path(&path))?);
+    serde_json::to_writer(&mut wtr, issues)?;
+    wtr.flush()?;
+
+    Ok(path)
+}
+
+// Names of the snapshots stored in 'dir' and their issues, oldest first. The
+// transforms are applied again as computed fields are not stored.
+pub fn load_all(dir: &Path, pipeline: &Pipeline) -> io::Result<Vec<(String, Vec<Issue>)>> {
+    let mut paths = fs::read_dir(long_path(dir))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+    paths.retain(|p| p.extension().and_then(|e| e.to_str()) == Some("json"));
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let rdr = BufReader::new(File::open(long_path(path))?);
+            let issues: Vec<Issue> = serde_json::from_reader(rdr)?;
+            let name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string();
+
+            Ok((name, pipeline.apply(issues)))
+        })
+        .collect()
+}
+
+// Set the times of the first and last snapshots of 'dir' including each issue,
+// from the names of the snapshots. Issues never stored are left unset.
+pub fn set_seen(dir: &Path, issues: &mut [Issue]) -> io::Result<()> {
+    // Transforms are not needed to identify the issues
+    let pipeline = Pipeline::default();
+    let mut seen: HashMap<String, (DateTime<Utc>, DateTime<Utc>)> = HashMap::new();
+
+    for (name, snapshot) in load_all(dir, &pipeline)? {
+        let time = match NaiveDateTime::parse_from_str(&name, NAME_FORMAT) {
+            Ok(time) => time.and_utc(),
+            Err(_) => {
+                warn!("Ignoring snapshot {} not named after its time", name);
+                continue;
+            }
+        };
+
+        for issue in snapshot.iter() {
+            let entry = seen.entry(issue.get_reference()).or_insert((time, time));
+            entry.0 = entry.0.min(time);
+            entry.1 = entry.1.max(time);
+        }
+    }
+
+    for issue in issues.iter_mut() {
+        issue.seen = seen.get(&issue.get_reference()).cloned();
+    }
+    Ok(())
+}
+
+// Number of issues matching the filter in each snapshot
+pub fn write_history<W: Write>(
+    mut wtr: W,
+    dir: &Path,
+    pipeline: &Pipeline,
+    filter: &Filter,
+    context: &Context,
+) -> io::Result<()> {
+    writeln!(wtr, "| snapshot | issues |")?;
+    writeln!(wtr, "|---|---|")?;
+
+    for (name, issues) in load_all(dir, pipeline)? {
+        let count = issues.iter().filter(|i| filter.matches(i, context)).count();
+        writeln!(wtr, "| {} | {} |", name, count)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    use std::env;
+    use std::process;
+
+    use client::Github;
+    use column::Column;
+    use output::Options;
+
+    fn issue(number: u32) -> Issue {
+        serde_json::from_value(serde_json::json!({
+            "title": format!("Issue {}", number),
+            "html_url": format!("https://github.com/owner/repo/issues/{}", number),
+            "number": number,
+            "repository_url": "https://api.github.com/repos/owner/repo",
+            "labels": [],
+            "state": "open",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn first_and_last_seen() {
+        let dir = env::temp_dir().join(format!("github-issues-snapshots-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let snapshots: &[(&str, &[u32])] = &[
+            ("2024-03-01T10-00-00Z", &[1, 2]),
+            ("2024-03-02T10-00-00Z", &[1, 2, 3]),
+            // Issue 2 left the exported scope
+            ("2024-03-03T10-00-00Z", &[1, 3]),
+            ("not-a-time", &[4]),
+        ];
+        for &(name, numbers) in snapshots.iter() {
+            let issues: Vec<Issue> = numbers.iter().map(|&n| issue(n)).collect();
+            let file = File::create(dir.join(format!("{}.json", name))).unwrap();
+            serde_json::to_writer(file, &issues).unwrap();
+        }
+
+        let mut issues: Vec<Issue> = (1..=4).map(issue).collect();
+        let result = set_seen(&dir, &mut issues);
+        fs::remove_dir_all(&dir).unwrap();
+        result.unwrap();
+
+        let day = |d: u32| Utc.with_ymd_and_hms(2024, 3, d, 10, 0, 0).unwrap();
+        assert_eq!(issues[0].seen, Some((day(1), day(3))));
+        assert_eq!(issues[1].seen, Some((day(1), day(2))));
+        assert_eq!(issues[2].seen, Some((day(2), day(3))));
+        assert_eq!(issues[3].seen, None);
+
+        // Exported as columns
+        let options = Options {
+            columns: vec![Column::Id, Column::FirstSeen, Column::LastSeen],
+            ..Options::default()
+        };
+        let client = Github::new("token").unwrap();
+        let row = issues[1].csv(&client, &options);
+        assert_eq!(row.field(Column::FirstSeen), "2024-03-01");
+        assert_eq!(row.field(Column::LastSeen), "2024-03-02");
+        assert_eq!(
+            issues[3].csv(&client, &options).field(Column::FirstSeen),
+            ""
+        );
+    }
+}
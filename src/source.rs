@@ -0,0 +1,73 @@
+use serde::de::DeserializeOwned;
+use serde_json;
+use ureq;
+
+use client::Github;
+use github::{get_all_issues, IssueQuery, PullRequestFilter};
+use issue::Issue;
+
+// Issue tracker the issues are fetched from, producing issues in the format of
+// the github REST API so they can be transformed and exported the same way
+pub trait Source {
+    // The API may only do part of the filtering of the query, the issues being
+    // matched against it once transformed. If 'limit' is set, only keep the first
+    // 'limit' issues of each component.
+    fn fetch(
+        &self,
+        query: &IssueQuery,
+        limit: Option<usize>,
+        pull_requests: PullRequestFilter,
+        quiet: bool,
+    ) -> Vec<Issue>;
+}
+
+// Send a request to the REST API of another source, logging its errors
+pub(crate) fn get_json<T: DeserializeOwned>(request: ureq::Request) -> Option<T> {
+    let body = match request
+        .call()
+        .and_then(|r| r.into_string().map_err(ureq::Error::from))
+    {
+        Ok(body) => body,
+        Err(e) => {
+            error!("{}", e);
+            return None;
+        }
+    };
+
+    match serde_json::from_str(&body) {
+        Ok(json) => Some(json),
+        Err(e) => {
+            error!("{}", e);
+            None
+        }
+    }
+}
+
+pub struct GithubSource<'a> {
+    pub client: &'a Github,
+    pub owner: &'a str,
+    pub components: &'a [String],
+    // Maximum number of components fetched at once
+    pub jobs: usize,
+}
+
+impl<'a> Source for GithubSource<'a> {
+    fn fetch(
+        &self,
+        query: &IssueQuery,
+        limit: Option<usize>,
+        pull_requests: PullRequestFilter,
+        quiet: bool,
+    ) -> Vec<Issue> {
+        get_all_issues(
+            self.client,
+            self.owner,
+            self.components,
+            limit,
+            pull_requests,
+            query,
+            self.jobs,
+            quiet,
+        )
+    }
+}
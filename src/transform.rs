@@ -0,0 +1,613 @@
+use regex::Regex;
+
+use std::collections::{HashMap, HashSet};
+
+use config::Config;
+use issue::Issue;
+
+/// A processing step applied on the fetched issues before they are exported.
+pub trait Transform {
+    fn name(&self) -> &str;
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue>;
+}
+
+/// Remove emoji from issue titles.
+pub struct StripEmoji;
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0xFE0F | 0x200D
+    )
+}
+
+impl Transform for StripEmoji {
+    fn name(&self) -> &str {
+        "strip-emoji"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                let title: String = issue.title.chars().filter(|c| !is_emoji(*c)).collect();
+                issue.title = title.split_whitespace().collect::<Vec<&str>>().join(" ");
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Rename labels, so repositories using different conventions can be aggregated.
+pub struct MapLabels {
+    map: HashMap<String, String>,
+}
+
+impl MapLabels {
+    pub fn new(map: HashMap<String, String>) -> MapLabels {
+        MapLabels { map }
+    }
+}
+
+impl Transform for MapLabels {
+    fn name(&self) -> &str {
+        "map-labels"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                if let Some(labels) = issue.labels.as_mut() {
+                    for label in labels.iter_mut() {
+                        if let Some(name) = self.map.get(&label.name) {
+                            label.name = name.clone();
+                        }
+                    }
+                }
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Rename the variants of each label, such as 'prio:high' and 'Priority-High',
+/// to a single name.
+pub struct NormalizeLabels {
+    // Normalized variant -> label name
+    map: HashMap<String, String>,
+}
+
+impl NormalizeLabels {
+    // Variants are compared ignoring case, spaces and punctuation
+    fn normalize(name: &str) -> String {
+        name.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    }
+
+    // 'labels': label name -> its variants
+    pub fn new(labels: &HashMap<String, Vec<String>>) -> NormalizeLabels {
+        let mut map = HashMap::new();
+        for (name, variants) in labels.iter() {
+            for variant in variants.iter().chain(Some(name)) {
+                map.insert(NormalizeLabels::normalize(variant), name.clone());
+            }
+        }
+
+        NormalizeLabels { map }
+    }
+}
+
+impl Transform for NormalizeLabels {
+    fn name(&self) -> &str {
+        "normalize-labels"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                if let Some(labels) = issue.labels.as_mut() {
+                    for label in labels.iter_mut() {
+                        if let Some(name) = self.map.get(&NormalizeLabels::normalize(&label.name)) {
+                            label.name = name.clone();
+                        }
+                    }
+                    // Several variants of the same label may have been set
+                    let mut seen = HashSet::new();
+                    labels.retain(|l| seen.insert(l.name.clone()));
+                }
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Set the component of issues from the first of their labels starting with a
+/// prefix, such as 'area/audio' -> 'audio'.
+pub struct ComponentFromLabel {
+    prefix: String,
+}
+
+impl ComponentFromLabel {
+    pub fn new(prefix: &str) -> ComponentFromLabel {
+        ComponentFromLabel {
+            prefix: prefix.to_string(),
+        }
+    }
+}
+
+impl Transform for ComponentFromLabel {
+    fn name(&self) -> &str {
+        "component-label"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                let component = match issue.labels {
+                    Some(ref labels) => labels
+                        .iter()
+                        .find_map(|l| l.name.strip_prefix(self.prefix.as_str()))
+                        .filter(|c| !c.is_empty())
+                        .map(|c| c.to_string()),
+                    None => None,
+                };
+                // Issues without such label keep their repository as component
+                if component.is_some() {
+                    issue.component = component;
+                }
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Rename or merge components, such as repositories, into logical components.
+pub struct MapComponents {
+    map: HashMap<String, String>,
+}
+
+impl MapComponents {
+    // 'map': component name -> new name
+    pub fn new(map: HashMap<String, String>) -> MapComponents {
+        MapComponents { map }
+    }
+}
+
+impl Transform for MapComponents {
+    fn name(&self) -> &str {
+        "components"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                if let Some(component) = self.map.get(&issue.get_component()) {
+                    issue.component = Some(component.clone());
+                }
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Compute a score for each issue by summing the weights of its labels.
+pub struct ComputeScore {
+    weights: HashMap<String, f64>,
+}
+
+impl ComputeScore {
+    pub fn new(weights: HashMap<String, f64>) -> ComputeScore {
+        ComputeScore { weights }
+    }
+}
+
+impl Transform for ComputeScore {
+    fn name(&self) -> &str {
+        "compute-score"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                let score = match issue.labels {
+                    Some(ref labels) => labels
+                        .iter()
+                        .filter_map(|l| self.weights.get(&l.name))
+                        .sum::<f64>(),
+                    None => 0.0,
+                };
+                issue.score = Some(score);
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Hide parts of issue titles matching any of the given patterns.
+pub struct Redact {
+    patterns: Vec<Regex>,
+}
+
+impl Redact {
+    pub fn new(patterns: &[String]) -> Result<Redact, String> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| format!("Invalid redact pattern '{}': {}", p, e)))
+            .collect::<Result<Vec<Regex>, String>>()?;
+
+        Ok(Redact { patterns })
+    }
+}
+
+impl Transform for Redact {
+    fn name(&self) -> &str {
+        "redact"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                for pattern in self.patterns.iter() {
+                    issue.title = pattern.replace_all(&issue.title, "[redacted]").into_owned();
+                }
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Flag issues whose title or description mention any of the given keywords.
+pub struct UrgentKeywords {
+    keywords: Vec<String>,
+}
+
+impl UrgentKeywords {
+    pub fn new(keywords: &[String]) -> UrgentKeywords {
+        UrgentKeywords {
+            keywords: keywords.iter().map(|k| k.to_lowercase()).collect(),
+        }
+    }
+}
+
+impl Transform for UrgentKeywords {
+    fn name(&self) -> &str {
+        "urgent-keywords"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                let text = format!("{}\n{}", issue.title, issue.body.as_deref().unwrap_or(""))
+                    .to_lowercase();
+
+                issue.urgent_keywords = self
+                    .keywords
+                    .iter()
+                    .filter(|k| text.contains(k.as_str()))
+                    .cloned()
+                    .collect();
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Set the priority of issues from the first of their labels defining one.
+pub struct Priorities {
+    labels: HashMap<String, u32>,
+    pattern: Option<Regex>,
+}
+
+impl Priorities {
+    // 'labels' maps label names to priorities, 'pattern' captures the priority from
+    // the label name in its first group. Defaults to the 'P0' to 'P5' labels.
+    pub fn new(labels: HashMap<String, u32>, pattern: Option<&str>) -> Result<Priorities, String> {
+        let pattern = match pattern {
+            Some(p) => Some(
+                Regex::new(p).map_err(|e| format!("Invalid priority pattern '{}': {}", p, e))?,
+            ),
+            None => None,
+        };
+
+        let labels = if labels.is_empty() && pattern.is_none() {
+            (0..6).map(|p| (format!("P{}", p), p)).collect()
+        } else {
+            labels
+        };
+
+        Ok(Priorities { labels, pattern })
+    }
+
+    fn priority(&self, label: &str) -> Option<u32> {
+        if let Some(priority) = self.labels.get(label) {
+            return Some(*priority);
+        }
+
+        self.pattern
+            .as_ref()
+            .and_then(|re| re.captures(label))
+            .and_then(|c| c.get(1))
+            .and_then(|m| m.as_str().parse().ok())
+    }
+}
+
+impl Transform for Priorities {
+    fn name(&self) -> &str {
+        "priorities"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                let found = match issue.labels {
+                    Some(ref labels) => labels
+                        .iter()
+                        .find_map(|l| self.priority(&l.name).map(|p| (l.name.clone(), p))),
+                    None => None,
+                };
+                issue.priority = found.map(|(label, priority)| {
+                    issue.consumed_labels.push(label);
+                    priority
+                });
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Set the severity of issues from the first of their labels defining one.
+pub struct Severities {
+    labels: HashMap<String, String>,
+}
+
+impl Severities {
+    pub fn new(labels: HashMap<String, String>) -> Severities {
+        Severities { labels }
+    }
+}
+
+impl Transform for Severities {
+    fn name(&self) -> &str {
+        "severities"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                let found = match issue.labels {
+                    Some(ref labels) => labels.iter().find_map(|l| {
+                        self.labels
+                            .get(&l.name)
+                            .map(|s| (l.name.clone(), s.clone()))
+                    }),
+                    None => None,
+                };
+                issue.severity = found.map(|(label, severity)| {
+                    issue.consumed_labels.push(label);
+                    severity
+                });
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Set the estimate of issues from the first of their labels defining one.
+pub struct Estimates {
+    labels: HashMap<String, f64>,
+}
+
+impl Estimates {
+    pub fn new(labels: HashMap<String, f64>) -> Estimates {
+        Estimates { labels }
+    }
+}
+
+impl Transform for Estimates {
+    fn name(&self) -> &str {
+        "estimates"
+    }
+
+    fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        issues
+            .into_iter()
+            .map(|mut issue| {
+                let found = match issue.labels {
+                    Some(ref labels) => labels
+                        .iter()
+                        .find_map(|l| self.labels.get(&l.name).map(|e| (l.name.clone(), *e))),
+                    None => None,
+                };
+                issue.estimate = found.map(|(label, estimate)| {
+                    issue.consumed_labels.push(label);
+                    estimate
+                });
+                issue
+            })
+            .collect()
+    }
+}
+
+/// Ordered list of transforms applied between fetching and exporting issues.
+#[derive(Default)]
+pub struct Pipeline {
+    transforms: Vec<Box<dyn Transform>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    /// Build the pipeline of built-in transforms listed in the configuration.
+    /// Labels are normalized first, and priorities, severities and estimates are
+    /// always set last, so they use the transformed labels.
+    pub fn from_config(config: &Config) -> Result<Pipeline, String> {
+        let mut pipeline = Pipeline::new();
+
+        if !config.normalize_labels.is_empty() {
+            pipeline.add(NormalizeLabels::new(&config.normalize_labels));
+        }
+        if let Some(ref prefix) = config.component_label {
+            pipeline.add(ComponentFromLabel::new(prefix));
+        }
+        if !config.components.is_empty() {
+            pipeline.add(MapComponents::new(config.components.clone()));
+        }
+
+        for name in config.transforms.iter() {
+            match name.as_str() {
+                "strip-emoji" => pipeline.add(StripEmoji),
+                "map-labels" => pipeline.add(MapLabels::new(config.map_labels.clone())),
+                "compute-score" => pipeline.add(ComputeScore::new(config.compute_score.clone())),
+                "redact" => pipeline.add(Redact::new(&config.redact)?),
+                "urgent-keywords" => pipeline.add(UrgentKeywords::new(&config.urgent_keywords)),
+                _ => return Err(format!("Unknown transform '{}'", name)),
+            }
+        }
+
+        pipeline.add(Priorities::new(
+            config.priorities.clone(),
+            config.priority_pattern.as_deref(),
+        )?);
+        pipeline.add(Severities::new(config.severities.clone()));
+        pipeline.add(Estimates::new(config.estimates.clone()));
+
+        Ok(pipeline)
+    }
+
+    pub fn add<T: Transform + 'static>(&mut self, transform: T) {
+        self.transforms.push(Box::new(transform));
+    }
+
+    pub fn apply(&self, issues: Vec<Issue>) -> Vec<Issue> {
+        self.transforms
+            .iter()
+            .fold(issues, |issues, transform| transform.apply(issues))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+    use toml;
+
+    fn issue(title: &str, labels: &[&str]) -> Issue {
+        let labels: Vec<serde_json::Value> = labels
+            .iter()
+            .map(|l| serde_json::json!({ "name": l }))
+            .collect();
+
+        serde_json::from_value(serde_json::json!({
+            "title": title,
+            "html_url": "https://github.com/owner/repo/issues/1",
+            "number": 1,
+            "repository_url": "https://api.github.com/repos/owner/repo",
+            "labels": labels,
+            "state": "open",
+            "body": "Fails with a SEGFAULT on startup",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    fn labels(issue: &Issue) -> Vec<&str> {
+        issue
+            .labels
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|l| l.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn pipeline_from_config() {
+        let config: Config = toml::from_str(
+            r#"
+transforms = ["strip-emoji", "compute-score", "redact", "urgent-keywords"]
+component-label = "area/"
+redact = ["secret-[a-z]+"]
+urgent-keywords = ["segfault", "data loss"]
+
+[normalize-labels]
+"P1" = ["prio:high", "Priority-High"]
+
+[components]
+audio = "media"
+
+[compute-score]
+"bug" = 2.0
+"P1" = 3.0
+"#,
+        )
+        .unwrap();
+        let pipeline = Pipeline::from_config(&config).unwrap();
+
+        let issues = pipeline.apply(vec![
+            issue(
+                "🔥 Crash  in secret-project",
+                &["area/audio", "bug", "prio:high", "P1"],
+            ),
+            issue("Typo", &["Priority-High"]),
+        ]);
+
+        // Variants are normalized before the priorities are set
+        assert_eq!(issues[0].title, "Crash in [redacted]");
+        assert_eq!(labels(&issues[0]), vec!["area/audio", "bug", "P1"]);
+        assert_eq!(issues[0].get_component(), "media");
+        assert_eq!(issues[0].score, Some(5.0));
+        assert_eq!(issues[0].urgent_keywords, vec!["segfault".to_string()]);
+        assert_eq!(issues[0].priority, Some(1));
+        assert_eq!(issues[0].consumed_labels, vec!["P1".to_string()]);
+
+        assert_eq!(issues[1].get_component(), "repo");
+        assert_eq!(issues[1].priority, Some(1));
+        assert_eq!(issues[1].score, Some(3.0));
+    }
+
+    #[test]
+    fn unknown_transform() {
+        let config: Config = toml::from_str(r#"transforms = ["upper-case"]"#).unwrap();
+
+        assert_eq!(
+            Pipeline::from_config(&config).err(),
+            Some("Unknown transform 'upper-case'".to_string())
+        );
+    }
+
+    #[test]
+    fn priorities() {
+        let defaults = Priorities::new(HashMap::new(), None).unwrap();
+        let pattern = Priorities::new(HashMap::new(), Some("^priority/([0-9]+)$")).unwrap();
+
+        let issues = defaults.apply(vec![issue("Crash", &["bug", "P2", "P0"])]);
+        assert_eq!(issues[0].priority, Some(2));
+
+        let issues = pattern.apply(vec![
+            issue("Crash", &["priority/3"]),
+            issue("Typo", &["P0", "priority/high"]),
+        ]);
+        assert_eq!(issues[0].priority, Some(3));
+        assert_eq!(issues[0].consumed_labels, vec!["priority/3".to_string()]);
+        // The default labels are replaced by the pattern
+        assert_eq!(issues[1].priority, None);
+        assert!(issues[1].consumed_labels.is_empty());
+
+        assert!(Priorities::new(HashMap::new(), Some("(")).is_err());
+    }
+}
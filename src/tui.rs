@@ -0,0 +1,476 @@
+use open;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::layout::Rect;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::{Frame, Terminal};
+
+use std::cmp::Reverse;
+use std::io;
+
+use issue::{Issue, IssueCSV};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StateFilter {
+    All,
+    Open,
+    Closed,
+}
+
+impl StateFilter {
+    fn next(self) -> StateFilter {
+        match self {
+            StateFilter::All => StateFilter::Open,
+            StateFilter::Open => StateFilter::Closed,
+            StateFilter::Closed => StateFilter::All,
+        }
+    }
+
+    fn keeps(self, row: &IssueCSV) -> bool {
+        match self {
+            StateFilter::All => true,
+            StateFilter::Open => row.state != "closed",
+            StateFilter::Closed => row.state == "closed",
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            StateFilter::All => "all",
+            StateFilter::Open => "open",
+            StateFilter::Closed => "closed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortKey {
+    // Order of the export
+    Default,
+    Priority,
+    Created,
+    // Most recently updated first
+    Updated,
+    Title,
+}
+
+impl SortKey {
+    fn next(self) -> SortKey {
+        match self {
+            SortKey::Default => SortKey::Priority,
+            SortKey::Priority => SortKey::Created,
+            SortKey::Created => SortKey::Updated,
+            SortKey::Updated => SortKey::Title,
+            SortKey::Title => SortKey::Default,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            SortKey::Default => "default",
+            SortKey::Priority => "priority",
+            SortKey::Created => "created",
+            SortKey::Updated => "updated",
+            SortKey::Title => "title",
+        }
+    }
+}
+
+// Characters of 'pattern' must appear in 'text' in the same order, ignoring case
+fn fuzzy_matches(pattern: &str, text: &str) -> bool {
+    let mut chars = text.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|p| chars.any(|c| c == p))
+}
+
+// State, priority and assignees of an issue in a snapshot
+type Values = [String; 3];
+
+fn values(issue: &Issue) -> Values {
+    [
+        issue.get_state_str(),
+        issue
+            .get_priority()
+            .map(|p| format!("P{}", p))
+            .unwrap_or_default(),
+        issue.get_assignees().join(", "),
+    ]
+}
+
+// How an issue changed across the snapshots
+struct Timeline {
+    reference: String,
+    // Name of each snapshot, oldest first, and the issue's values if it was in it
+    entries: Vec<(String, Option<Values>)>,
+    table: TableState,
+}
+
+impl Timeline {
+    fn new(reference: String, snapshots: &[(String, Vec<Issue>)]) -> Timeline {
+        let entries: Vec<(String, Option<Values>)> = snapshots
+            .iter()
+            .map(|(name, issues)| {
+                let issue = issues.iter().find(|i| i.get_reference() == reference);
+                (name.clone(), issue.map(values))
+            })
+            .collect();
+
+        // Start from the latest snapshot
+        let mut table = TableState::default();
+        table.select(entries.len().checked_sub(1));
+
+        Timeline {
+            reference,
+            entries,
+            table,
+        }
+    }
+
+    fn step(&mut self, offset: isize) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        let last = self.entries.len() as isize - 1;
+        self.table
+            .select(Some((current + offset).clamp(0, last) as usize));
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let changed = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        let entries = &self.entries;
+
+        let rows = entries.iter().enumerate().map(|(idx, (name, values))| {
+            let previous = idx.checked_sub(1).and_then(|p| entries[p].1.as_ref());
+            let mut cells = vec![Cell::from(name.clone())];
+            match values {
+                Some(values) => cells.extend(values.iter().enumerate().map(|(field, value)| {
+                    let cell = Cell::from(value.clone());
+                    // Highlight what changed since the previous snapshot
+                    if previous.is_some_and(|p| p[field] != *value) {
+                        cell.style(changed)
+                    } else {
+                        cell
+                    }
+                })),
+                None => cells.push(Cell::from("not exported")),
+            }
+            Row::new(cells)
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(24),
+                Constraint::Length(12),
+                Constraint::Length(4),
+                Constraint::Min(10),
+            ],
+        )
+        .header(
+            Row::new(vec!["snapshot", "state", "prio", "assignee"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            " {} in {} snapshots ",
+            self.reference,
+            self.entries.len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+}
+
+struct App<'r, 'a> {
+    rows: &'r [IssueCSV<'a>],
+    // Snapshots of --snapshot-dir, oldest first
+    snapshots: &'r [(String, Vec<Issue>)],
+    // Shown instead of the list when set
+    timeline: Option<Timeline>,
+    // Indexes in 'rows' of the listed issues
+    visible: Vec<usize>,
+    search: String,
+    searching: bool,
+    state: StateFilter,
+    sort: SortKey,
+    table: TableState,
+}
+
+impl<'r, 'a> App<'r, 'a> {
+    fn new(rows: &'r [IssueCSV<'a>], snapshots: &'r [(String, Vec<Issue>)]) -> App<'r, 'a> {
+        let mut app = App {
+            rows,
+            snapshots,
+            timeline: None,
+            visible: Vec::new(),
+            search: String::new(),
+            searching: false,
+            state: StateFilter::All,
+            sort: SortKey::Default,
+            table: TableState::default(),
+        };
+        app.refresh();
+        app
+    }
+
+    // Compute the listed issues again after a change of the search, filter or sort
+    fn refresh(&mut self) {
+        let rows = self.rows;
+        let mut visible: Vec<usize> = (0..rows.len())
+            .filter(|&i| self.state.keeps(&rows[i]))
+            .filter(|&i| {
+                let row = &rows[i];
+                let text = format!("{}#{} {}", row.component, row.number, row.title);
+                fuzzy_matches(&self.search, &text)
+            })
+            .collect();
+
+        match self.sort {
+            SortKey::Default => {}
+            // Issues without priority last
+            SortKey::Priority => visible.sort_by_key(|&i| rows[i].priority.unwrap_or(u32::MAX)),
+            SortKey::Created => visible.sort_by_key(|&i| rows[i].dates.created),
+            SortKey::Updated => visible.sort_by_key(|&i| Reverse(rows[i].dates.updated)),
+            SortKey::Title => visible.sort_by_key(|&i| rows[i].title.to_lowercase()),
+        }
+
+        self.visible = visible;
+        self.table.select(if self.visible.is_empty() {
+            None
+        } else {
+            Some(0)
+        });
+    }
+
+    fn selected(&self) -> Option<&IssueCSV<'a>> {
+        self.table
+            .selected()
+            .and_then(|s| self.visible.get(s))
+            .map(|&i| &self.rows[i])
+    }
+
+    fn select(&mut self, offset: isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        let last = self.visible.len() as isize - 1;
+        self.table
+            .select(Some((current + offset).clamp(0, last) as usize));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [list, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+        if let Some(ref mut timeline) = self.timeline {
+            timeline.draw(frame, list);
+            frame.render_widget(
+                Paragraph::new("up/down step through the snapshots, esc back to the issues"),
+                status,
+            );
+            return;
+        }
+
+        let rows = self.visible.iter().map(|&i| {
+            let row = &self.rows[i];
+            Row::new(vec![
+                format!("{}#{}", row.component, row.number),
+                row.priority.map(|p| format!("P{}", p)).unwrap_or_default(),
+                row.state.clone(),
+                row.assignee.clone().unwrap_or_default(),
+                row.title.to_string(),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(24),
+                Constraint::Length(4),
+                Constraint::Length(12),
+                Constraint::Length(16),
+                Constraint::Min(10),
+            ],
+        )
+        .header(
+            Row::new(vec!["issue", "prio", "state", "assignee", "title"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            " {} of {} issues ",
+            self.visible.len(),
+            self.rows.len()
+        )))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, list, &mut self.table);
+
+        let line = if self.searching {
+            format!("/{}", self.search)
+        } else {
+            format!(
+                "search: '{}' | state: {} | sort: {} | / search, f filter, s sort, t timeline, enter open, q quit",
+                self.search,
+                self.state.as_str(),
+                self.sort.as_str()
+            )
+        };
+        frame.render_widget(Paragraph::new(line), status);
+    }
+
+    // Return false to quit
+    fn handle(&mut self, code: KeyCode) -> bool {
+        if self.searching {
+            match code {
+                KeyCode::Enter | KeyCode::Esc => self.searching = false,
+                KeyCode::Backspace => {
+                    self.search.pop();
+                    self.refresh();
+                }
+                KeyCode::Char(c) => {
+                    self.search.push(c);
+                    self.refresh();
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        if let Some(ref mut timeline) = self.timeline {
+            match code {
+                KeyCode::Down | KeyCode::Char('j') => timeline.step(1),
+                KeyCode::Up | KeyCode::Char('k') => timeline.step(-1),
+                KeyCode::Esc | KeyCode::Char('t') | KeyCode::Char('q') => self.timeline = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('/') => self.searching = true,
+            KeyCode::Char('f') => {
+                self.state = self.state.next();
+                self.refresh();
+            }
+            KeyCode::Char('s') => {
+                self.sort = self.sort.next();
+                self.refresh();
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.select(1),
+            KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+            KeyCode::PageDown => self.select(20),
+            KeyCode::PageUp => self.select(-20),
+            KeyCode::Char('t') => {
+                if let Some(reference) = self.selected().map(|r| r.reference.clone()) {
+                    self.timeline = Some(Timeline::new(reference, self.snapshots));
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('o') => {
+                if let Some(row) = self.selected() {
+                    // Failing to start a browser should not quit
+                    let _ = open::that(&row.url);
+                }
+            }
+            _ => {}
+        }
+
+        true
+    }
+}
+
+// Browse the issues in the terminal until the user quits, the snapshots
+// giving the timeline of each issue
+pub fn browse(rows: &[IssueCSV], snapshots: &[(String, Vec<Issue>)]) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+
+    let mut app = App::new(rows, snapshots);
+    let result = loop {
+        if let Err(e) = terminal.draw(|frame| app.draw(frame)) {
+            break Err(e);
+        }
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                if !app.handle(key.code) {
+                    break Ok(());
+                }
+            }
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+
+    // Always restore the terminal, even on errors
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json;
+
+    use client::Github;
+    use fixtures::issue;
+    use output::Options;
+
+    #[test]
+    fn fuzzy_search() {
+        assert!(fuzzy_matches("crsh", "repo#1 Crash on start"));
+        assert!(fuzzy_matches("R#1", "repo#1 Crash"));
+        assert!(fuzzy_matches("", "repo#1 Crash"));
+        // Characters out of order
+        assert!(!fuzzy_matches("hsarc", "repo#1 Crash"));
+    }
+
+    #[test]
+    fn filters_and_sorts() {
+        let mut issues = vec![
+            issue(1, serde_json::json!({ "title": "Crash" })),
+            issue(2, serde_json::json!({ "title": "Typo", "state": "closed" })),
+            issue(3, serde_json::json!({ "title": "Leak" })),
+        ];
+        issues[0].priority = Some(2);
+        issues[2].priority = Some(0);
+        let client = Github::new("token").unwrap();
+        let rows: Vec<IssueCSV> = issues
+            .iter()
+            .map(|i| i.csv(&client, &Options::default()))
+            .collect();
+        let snapshots = Vec::new();
+        let mut app = App::new(&rows, &snapshots);
+        assert_eq!(app.visible, vec![0, 1, 2]);
+
+        app.handle(KeyCode::Char('f'));
+        assert_eq!(app.state, StateFilter::Open);
+        assert_eq!(app.visible, vec![0, 2]);
+
+        app.handle(KeyCode::Char('s'));
+        assert_eq!(app.sort, SortKey::Priority);
+        assert_eq!(app.visible, vec![2, 0]);
+        app.handle(KeyCode::Down);
+        assert_eq!(app.selected().map(|r| r.number), Some(1));
+
+        // Keys are part of the search while searching
+        app.handle(KeyCode::Char('/'));
+        for c in "cr".chars() {
+            app.handle(KeyCode::Char(c));
+        }
+        app.handle(KeyCode::Enter);
+        assert_eq!(app.visible, vec![0]);
+        assert_eq!(app.state, StateFilter::Open);
+        assert!(!app.handle(KeyCode::Char('q')));
+    }
+}
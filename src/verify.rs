@@ -0,0 +1,134 @@
+use rusqlite;
+use url::Url;
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use client::{Github, StatusCode};
+use issue::Issue;
+use output::sqlite;
+
+// What happened to a previously exported issue
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrlStatus {
+    Ok,
+    // Transferred to another repository
+    Moved(String),
+    Deleted,
+}
+
+impl UrlStatus {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            UrlStatus::Ok => "ok",
+            UrlStatus::Moved(_) => "moved",
+            UrlStatus::Deleted => "deleted",
+        }
+    }
+}
+
+// 'https://github.com/owner/repo/issues/42' -> ('owner', 'repo', 42)
+fn parse_issue_url(url: &str) -> Option<(String, String, u32)> {
+    let url = Url::parse(url).ok()?;
+    let segments: Vec<&str> = url.path_segments()?.collect();
+
+    match segments.as_slice() {
+        [owner, repo, _, number] => {
+            Some((owner.to_string(), repo.to_string(), number.parse().ok()?))
+        }
+        _ => None,
+    }
+}
+
+// None if the status could not be checked
+pub fn check_url(client: &Github, url: &str) -> Option<UrlStatus> {
+    let (owner, repo, number) = parse_issue_url(url)?;
+
+    // The API follows transfers of issues to their new repository
+    let endpoint = format!("repos/{}/{}/issues/{}", owner, repo, number);
+    match client.get::<Issue>(&endpoint) {
+        Ok((_headers, status, _))
+            if status == StatusCode::NOT_FOUND || status == StatusCode::GONE =>
+        {
+            Some(UrlStatus::Deleted)
+        }
+        Ok((_headers, _status, Some(issue))) => {
+            if issue.html_url == url {
+                Some(UrlStatus::Ok)
+            } else {
+                Some(UrlStatus::Moved(issue.html_url))
+            }
+        }
+        Ok(_) => None,
+        Err(e) => {
+            error!("{}", e);
+            None
+        }
+    }
+}
+
+// Check the URLs of the issues stored in the database, or only 'sample' of them,
+// record their status and list the stale ones
+pub fn verify<W: Write>(
+    mut wtr: W,
+    client: &Github,
+    db: &Path,
+    sample: Option<usize>,
+) -> io::Result<()> {
+    let to_io = |e: rusqlite::Error| io::Error::new(io::ErrorKind::Other, e.to_string());
+
+    let urls = sqlite::urls_to_verify(db, sample).map_err(to_io)?;
+    let mut statuses = Vec::new();
+
+    for url in urls.into_iter() {
+        match check_url(client, &url) {
+            Some(UrlStatus::Moved(new_url)) => {
+                writeln!(wtr, "moved: {} -> {}", url, new_url)?;
+                statuses.push((url, "moved", Some(new_url)));
+            }
+            Some(status) => {
+                if status == UrlStatus::Deleted {
+                    writeln!(wtr, "deleted: {}", url)?;
+                }
+                statuses.push((url, status.as_str(), None));
+            }
+            None => warn!("Failed to verify {}", url),
+        }
+    }
+
+    sqlite::set_url_status(db, &statuses).map_err(to_io)?;
+    writeln!(wtr, "{} URLs verified", statuses.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_urls() {
+        assert_eq!(
+            parse_issue_url("https://github.com/owner/repo/issues/42"),
+            Some(("owner".to_string(), "repo".to_string(), 42))
+        );
+        assert_eq!(
+            parse_issue_url("https://github.com/owner/repo/pull/7"),
+            Some(("owner".to_string(), "repo".to_string(), 7))
+        );
+        assert_eq!(parse_issue_url("https://github.com/owner/repo"), None);
+        assert_eq!(
+            parse_issue_url("https://github.com/owner/repo/issues/new"),
+            None
+        );
+        assert_eq!(parse_issue_url("owner/repo#42"), None);
+    }
+
+    #[test]
+    fn url_statuses() {
+        assert_eq!(UrlStatus::Ok.as_str(), "ok");
+        assert_eq!(
+            UrlStatus::Moved("https://github.com/owner/lib/issues/1".to_string()).as_str(),
+            "moved"
+        );
+        assert_eq!(UrlStatus::Deleted.as_str(), "deleted");
+    }
+}